@@ -0,0 +1,30 @@
+use cranelift_module::Module;
+
+use crate::{
+    errors::codegen::CodegenError,
+    frontend::parsing::ast::bodies::{BodyElementKind, BodyElementNode, BodyKind, BodyNode},
+};
+
+use super::{functions::FunctionTranslator, Scope};
+
+impl<'a, M: Module> FunctionTranslator<'a, '_, M> {
+    pub(super) fn lower_body(&mut self, body: &'a BodyNode) -> Result<(), CodegenError> {
+        self.scopes.push(Scope::new());
+
+        let result = match &body.kind {
+            BodyKind::Inline(el) => self.lower_body_element(el),
+            BodyKind::Multiline(els) => els.iter().try_for_each(|el| self.lower_body_element(el)),
+        };
+
+        self.scopes.pop();
+        return result;
+    }
+
+    fn lower_body_element(&mut self, el: &'a BodyElementNode) -> Result<(), CodegenError> {
+        match &el.kind {
+            BodyElementKind::Statement(stmt) => self.lower_statement(stmt),
+            BodyElementKind::Body(body) => self.lower_body(body),
+            BodyElementKind::Empty => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,493 @@
+use cranelift::prelude::{
+    types, AbiParam, FloatCC, InstBuilder, IntCC, MemFlags, Signature, TrapCode, Type, Value,
+};
+use cranelift_module::{FuncId, Linkage, Module};
+
+use crate::{
+    errors::codegen::{CodegenError, CodegenErrorKind},
+    frontend::{
+        modules::{is_builtin, std_path},
+        parsing::ast::expressions::ExpressionNode,
+        primitives::{FloatType, IntType},
+        resolving::qualify,
+        Loc,
+    },
+};
+
+use super::{
+    error_ctx, functions::FunctionTranslator, items::mangle, module_error, types::ValueType,
+    Codegen, Scope,
+};
+
+const PRINTF_VARIADIC_ARGS: usize = 4;
+const FORMAT_BUFFER_SIZE: i64 = 64;
+const RUNTIME_ERROR_CODE: i64 = 101;
+
+impl<'a, M: Module> Codegen<'a, M> {
+    pub(super) fn declare_formatters(&mut self) {
+        for idx in 0..self.structs.len() {
+            // instances declare their own when they're created
+            if self.structs[idx].is_template() || self.structs[idx].template.is_some() {
+                continue;
+            }
+
+            let name = qualify(&self.structs[idx].name, "<to_string>");
+            match self.declare_formatter(&name) {
+                Ok(id) => self.structs[idx].formatter = id,
+                Err(err) => self.errors.push(err),
+            }
+        }
+
+        for idx in 0..self.enums.len() {
+            let name = qualify(&self.enums[idx].name, "<to_string>");
+            match self.declare_formatter(&name) {
+                Ok(id) => self.enums[idx].formatter = id,
+                Err(err) => self.errors.push(err),
+            }
+        }
+    }
+
+    fn declare_formatter(&mut self, name: &str) -> Result<FuncId, CodegenError> {
+        let signature = formatter_signature(&self.module);
+        self.module
+            .declare_function(&mangle(name), Linkage::Local, &signature)
+            .map_err(module_error)
+    }
+
+    pub(super) fn define_formatters(&mut self) {
+        for idx in 0..self.structs.len() {
+            if self.structs[idx].is_template() {
+                continue;
+            }
+
+            self.current = self.structs[idx].namespace;
+            let (id, loc) = (self.structs[idx].formatter, &self.structs[idx].node.loc);
+
+            let signature = formatter_signature(&self.module);
+            let result = self.define_with(id, signature, ValueType::String, Scope::new(), |t| {
+                let value = t.builder.block_params(t.builder.current_block().unwrap())[0];
+                let text = t.struct_to_string(value, idx, loc)?;
+                t.builder.ins().return_(&[text]);
+                Ok(())
+            });
+            self.push_error(result);
+        }
+
+        for idx in 0..self.enums.len() {
+            self.current = self.enums[idx].namespace;
+            let (id, loc) = (self.enums[idx].formatter, &self.enums[idx].node.loc);
+
+            let signature = formatter_signature(&self.module);
+            let result = self.define_with(id, signature, ValueType::String, Scope::new(), |t| {
+                let value = t.builder.block_params(t.builder.current_block().unwrap())[0];
+                let text = t.enum_to_string(value, idx, loc)?;
+                t.builder.ins().return_(&[text]);
+                Ok(())
+            });
+            self.push_error(result);
+        }
+    }
+}
+
+impl<'a, M: Module> FunctionTranslator<'a, '_, M> {
+    pub(super) fn lower_builtin(
+        &mut self,
+        path: &str,
+        args: &'a [ExpressionNode],
+        loc: &Loc,
+    ) -> Result<Option<(Value, ValueType)>, CodegenError> {
+        let Some(builtin) = self
+            .namespace
+            .candidates(path)
+            .into_iter()
+            .find(|candidate| is_builtin(candidate))
+        else {
+            return Ok(None);
+        };
+
+        if std_path(&builtin) == "Dict::new" {
+            return self.lower_dict_new(args, loc).map(Some);
+        }
+
+        let newline = std_path(&builtin) == "console::push_line";
+
+        if args.len() > 1 || (!newline && args.is_empty()) {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(format!("`{path}` takes a single argument"))
+                .ctx(error_ctx(self.src, loc)));
+        }
+
+        let end = if newline { "\n" } else { "" };
+
+        let Some(arg) = args.first() else {
+            self.printf(end, &[])?;
+            return Ok(Some((self.zero_value(ValueType::Void)?, ValueType::Void)));
+        };
+
+        let (value, r#type) = self.lower_expression(arg)?;
+        match r#type {
+            ValueType::Int(_) | ValueType::Float(_) => {
+                let (format, args) = self.number_format(value, r#type)?;
+                self.printf(&format!("{format}{end}"), &args)?;
+            }
+            ValueType::String => self.printf(&format!("%s{end}"), &[value])?,
+            ValueType::Struct(_)
+            | ValueType::Enum(_)
+            | ValueType::Array(_)
+            | ValueType::Tuple(_)
+            | ValueType::Dict(_) => {
+                let text = self.aggregate_to_string(value, r#type);
+                self.printf(&format!("%s{end}"), &[text])?;
+            }
+            ValueType::Char => {
+                let text = self.char_to_string(value)?;
+                self.printf(&format!("%s{end}"), &[text])?;
+            }
+            ValueType::Bool => {
+                let true_str = self.string_value("true")?;
+                let false_str = self.string_value("false")?;
+                let text = self.builder.ins().select(value, true_str, false_str);
+                self.printf(&format!("%s{end}"), &[text])?;
+            }
+            ValueType::Void => {
+                return Err(CodegenError::default()
+                    .kind(CodegenErrorKind::MismatchedTypes)
+                    .msg("cannot print a value of type void")
+                    .ctx(error_ctx(self.src, &arg.loc)));
+            }
+        }
+
+        Ok(Some((self.zero_value(ValueType::Void)?, ValueType::Void)))
+    }
+
+    pub(super) fn lower_to_string(
+        &mut self,
+        value: Value,
+        r#type: ValueType,
+        loc: &Loc,
+    ) -> Result<Value, CodegenError> {
+        match r#type {
+            ValueType::Int(_) | ValueType::Float(_) => {
+                let (format, args) = self.number_format(value, r#type)?;
+                self.sprintf(format, &args)
+            }
+            ValueType::String => Ok(value),
+            ValueType::Struct(_)
+            | ValueType::Enum(_)
+            | ValueType::Array(_)
+            | ValueType::Tuple(_)
+            | ValueType::Dict(_) => Ok(self.aggregate_to_string(value, r#type)),
+            ValueType::Char => self.char_to_string(value),
+            ValueType::Bool => {
+                let true_str = self.string_value("true")?;
+                let false_str = self.string_value("false")?;
+                Ok(self.builder.ins().select(value, true_str, false_str))
+            }
+            ValueType::Void => Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg("cannot format a value of type void")
+                .ctx(error_ctx(self.src, loc))),
+        }
+    }
+
+    // printf only takes 64-bit integers and doubles, so narrower numbers are
+    // widened first
+    pub(super) fn number_format(
+        &mut self,
+        value: Value,
+        r#type: ValueType,
+    ) -> Result<(&'static str, Vec<Value>), CodegenError> {
+        match r#type {
+            ValueType::Int(int) if int.is_signed() => {
+                let value = self.convert_int(value, int, IntType::Int64);
+                Ok(("%lld", vec![value]))
+            }
+            ValueType::Int(int) => {
+                let value = self.convert_int(value, int, IntType::UInt64);
+                Ok(("%llu", vec![value]))
+            }
+            ValueType::Float(float) => {
+                let value = match float {
+                    FloatType::Float32 => self.builder.ins().fpromote(types::F64, value),
+                    FloatType::Float64 => value,
+                };
+
+                Ok(("%s%lld.%0*lld", self.float_parts(value)?.to_vec()))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    // structs, enums and collections are formatted by functions of their own,
+    // since they can hold values of the same type
+    fn aggregate_to_string(&mut self, value: Value, r#type: ValueType) -> Value {
+        let id = match r#type {
+            ValueType::Struct(idx) => self.structs[idx].formatter,
+            ValueType::Enum(idx) => self.enums[idx].formatter,
+            ValueType::Array(idx) | ValueType::Tuple(idx) | ValueType::Dict(idx) => {
+                self.collections[idx].formatter
+            }
+            _ => unreachable!(),
+        };
+
+        let func_ref = self.func_ref(id);
+        let call = self.builder.ins().call(func_ref, &[value]);
+        self.builder.inst_results(call)[0]
+    }
+
+    // encodes the code point as utf-8 into a zeroed word, which leaves the
+    // string null-terminated on little-endian targets
+    fn char_to_string(&mut self, value: Value) -> Result<Value, CodegenError> {
+        let code = self.builder.ins().uextend(types::I64, value);
+
+        let one = code;
+        let two = self.utf8_sequence(code, &[(6, 0x1F, 0xC0), (0, 0x3F, 0x80)]);
+        let three = self.utf8_sequence(code, &[(12, 0x0F, 0xE0), (6, 0x3F, 0x80), (0, 0x3F, 0x80)]);
+        let four = self.utf8_sequence(
+            code,
+            &[
+                (18, 0x07, 0xF0),
+                (12, 0x3F, 0x80),
+                (6, 0x3F, 0x80),
+                (0, 0x3F, 0x80),
+            ],
+        );
+
+        let below_four = self
+            .builder
+            .ins()
+            .icmp_imm(IntCC::UnsignedLessThan, code, 0x10000);
+        let word = self.builder.ins().select(below_four, three, four);
+        let below_three = self
+            .builder
+            .ins()
+            .icmp_imm(IntCC::UnsignedLessThan, code, 0x800);
+        let word = self.builder.ins().select(below_three, two, word);
+        let below_two = self
+            .builder
+            .ins()
+            .icmp_imm(IntCC::UnsignedLessThan, code, 0x80);
+        let word = self.builder.ins().select(below_two, one, word);
+
+        let ptr = self.ptr_type();
+        let size = self.builder.ins().iconst(ptr, 8);
+        let buffer = self.call_import("malloc", &[size])?;
+        self.builder
+            .ins()
+            .store(MemFlags::trusted(), word, buffer, 0);
+
+        Ok(buffer)
+    }
+
+    fn utf8_sequence(&mut self, code: Value, bytes: &[(i64, i64, i64)]) -> Value {
+        let mut word = self.builder.ins().iconst(types::I64, 0);
+
+        for (i, (shift, mask, tag)) in bytes.iter().enumerate() {
+            let byte = self.builder.ins().ushr_imm(code, *shift);
+            let byte = self.builder.ins().band_imm(byte, *mask);
+            let byte = self.builder.ins().bor_imm(byte, *tag);
+            let byte = self.builder.ins().ishl_imm(byte, i as i64 * 8);
+            word = self.builder.ins().bor(word, byte);
+        }
+
+        word
+    }
+
+    fn float_parts(&mut self, value: Value) -> Result<[Value; 4], CodegenError> {
+        let zero = self.builder.ins().f64const(0.0);
+        let negative = self.builder.ins().fcmp(FloatCC::LessThan, value, zero);
+
+        let abs = self.builder.ins().fabs(value);
+        let int_part = self.builder.ins().floor(abs);
+        let frac_part = self.builder.ins().fsub(abs, int_part);
+
+        let scale = self.builder.ins().f64const(1_000_000.0);
+        let scaled = self.builder.ins().fmul(frac_part, scale);
+        let scaled = self.builder.ins().nearest(scaled);
+
+        let int = self.builder.ins().fcvt_to_sint_sat(types::I64, int_part);
+        let frac = self.builder.ins().fcvt_to_sint_sat(types::I64, scaled);
+
+        let carry = self.builder.ins().icmp_imm(IntCC::Equal, frac, 1_000_000);
+        let int_carried = self.builder.ins().iadd_imm(int, 1);
+        let int = self.builder.ins().select(carry, int_carried, int);
+        let zero = self.builder.ins().iconst(types::I64, 0);
+        let frac = self.builder.ins().select(carry, zero, frac);
+
+        let header_block = self.builder.create_block();
+        let body_block = self.builder.create_block();
+        let exit_block = self.builder.create_block();
+        for block in [header_block, exit_block] {
+            self.builder.append_block_param(block, types::I64);
+            self.builder.append_block_param(block, types::I64);
+        }
+
+        let digits = self.builder.ins().iconst(types::I64, 6);
+        self.builder.ins().jump(header_block, &[frac, digits]);
+
+        self.builder.switch_to_block(header_block);
+        let params = self.builder.block_params(header_block).to_vec();
+        let (frac, digits) = (params[0], params[1]);
+        let rem = self.builder.ins().srem_imm(frac, 10);
+        let trailing_zero = self.builder.ins().icmp_imm(IntCC::Equal, rem, 0);
+        let more_digits = self
+            .builder
+            .ins()
+            .icmp_imm(IntCC::SignedGreaterThan, digits, 1);
+        let trim = self.builder.ins().band(trailing_zero, more_digits);
+        self.builder
+            .ins()
+            .brif(trim, body_block, &[], exit_block, &[frac, digits]);
+
+        self.builder.switch_to_block(body_block);
+        let trimmed_frac = self.builder.ins().sdiv_imm(frac, 10);
+        let trimmed_digits = self.builder.ins().iadd_imm(digits, -1);
+        self.builder
+            .ins()
+            .jump(header_block, &[trimmed_frac, trimmed_digits]);
+
+        self.builder.switch_to_block(exit_block);
+        let params = self.builder.block_params(exit_block).to_vec();
+
+        let minus = self.string_value("-")?;
+        let empty = self.string_value("")?;
+        let sign = self.builder.ins().select(negative, minus, empty);
+
+        Ok([sign, int, params[1], params[0]])
+    }
+
+    fn printf(&mut self, format: &str, args: &[Value]) -> Result<(), CodegenError> {
+        let mut values = vec![self.string_value(format)?];
+        values.extend_from_slice(args);
+
+        while values.len() <= PRINTF_VARIADIC_ARGS {
+            values.push(self.builder.ins().iconst(types::I64, 0));
+        }
+
+        self.call_import("printf", &values)?;
+        Ok(())
+    }
+
+    fn sprintf(&mut self, format: &str, args: &[Value]) -> Result<Value, CodegenError> {
+        let ptr = self.ptr_type();
+        let size = self.builder.ins().iconst(ptr, FORMAT_BUFFER_SIZE);
+        let buffer = self.call_import("malloc", &[size])?;
+
+        let mut values = vec![buffer, size, self.string_value(format)?];
+        values.extend_from_slice(args);
+
+        while values.len() < 3 + PRINTF_VARIADIC_ARGS {
+            values.push(self.builder.ins().iconst(types::I64, 0));
+        }
+
+        self.call_import("snprintf", &values)?;
+        Ok(buffer)
+    }
+
+    // stops the program with a message pointing at `loc` unless `cond` holds,
+    // the message is only built once it's needed
+    pub(super) fn lower_check<F>(
+        &mut self,
+        cond: Value,
+        loc: &Loc,
+        message: F,
+    ) -> Result<(), CodegenError>
+    where
+        F: FnOnce(&mut Self) -> Result<(String, Vec<Value>), CodegenError>,
+    {
+        let fail_block = self.builder.create_block();
+        let ok_block = self.builder.create_block();
+        self.builder
+            .ins()
+            .brif(cond, ok_block, &[], fail_block, &[]);
+
+        self.builder.switch_to_block(fail_block);
+        let (message, args) = message(self)?;
+        let path = self.src.path.display().to_string().replace('%', "%%");
+        let format = format!("{path}:({}, {}): {message}\n", loc.ln, loc.start_col);
+        self.printf(&format, &args)?;
+        self.flush()?;
+
+        let code = self.builder.ins().iconst(types::I32, RUNTIME_ERROR_CODE);
+        self.call_import("exit", &[code])?;
+        self.builder.ins().trap(TrapCode::UnreachableCodeReached);
+
+        self.builder.switch_to_block(ok_block);
+        Ok(())
+    }
+
+    pub(super) fn flush(&mut self) -> Result<(), CodegenError> {
+        let ptr = self.ptr_type();
+        let null = self.builder.ins().iconst(ptr, 0);
+        self.call_import("fflush", &[null])?;
+
+        Ok(())
+    }
+
+    pub(super) fn call_import(
+        &mut self,
+        name: &'static str,
+        args: &[Value],
+    ) -> Result<Value, CodegenError> {
+        let id = if let Some(id) = self.imports.get(name) {
+            *id
+        } else {
+            let (params, returns) = import_signature(name, self.ptr_type());
+
+            let mut signature = self.module.make_signature();
+            signature
+                .params
+                .extend(params.into_iter().map(AbiParam::new));
+            signature.returns.push(AbiParam::new(returns));
+
+            let id = self
+                .module
+                .declare_function(name, Linkage::Import, &signature)
+                .map_err(module_error)?;
+            self.imports.insert(name, id);
+            id
+        };
+
+        let func_ref = self.func_ref(id);
+        let call = self.builder.ins().call(func_ref, args);
+
+        Ok(self.builder.inst_results(call)[0])
+    }
+}
+
+// builtins live in `std`, but can also be used without importing them
+pub(super) fn formatter_signature<M: Module>(module: &M) -> Signature {
+    let ptr = module.target_config().pointer_type();
+
+    let mut signature = module.make_signature();
+    signature.params.push(AbiParam::new(ptr));
+    signature.returns.push(AbiParam::new(ptr));
+
+    return signature;
+}
+
+fn import_signature(name: &str, ptr: Type) -> (Vec<Type>, Type) {
+    match name {
+        "printf" => {
+            let mut params = vec![ptr];
+            params.extend([types::I64; PRINTF_VARIADIC_ARGS]);
+            (params, types::I32)
+        }
+        "snprintf" => {
+            let mut params = vec![ptr, ptr, ptr];
+            params.extend([types::I64; PRINTF_VARIADIC_ARGS]);
+            (params, types::I32)
+        }
+        "fflush" => (vec![ptr], types::I32),
+        "malloc" => (vec![ptr], ptr),
+        "realloc" => (vec![ptr, ptr], ptr),
+        // never returns, the result is only there to fit the other imports
+        "exit" => (vec![types::I32], types::I32),
+        "strlen" => (vec![ptr], ptr),
+        "strcmp" => (vec![ptr, ptr], types::I32),
+        "memcpy" => (vec![ptr, ptr, ptr], ptr),
+        "fmod" | "pow" => (vec![types::F64, types::F64], types::F64),
+        _ => unreachable!(),
+    }
+}
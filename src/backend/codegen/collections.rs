@@ -0,0 +1,779 @@
+use cranelift::prelude::{types, InstBuilder, IntCC, MemFlags, Value};
+use cranelift_module::{FuncId, Linkage, Module};
+
+use crate::{
+    errors::codegen::{CodegenError, CodegenErrorKind},
+    frontend::{
+        lexing::{AssignmentKind, ComplexPunctuationKind, LiteralKind},
+        parsing::ast::{
+            expressions::{ExpressionKind, ExpressionNode},
+            types::TypeNode,
+        },
+        resolving::qualify,
+        Loc,
+    },
+    utils::text::count_mismatch,
+};
+
+use super::{
+    builtins::formatter_signature,
+    error_ctx,
+    functions::FunctionTranslator,
+    generics::{Bound, GenericParam},
+    items::mangle,
+    module_error,
+    structs::{FieldSymbol, SLOT_SIZE},
+    types::{TypeContext, ValueType},
+    Codegen, Scope,
+};
+
+// arrays are a slot with their length followed by the elements, dictionaries
+// are a slot with their length and one with the entries, which are pairs of
+// slots that grow with every new key
+const LENGTH_SLOT: usize = 0;
+const ENTRIES_SLOT: usize = 1;
+const ENTRY_SIZE: i64 = 2 * SLOT_SIZE;
+
+// collections have no declarations, they exist once for every combination of
+// element types that's written or built somewhere
+pub struct CollectionType {
+    pub kind: CollectionKind,
+    pub name: String,
+    pub formatter: FuncId,
+    pub namespace: usize,
+    pub loc: Loc,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum CollectionKind {
+    Array(ValueType),
+    Tuple(Vec<ValueType>),
+    Dict(ValueType, ValueType),
+}
+
+impl CollectionKind {
+    fn value_type(&self, idx: usize) -> ValueType {
+        match self {
+            CollectionKind::Array(_) => ValueType::Array(idx),
+            CollectionKind::Tuple(_) => ValueType::Tuple(idx),
+            CollectionKind::Dict(..) => ValueType::Dict(idx),
+        }
+    }
+}
+
+impl<'a, M: Module> Codegen<'a, M> {
+    pub(super) fn define_collection_formatters(&mut self) {
+        for idx in 0..self.collections.len() {
+            self.current = self.collections[idx].namespace;
+            let (id, loc) = (
+                self.collections[idx].formatter,
+                self.collections[idx].loc.clone(),
+            );
+
+            let signature = formatter_signature(&self.module);
+            let result = self.define_with(id, signature, ValueType::String, Scope::new(), |t| {
+                let value = t.builder.block_params(t.builder.current_block().unwrap())[0];
+                let text = t.collection_to_string(value, idx, &loc)?;
+                t.builder.ins().return_(&[text]);
+                Ok(())
+            });
+            self.push_error(result);
+        }
+    }
+}
+
+impl<M: Module> TypeContext<'_, '_, M> {
+    pub fn collection(
+        &mut self,
+        kind: CollectionKind,
+        namespace: usize,
+        loc: &Loc,
+    ) -> Result<ValueType, CodegenError> {
+        if let Some(idx) = self
+            .collections
+            .iter()
+            .position(|collection| collection.kind == kind)
+        {
+            return Ok(kind.value_type(idx));
+        }
+
+        let name = match &kind {
+            CollectionKind::Array(element) => format!("[{}]", self.type_name(*element)),
+            CollectionKind::Tuple(elements) if elements.len() == 1 => {
+                format!("({},)", self.type_name(elements[0]))
+            }
+            CollectionKind::Tuple(elements) => format!("({})", self.type_names(elements)),
+            CollectionKind::Dict(key, value) => {
+                format!("Dict<{}, {}>", self.type_name(*key), self.type_name(*value))
+            }
+        };
+
+        let signature = formatter_signature(self.module);
+        let formatter = self
+            .module
+            .declare_function(
+                &mangle(&qualify(&name, "<to_string>")),
+                Linkage::Local,
+                &signature,
+            )
+            .map_err(module_error)?;
+
+        let idx = self.collections.len();
+        self.collections.push(CollectionType {
+            kind: kind.clone(),
+            name,
+            formatter,
+            namespace,
+            loc: loc.clone(),
+        });
+
+        Ok(kind.value_type(idx))
+    }
+
+    pub fn lower_element_type(
+        &mut self,
+        namespace: usize,
+        params: &[(String, ValueType)],
+        node: &TypeNode,
+    ) -> Result<ValueType, CodegenError> {
+        let r#type = self.lower_type(namespace, params, node)?;
+        if r#type == ValueType::Void {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg("collections cannot hold values of type void")
+                .ctx(error_ctx(self.src(namespace), &node.loc)));
+        }
+
+        Ok(r#type)
+    }
+
+    // `Dict` is the only builtin type so far
+    pub fn lower_builtin_type(
+        &mut self,
+        namespace: usize,
+        params: &[(String, ValueType)],
+        generics: &[TypeNode],
+        loc: &Loc,
+    ) -> Result<ValueType, CodegenError> {
+        if generics.len() != 2 {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(count_mismatch("Dict", 2, "type argument", generics.len()))
+                .ctx(error_ctx(self.src(namespace), loc)));
+        }
+
+        let key = self.lower_element_type(namespace, params, &generics[0])?;
+        let value = self.lower_element_type(namespace, params, &generics[1])?;
+
+        self.dict_type(key, value, namespace, loc)
+    }
+
+    // keys are compared with `==`, so they have to support it
+    pub fn dict_type(
+        &mut self,
+        key: ValueType,
+        value: ValueType,
+        namespace: usize,
+        loc: &Loc,
+    ) -> Result<ValueType, CodegenError> {
+        let generics = [
+            GenericParam {
+                name: "K".to_owned(),
+                bounds: vec![Bound::Eq],
+            },
+            GenericParam {
+                name: "V".to_owned(),
+                bounds: Vec::new(),
+            },
+        ];
+
+        let src = self.src(namespace);
+        self.check_bounds(&generics, &[key, value], "Dict", loc, src)?;
+
+        self.collection(CollectionKind::Dict(key, value), namespace, loc)
+    }
+}
+
+impl<'a, M: Module> FunctionTranslator<'a, '_, M> {
+    pub(super) fn lower_array(
+        &mut self,
+        elements: &'a [ExpressionNode],
+        expected: Option<ValueType>,
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let expected = match expected {
+            Some(ValueType::Array(idx)) => match self.collections[idx].kind {
+                CollectionKind::Array(element) => Some(element),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        // without an expected type, every element is expected to have the
+        // type of the first one
+        let values = self.lower_elements(elements, |_, first| expected.or(first))?;
+
+        let first = values.first().map(|(_, r#type)| *r#type);
+        let Some(element_type) = expected.or(first) else {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg("cannot infer the element type of an empty array")
+                .ctx(error_ctx(self.src, loc)));
+        };
+
+        for ((_, r#type), element) in values.iter().zip(elements.iter()) {
+            self.expect_type(element_type, *r#type, &element.loc)?;
+        }
+
+        let r#type = self.collection(CollectionKind::Array(element_type), loc)?;
+
+        let ptr = self.allocate(values.len() + 1)?;
+        let len = self.builder.ins().iconst(types::I64, values.len() as i64);
+        self.store_slot(ptr, LENGTH_SLOT, len);
+
+        for (i, (value, _)) in values.into_iter().enumerate() {
+            self.store_slot(ptr, i + 1, value);
+        }
+
+        Ok((ptr, r#type))
+    }
+
+    pub(super) fn lower_tuple(
+        &mut self,
+        elements: &'a [ExpressionNode],
+        expected: Option<ValueType>,
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let expected = match expected {
+            Some(ValueType::Tuple(idx)) => match &self.collections[idx].kind {
+                CollectionKind::Tuple(types) => types.clone(),
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+
+        let values = self.lower_elements(elements, |i, _| expected.get(i).copied())?;
+
+        let types = values.iter().map(|(_, r#type)| *r#type).collect();
+        let r#type = self.collection(CollectionKind::Tuple(types), loc)?;
+
+        let ptr = self.allocate(values.len())?;
+        for (i, (value, _)) in values.into_iter().enumerate() {
+            self.store_slot(ptr, i, value);
+        }
+
+        Ok((ptr, r#type))
+    }
+
+    // `expected` gives the type an element is expected to have by its index
+    // and the type of the first element, once that one is lowered
+    fn lower_elements(
+        &mut self,
+        elements: &'a [ExpressionNode],
+        expected: impl Fn(usize, Option<ValueType>) -> Option<ValueType>,
+    ) -> Result<Vec<(Value, ValueType)>, CodegenError> {
+        let mut values: Vec<(Value, ValueType)> = Vec::new();
+
+        for (i, element) in elements.iter().enumerate() {
+            let first = values.first().map(|(_, r#type)| *r#type);
+            let (value, r#type) = match expected(i, first) {
+                Some(expected) => self.lower_expression_as(element, expected)?,
+                None => self.lower_expression(element)?,
+            };
+            if r#type == ValueType::Void {
+                return Err(CodegenError::default()
+                    .kind(CodegenErrorKind::MismatchedTypes)
+                    .msg("collections cannot hold values of type void")
+                    .ctx(error_ctx(self.src, &element.loc)));
+            }
+
+            values.push((value, r#type));
+        }
+
+        Ok(values)
+    }
+
+    pub(super) fn lower_index(
+        &mut self,
+        object: &'a ExpressionNode,
+        index: &'a ExpressionNode,
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let (ptr, slot, r#type) = self.index_slot(object, index, loc)?;
+        Ok((self.load_slot(ptr, slot, r#type), r#type))
+    }
+
+    // assigning to a missing key of a dictionary adds it, every other index has
+    // to exist already
+    pub(super) fn lower_index_assignment(
+        &mut self,
+        object: &'a ExpressionNode,
+        index: &'a ExpressionNode,
+        op: &AssignmentKind,
+        value: &'a ExpressionNode,
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        if op.operator().is_some() {
+            let (ptr, slot, target_type) = self.index_slot(object, index, loc)?;
+            return self.assign_slot(ptr, slot, target_type, op, value, loc);
+        }
+
+        let (dict, dict_type) = self.lower_expression(object)?;
+        let ValueType::Dict(idx) = dict_type else {
+            let (ptr, slot, target_type) = self.indexed_slot(dict, dict_type, index, loc)?;
+            return self.assign_slot(ptr, slot, target_type, op, value, loc);
+        };
+
+        let CollectionKind::Dict(key_type, value_type) = self.collections[idx].kind else {
+            unreachable!()
+        };
+
+        let (key, r#type) = self.lower_expression_as(index, key_type)?;
+        self.expect_type(key_type, r#type, &index.loc)?;
+
+        let (new_value, r#type) = self.lower_expression_as(value, value_type)?;
+        self.expect_type(value_type, r#type, &value.loc)?;
+
+        self.dict_insert(dict, key, new_value, key_type, loc)?;
+        Ok((new_value, value_type))
+    }
+
+    fn index_slot(
+        &mut self,
+        object: &'a ExpressionNode,
+        index: &'a ExpressionNode,
+        loc: &Loc,
+    ) -> Result<(Value, usize, ValueType), CodegenError> {
+        let (ptr, r#type) = self.lower_expression(object)?;
+        self.indexed_slot(ptr, r#type, index, loc)
+    }
+
+    // the address of an element and its slot relative to it, which is only
+    // known when the program runs for arrays and dictionaries
+    fn indexed_slot(
+        &mut self,
+        ptr: Value,
+        r#type: ValueType,
+        index: &'a ExpressionNode,
+        loc: &Loc,
+    ) -> Result<(Value, usize, ValueType), CodegenError> {
+        let idx = match r#type {
+            ValueType::Array(idx) | ValueType::Tuple(idx) | ValueType::Dict(idx) => idx,
+            // the type checker reports the rest, except for values whose type
+            // it can't tell, like `self` in provided trait methods
+            _ => {
+                return Err(CodegenError::default()
+                    .kind(CodegenErrorKind::MismatchedTypes)
+                    .msg(format!("`{}` cannot be indexed", self.type_name(r#type)))
+                    .ctx(error_ctx(self.src, loc)));
+            }
+        };
+
+        match self.collections[idx].kind.clone() {
+            CollectionKind::Array(element_type) => {
+                let (value, r#type) = self.lower_expression(index)?;
+                self.expect_type(ValueType::INT, r#type, &index.loc)?;
+
+                let len = self.load_slot(ptr, LENGTH_SLOT, ValueType::INT);
+                let in_bounds = self.builder.ins().icmp(IntCC::UnsignedLessThan, value, len);
+                self.lower_check(in_bounds, loc, |_| {
+                    Ok((
+                        "index out of bounds: the length is %lld but the index is %lld".to_owned(),
+                        vec![len, value],
+                    ))
+                })?;
+
+                let element = self.element_address(ptr, value, SLOT_SIZE);
+                Ok((element, 1, element_type))
+            }
+            CollectionKind::Tuple(elements) => {
+                let ExpressionKind::Literal {
+                    kind: LiteralKind::Integer,
+                    value,
+                    ..
+                } = &index.kind
+                else {
+                    return Err(CodegenError::default()
+                        .kind(CodegenErrorKind::Unsupported)
+                        .msg("tuples can only be indexed with an integer literal")
+                        .ctx(error_ctx(self.src, &index.loc)));
+                };
+
+                match value.parse::<usize>() {
+                    Ok(slot) if slot < elements.len() => Ok((ptr, slot, elements[slot])),
+                    _ => Err(CodegenError::default()
+                        .kind(CodegenErrorKind::Undefined)
+                        .msg(format!(
+                            "`{}` has no element {value}",
+                            self.type_name(ValueType::Tuple(idx))
+                        ))
+                        .ctx(error_ctx(self.src, &index.loc))),
+                }
+            }
+            CollectionKind::Dict(key_type, value_type) => {
+                let (key, r#type) = self.lower_expression_as(index, key_type)?;
+                self.expect_type(key_type, r#type, &index.loc)?;
+
+                let (found, len) = self.dict_find(ptr, key, key_type, loc)?;
+                let exists = self.builder.ins().icmp(IntCC::UnsignedLessThan, found, len);
+                self.lower_check(exists, loc, |t| {
+                    let text = t.lower_to_string(key, key_type, loc)?;
+                    Ok(("key not found: %s".to_owned(), vec![text]))
+                })?;
+
+                let entries = self.load_entries(ptr);
+                let entry = self.element_address(entries, found, ENTRY_SIZE);
+                Ok((entry, 1, value_type))
+            }
+        }
+    }
+
+    // builds a dictionary out of an array of key-value pairs, later pairs
+    // replace earlier ones with the same key
+    pub(super) fn lower_dict_new(
+        &mut self,
+        args: &'a [ExpressionNode],
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let [arg] = args else {
+            let msg = match args.is_empty() {
+                true => {
+                    "cannot infer the types of an empty dictionary, declare it with a type instead"
+                }
+                false => "`Dict::new` takes a single argument",
+            };
+
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(msg)
+                .ctx(error_ctx(self.src, loc)));
+        };
+
+        let (pairs, r#type) = self.lower_expression(arg)?;
+        let Some((pair, key_type, value_type)) = self.pair_types(r#type) else {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(format!(
+                    "expected an array of key-value pairs, found `{}`",
+                    self.type_name(r#type)
+                ))
+                .ctx(error_ctx(self.src, &arg.loc)));
+        };
+
+        let namespace = self.namespace.index;
+        let dict_type = self
+            .types()
+            .dict_type(key_type, value_type, namespace, loc)?;
+
+        let dict = self.new_dict()?;
+        let len = self.load_slot(pairs, LENGTH_SLOT, ValueType::INT);
+        self.fold(len, dict, |t, i, dict| {
+            let element = t.element_address(pairs, i, SLOT_SIZE);
+            let entry = t.load_slot(element, 1, pair);
+
+            let key = t.load_slot(entry, 0, key_type);
+            let value = t.load_slot(entry, 1, value_type);
+            t.dict_insert(dict, key, value, key_type, loc)?;
+
+            Ok(dict)
+        })?;
+
+        Ok((dict, dict_type))
+    }
+
+    // the element, key and value types of an array of pairs
+    fn pair_types(&self, r#type: ValueType) -> Option<(ValueType, ValueType, ValueType)> {
+        let ValueType::Array(idx) = r#type else {
+            return None;
+        };
+
+        let CollectionKind::Array(pair @ ValueType::Tuple(tuple)) = self.collections[idx].kind
+        else {
+            return None;
+        };
+
+        match self.collections[tuple].kind {
+            CollectionKind::Tuple(ref elements) if elements.len() == 2 => {
+                Some((pair, elements[0], elements[1]))
+            }
+            _ => None,
+        }
+    }
+
+    pub(super) fn zero_collection(&mut self, idx: usize) -> Result<Value, CodegenError> {
+        match self.collections[idx].kind.clone() {
+            CollectionKind::Array(_) => {
+                let ptr = self.allocate(1)?;
+                let len = self.builder.ins().iconst(types::I64, 0);
+                self.store_slot(ptr, LENGTH_SLOT, len);
+
+                Ok(ptr)
+            }
+            CollectionKind::Tuple(elements) => {
+                let ptr = self.allocate(elements.len())?;
+                for (i, element) in elements.into_iter().enumerate() {
+                    let value = self.zero_value(element)?;
+                    self.store_slot(ptr, i, value);
+                }
+
+                Ok(ptr)
+            }
+            CollectionKind::Dict(..) => self.new_dict(),
+        }
+    }
+
+    fn new_dict(&mut self) -> Result<Value, CodegenError> {
+        let dict = self.allocate(2)?;
+        let entries = self.allocate(0)?;
+
+        let len = self.builder.ins().iconst(types::I64, 0);
+        self.store_slot(dict, LENGTH_SLOT, len);
+        self.store_slot(dict, ENTRIES_SLOT, entries);
+
+        Ok(dict)
+    }
+
+    // the index of the entry with `key`, or the length if there's none
+    fn dict_find(
+        &mut self,
+        dict: Value,
+        key: Value,
+        key_type: ValueType,
+        loc: &Loc,
+    ) -> Result<(Value, Value), CodegenError> {
+        let len = self.load_slot(dict, LENGTH_SLOT, ValueType::INT);
+        let entries = self.load_entries(dict);
+
+        let found = self.fold(len, len, |t, i, found| {
+            let entry = t.element_address(entries, i, ENTRY_SIZE);
+            let other = t.load_slot(entry, 0, key_type);
+
+            let (equal, _) = t.lower_operator(
+                &ComplexPunctuationKind::OperatorEqual,
+                (other, key_type),
+                (key, key_type),
+                loc,
+            )?;
+            let missing = t.builder.ins().icmp(IntCC::Equal, found, len);
+            let first = t.builder.ins().band(equal, missing);
+
+            Ok(t.builder.ins().select(first, i, found))
+        })?;
+
+        Ok((found, len))
+    }
+
+    fn dict_insert(
+        &mut self,
+        dict: Value,
+        key: Value,
+        value: Value,
+        key_type: ValueType,
+        loc: &Loc,
+    ) -> Result<(), CodegenError> {
+        let (found, len) = self.dict_find(dict, key, key_type, loc)?;
+        let entries = self.load_entries(dict);
+
+        let ptr = self.ptr_type();
+        let grow_block = self.builder.create_block();
+        let store_block = self.builder.create_block();
+        self.builder.append_block_param(store_block, ptr);
+        self.builder.append_block_param(store_block, types::I64);
+
+        let missing = self.builder.ins().icmp(IntCC::Equal, found, len);
+        self.builder
+            .ins()
+            .brif(missing, grow_block, &[], store_block, &[entries, found]);
+
+        self.builder.switch_to_block(grow_block);
+        let new_len = self.builder.ins().iadd_imm(len, 1);
+        let size = self.builder.ins().imul_imm(new_len, ENTRY_SIZE);
+        let grown = self.call_import("realloc", &[entries, size])?;
+        self.store_slot(dict, LENGTH_SLOT, new_len);
+        self.store_slot(dict, ENTRIES_SLOT, grown);
+        self.builder.ins().jump(store_block, &[grown, len]);
+
+        self.builder.switch_to_block(store_block);
+        let params = self.builder.block_params(store_block).to_vec();
+        let entry = self.element_address(params[0], params[1], ENTRY_SIZE);
+        self.store_slot(entry, 0, key);
+        self.store_slot(entry, 1, value);
+
+        Ok(())
+    }
+
+    // the number of elements a `for` loop visits, which is read again before
+    // every iteration since dictionaries can grow in the loop body
+    pub(super) fn iteration_len(&mut self, value: Value) -> Value {
+        self.load_slot(value, LENGTH_SLOT, ValueType::INT)
+    }
+
+    // dictionaries hand out copies of their entries as `(key, value)` tuples
+    pub(super) fn iteration_element(
+        &mut self,
+        value: Value,
+        idx: usize,
+        i: Value,
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        match self.collections[idx].kind.clone() {
+            CollectionKind::Array(element_type) => {
+                let element = self.element_address(value, i, SLOT_SIZE);
+                Ok((self.load_slot(element, 1, element_type), element_type))
+            }
+            CollectionKind::Dict(key_type, value_type) => {
+                let pair_type =
+                    self.collection(CollectionKind::Tuple(vec![key_type, value_type]), loc)?;
+
+                let entries = self.load_entries(value);
+                let entry = self.element_address(entries, i, ENTRY_SIZE);
+                let key = self.load_slot(entry, 0, key_type);
+                let value = self.load_slot(entry, 1, value_type);
+
+                let pair = self.allocate(2)?;
+                self.store_slot(pair, 0, key);
+                self.store_slot(pair, 1, value);
+
+                Ok((pair, pair_type))
+            }
+            CollectionKind::Tuple(_) => unreachable!(),
+        }
+    }
+
+    fn load_entries(&mut self, dict: Value) -> Value {
+        let ptr = self.ptr_type();
+        self.builder.ins().load(
+            ptr,
+            MemFlags::trusted(),
+            dict,
+            (ENTRIES_SLOT as i64 * SLOT_SIZE) as i32,
+        )
+    }
+
+    fn element_address(&mut self, ptr: Value, index: Value, size: i64) -> Value {
+        let offset = self.builder.ins().imul_imm(index, size);
+        self.builder.ins().iadd(ptr, offset)
+    }
+
+    // runs `body` for every index below `len`, threading a value through the
+    // iterations
+    fn fold<F>(&mut self, len: Value, init: Value, mut body: F) -> Result<Value, CodegenError>
+    where
+        F: FnMut(&mut Self, Value, Value) -> Result<Value, CodegenError>,
+    {
+        let acc_type = self.builder.func.dfg.value_type(init);
+
+        let header_block = self.builder.create_block();
+        let body_block = self.builder.create_block();
+        let exit_block = self.builder.create_block();
+        self.builder.append_block_param(header_block, types::I64);
+        self.builder.append_block_param(header_block, acc_type);
+        self.builder.append_block_param(exit_block, acc_type);
+
+        let zero = self.builder.ins().iconst(types::I64, 0);
+        self.builder.ins().jump(header_block, &[zero, init]);
+
+        self.builder.switch_to_block(header_block);
+        let params = self.builder.block_params(header_block).to_vec();
+        let (i, acc) = (params[0], params[1]);
+        let done = self
+            .builder
+            .ins()
+            .icmp(IntCC::SignedGreaterThanOrEqual, i, len);
+        self.builder
+            .ins()
+            .brif(done, exit_block, &[acc], body_block, &[]);
+
+        self.builder.switch_to_block(body_block);
+        let acc = body(self, i, acc)?;
+        let next = self.builder.ins().iadd_imm(i, 1);
+        self.builder.ins().jump(header_block, &[next, acc]);
+
+        self.builder.switch_to_block(exit_block);
+        Ok(self.builder.block_params(exit_block)[0])
+    }
+
+    pub(super) fn collection_to_string(
+        &mut self,
+        value: Value,
+        idx: usize,
+        loc: &Loc,
+    ) -> Result<Value, CodegenError> {
+        let (text, end) = match self.collections[idx].kind.clone() {
+            CollectionKind::Tuple(elements) => {
+                let fields: Vec<FieldSymbol> = elements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, r#type)| FieldSymbol {
+                        name: i.to_string(),
+                        r#type: *r#type,
+                        default: None,
+                    })
+                    .collect();
+
+                let start = self.string_value("(")?;
+                let text = self.fields_to_string(value, 0, &fields, false, loc)?;
+                let text = self.lower_concat(start, text)?;
+
+                (text, if fields.len() == 1 { ",)" } else { ")" })
+            }
+            CollectionKind::Array(element_type) => {
+                let start = self.string_value("[")?;
+                let len = self.load_slot(value, LENGTH_SLOT, ValueType::INT);
+
+                let text = self.fold(len, start, |t, i, text| {
+                    let text = t.separate(text, i)?;
+                    let element = t.element_address(value, i, SLOT_SIZE);
+                    let element = t.load_slot(element, 1, element_type);
+
+                    let element = t.lower_to_string(element, element_type, loc)?;
+                    t.lower_concat(text, element)
+                })?;
+
+                (text, "]")
+            }
+            CollectionKind::Dict(key_type, value_type) => {
+                let start = self.string_value("{")?;
+                let len = self.load_slot(value, LENGTH_SLOT, ValueType::INT);
+                let entries = self.load_entries(value);
+
+                let text = self.fold(len, start, |t, i, text| {
+                    let text = t.separate(text, i)?;
+                    let entry = t.element_address(entries, i, ENTRY_SIZE);
+
+                    let key = t.load_slot(entry, 0, key_type);
+                    let key = t.lower_to_string(key, key_type, loc)?;
+                    let text = t.lower_concat(text, key)?;
+
+                    let colon = t.string_value(": ")?;
+                    let text = t.lower_concat(text, colon)?;
+
+                    let value = t.load_slot(entry, 1, value_type);
+                    let value = t.lower_to_string(value, value_type, loc)?;
+                    t.lower_concat(text, value)
+                })?;
+
+                (text, "}")
+            }
+        };
+
+        let end = self.string_value(end)?;
+        self.lower_concat(text, end)
+    }
+
+    // puts a comma before every element but the first one
+    fn separate(&mut self, text: Value, i: Value) -> Result<Value, CodegenError> {
+        let comma = self.string_value(", ")?;
+        let empty = self.string_value("")?;
+
+        let first = self.builder.ins().icmp_imm(IntCC::Equal, i, 0);
+        let separator = self.builder.ins().select(first, empty, comma);
+
+        self.lower_concat(text, separator)
+    }
+
+    fn collection(&mut self, kind: CollectionKind, loc: &Loc) -> Result<ValueType, CodegenError> {
+        let namespace = self.namespace.index;
+        self.types().collection(kind, namespace, loc)
+    }
+}
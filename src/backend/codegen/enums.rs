@@ -0,0 +1,402 @@
+use cranelift::prelude::{types, InstBuilder, IntCC, Value};
+use cranelift_module::{FuncId, Module};
+
+use crate::{
+    errors::codegen::{CodegenError, CodegenErrorKind},
+    frontend::{
+        parsing::ast::{
+            expressions::{ExpressionNode, FieldValueNode},
+            items::{EnumNode, ItemKind, VariantKind},
+        },
+        resolving::qualify,
+        Loc,
+    },
+};
+
+use super::{
+    collections::{CollectionKind, CollectionType},
+    error_ctx,
+    functions::FunctionTranslator,
+    structs::{FieldSymbol, StructType},
+    types::ValueType,
+    Codegen, Symbol,
+};
+
+// the first slot of an enum value holds the index of its variant, the
+// payload follows it
+const TAG_SLOT: usize = 0;
+
+pub struct EnumType<'a> {
+    pub name: String,
+    pub node: &'a EnumNode,
+    pub variants: Vec<VariantSymbol<'a>>,
+    pub namespace: usize,
+    pub zero: usize,
+    pub formatter: FuncId,
+}
+
+pub struct VariantSymbol<'a> {
+    pub name: String,
+    pub kind: &'a VariantKind,
+    pub fields: Vec<FieldSymbol<'a>>,
+}
+
+impl EnumType<'_> {
+    pub fn variant_name(&self, variant: usize) -> String {
+        format!("{}::{}", self.node.name, self.variants[variant].name)
+    }
+
+    fn slots(&self) -> usize {
+        let payload = self.variants.iter().map(|v| v.fields.len()).max();
+        1 + payload.unwrap_or(0)
+    }
+}
+
+impl<'a, M: Module> Codegen<'a, M> {
+    pub(super) fn declare_enums(&mut self) {
+        for idx in 0..self.namespaces.len() {
+            let namespace = &self.namespaces[idx];
+            let (node, path) = (namespace.node, namespace.path.clone());
+
+            for item in node.items.iter() {
+                let ItemKind::Enum(node) = &item.kind else {
+                    continue;
+                };
+
+                let name = qualify(&path, &node.name);
+                self.enums.push(EnumType {
+                    name: name.clone(),
+                    node,
+                    variants: Vec::new(),
+                    namespace: idx,
+                    formatter: FuncId::from_u32(0),
+                    zero: 0,
+                });
+
+                let enum_idx = self.enums.len() - 1;
+                for (variant, variant_node) in node.variants.iter().enumerate() {
+                    let symbol = Symbol::Variant(enum_idx, variant);
+                    let variant_name = qualify(&name, &variant_node.name);
+                    self.insert_global(variant_name, symbol);
+                }
+
+                self.insert_global(name, Symbol::Enum(enum_idx));
+            }
+        }
+    }
+
+    pub(super) fn define_enums(&mut self) {
+        for idx in 0..self.enums.len() {
+            self.current = self.enums[idx].namespace;
+
+            let mut variants = Vec::new();
+            for variant in self.enums[idx].node.variants.iter() {
+                let fields = match &variant.kind {
+                    VariantKind::Unit => Vec::new(),
+                    VariantKind::Tuple(types) => types
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(i, node)| {
+                            let name = i.to_string();
+                            let r#type = self.lower_field_type(&name, node)?;
+
+                            Some(FieldSymbol {
+                                name,
+                                r#type,
+                                default: None,
+                            })
+                        })
+                        .collect(),
+                    VariantKind::Struct(fields) => self.lower_fields(fields),
+                };
+
+                variants.push(VariantSymbol {
+                    name: variant.name.clone(),
+                    kind: &variant.kind,
+                    fields,
+                });
+            }
+
+            self.enums[idx].variants = variants;
+        }
+    }
+
+    // every struct and enum needs a value that doesn't contain itself, which
+    // is also what zero values are built from
+    pub(super) fn check_recursive_types(&mut self) {
+        let (structs, enums) = buildable_types(&self.structs, &self.enums, &self.collections);
+
+        // instances are checked when they're created
+        for (idx, buildable) in structs.into_iter().enumerate() {
+            if buildable || self.structs[idx].template.is_some() {
+                continue;
+            }
+
+            self.current = self.structs[idx].namespace;
+            let layout = &self.structs[idx];
+            self.errors.push(
+                CodegenError::default()
+                    .kind(CodegenErrorKind::Unsupported)
+                    .msg(format!(
+                        "`{}` contains itself, so none of its values can be built",
+                        layout.name
+                    ))
+                    .ctx(error_ctx(self.src(), &layout.node.loc)),
+            );
+        }
+
+        for (idx, zero) in enums.into_iter().enumerate() {
+            if let Some(zero) = zero {
+                self.enums[idx].zero = zero;
+                continue;
+            }
+
+            self.current = self.enums[idx].namespace;
+            let layout = &self.enums[idx];
+            let msg = if layout.variants.is_empty() {
+                format!("`{}` has no variants", layout.name)
+            } else {
+                format!(
+                    "every variant of `{}` contains itself, so none of its values can be built",
+                    layout.name
+                )
+            };
+
+            self.errors.push(
+                CodegenError::default()
+                    .kind(CodegenErrorKind::Unsupported)
+                    .msg(msg)
+                    .ctx(error_ctx(self.src(), &layout.node.loc)),
+            );
+        }
+    }
+}
+
+// which structs can be built and the variant each enum's zero value uses,
+// growing from the types that don't contain any others
+pub(super) fn buildable_types(
+    structs: &[StructType],
+    enums: &[EnumType],
+    collections: &[CollectionType],
+) -> (Vec<bool>, Vec<Option<usize>>) {
+    let mut buildable = vec![false; structs.len()];
+    let mut zeros: Vec<Option<usize>> = vec![None; enums.len()];
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for (idx, layout) in structs.iter().enumerate() {
+            if !buildable[idx] && all_buildable(&layout.fields, collections, &buildable, &zeros) {
+                buildable[idx] = true;
+                changed = true;
+            }
+        }
+
+        for (idx, layout) in enums.iter().enumerate() {
+            if zeros[idx].is_some() {
+                continue;
+            }
+
+            zeros[idx] = layout
+                .variants
+                .iter()
+                .position(|variant| variant.fields.is_empty())
+                .or_else(|| {
+                    layout.variants.iter().position(|variant| {
+                        all_buildable(&variant.fields, collections, &buildable, &zeros)
+                    })
+                });
+            changed |= zeros[idx].is_some();
+        }
+    }
+
+    return (buildable, zeros);
+}
+
+fn all_buildable(
+    fields: &[FieldSymbol],
+    collections: &[CollectionType],
+    structs: &[bool],
+    enums: &[Option<usize>],
+) -> bool {
+    fields
+        .iter()
+        .all(|field| is_buildable(field.r#type, collections, structs, enums))
+}
+
+// arrays and dictionaries can start out empty, tuples need all their elements
+fn is_buildable(
+    r#type: ValueType,
+    collections: &[CollectionType],
+    structs: &[bool],
+    enums: &[Option<usize>],
+) -> bool {
+    match r#type {
+        ValueType::Struct(idx) => structs[idx],
+        ValueType::Enum(idx) => enums[idx].is_some(),
+        ValueType::Tuple(idx) => {
+            let CollectionKind::Tuple(elements) = &collections[idx].kind else {
+                unreachable!()
+            };
+
+            elements
+                .iter()
+                .all(|element| is_buildable(*element, collections, structs, enums))
+        }
+        _ => true,
+    }
+}
+
+impl<'a, M: Module> FunctionTranslator<'a, '_, M> {
+    pub(super) fn lower_unit_variant(
+        &mut self,
+        idx: usize,
+        variant: usize,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        // the type checker only lets unit variants be used as values
+        let ptr = self.allocate_variant(idx, variant)?;
+        Ok((ptr, ValueType::Enum(idx)))
+    }
+
+    pub(super) fn lower_tuple_variant(
+        &mut self,
+        idx: usize,
+        variant: usize,
+        args: &'a [ExpressionNode],
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let enums = self.enums;
+        let layout = &enums[idx];
+        let symbol = &layout.variants[variant];
+
+        // the type checker made sure it's a tuple variant given all its values
+        let ptr = self.allocate_variant(idx, variant)?;
+        for (i, (arg, field)) in args.iter().zip(symbol.fields.iter()).enumerate() {
+            let (value, r#type) = self.lower_expression_as(arg, field.r#type)?;
+            self.expect_type(field.r#type, r#type, &arg.loc)?;
+            self.store_slot(ptr, TAG_SLOT + 1 + i, value);
+        }
+
+        Ok((ptr, ValueType::Enum(idx)))
+    }
+
+    pub(super) fn lower_struct_variant(
+        &mut self,
+        idx: usize,
+        variant: usize,
+        fields: &'a [FieldValueNode],
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let enums = self.enums;
+        let layout = &enums[idx];
+        let symbol = &layout.variants[variant];
+        let name = layout.variant_name(variant);
+
+        let given = self.lower_field_values(fields)?;
+        let ptr = self.allocate_variant(idx, variant)?;
+        self.store_fields(ptr, TAG_SLOT + 1, &symbol.fields, given, &name)?;
+
+        Ok((ptr, ValueType::Enum(idx)))
+    }
+
+    pub(super) fn zero_enum(&mut self, idx: usize) -> Result<Value, CodegenError> {
+        let enums = self.enums;
+        let layout = &enums[idx];
+
+        let ptr = self.allocate_variant(idx, layout.zero)?;
+        self.zero_fields(ptr, TAG_SLOT + 1, &layout.variants[layout.zero].fields)?;
+
+        Ok(ptr)
+    }
+
+    pub(super) fn load_variant_field(
+        &mut self,
+        value: Value,
+        field: usize,
+        r#type: ValueType,
+    ) -> Value {
+        self.load_slot(value, TAG_SLOT + 1 + field, r#type)
+    }
+
+    pub(super) fn load_tag(&mut self, value: Value) -> Value {
+        self.load_slot(value, TAG_SLOT, ValueType::INT)
+    }
+
+    fn allocate_variant(&mut self, idx: usize, variant: usize) -> Result<Value, CodegenError> {
+        let ptr = self.allocate(self.enums[idx].slots())?;
+
+        let tag = self.builder.ins().iconst(types::I64, variant as i64);
+        self.store_slot(ptr, TAG_SLOT, tag);
+
+        Ok(ptr)
+    }
+
+    pub(super) fn enum_to_string(
+        &mut self,
+        value: Value,
+        idx: usize,
+        loc: &Loc,
+    ) -> Result<Value, CodegenError> {
+        let enums = self.enums;
+        let layout = &enums[idx];
+
+        let merge_block = self.builder.create_block();
+        self.builder
+            .append_block_param(merge_block, self.ptr_type());
+
+        let tag = self.load_tag(value);
+
+        for (variant, symbol) in layout.variants.iter().enumerate() {
+            let variant_block = self.builder.create_block();
+
+            if variant + 1 < layout.variants.len() {
+                let next_block = self.builder.create_block();
+                let is_variant = self
+                    .builder
+                    .ins()
+                    .icmp_imm(IntCC::Equal, tag, variant as i64);
+                self.builder
+                    .ins()
+                    .brif(is_variant, variant_block, &[], next_block, &[]);
+
+                self.builder.switch_to_block(variant_block);
+                let text = self.variant_to_string(value, idx, variant, symbol, loc)?;
+                self.builder.ins().jump(merge_block, &[text]);
+
+                self.builder.switch_to_block(next_block);
+            } else {
+                let text = self.variant_to_string(value, idx, variant, symbol, loc)?;
+                self.builder.ins().jump(merge_block, &[text]);
+            }
+        }
+
+        self.builder.switch_to_block(merge_block);
+        Ok(self.builder.block_params(merge_block)[0])
+    }
+
+    fn variant_to_string(
+        &mut self,
+        value: Value,
+        idx: usize,
+        variant: usize,
+        symbol: &VariantSymbol,
+        loc: &Loc,
+    ) -> Result<Value, CodegenError> {
+        let name = self.enums[idx].variant_name(variant);
+
+        let (open, close, named) = match symbol.kind {
+            VariantKind::Unit => return self.string_value(&name),
+            VariantKind::Tuple(_) => ("(", ")", false),
+            VariantKind::Struct(_) if symbol.fields.is_empty() => {
+                return self.string_value(&format!("{name} {{}}"));
+            }
+            VariantKind::Struct(_) => (" { ", " }", true),
+        };
+
+        let start = self.string_value(&format!("{name}{open}"))?;
+        let fields = self.fields_to_string(value, TAG_SLOT + 1, &symbol.fields, named, loc)?;
+        let end = self.string_value(close)?;
+
+        let result = self.lower_concat(start, fields)?;
+        self.lower_concat(result, end)
+    }
+}
@@ -0,0 +1,1113 @@
+use std::cmp::Ordering;
+
+use cranelift::prelude::{types, FloatCC, InstBuilder, IntCC, Value};
+use cranelift_module::{DataDescription, Module};
+
+use crate::{
+    errors::codegen::{CodegenError, CodegenErrorKind},
+    frontend::{
+        evaluating::ConstValue,
+        lexing::{AssignmentKind, ComplexPunctuationKind, LiteralKind},
+        parsing::ast::{
+            expressions::{
+                ExpressionKind, ExpressionNode, InterpolationPart, NumberLiteral,
+                ResolutionExpressionKind,
+            },
+            types::TypeNode,
+        },
+        primitives::{FloatType, IntType},
+        Loc,
+    },
+    utils::text::count_mismatch,
+};
+
+use super::{
+    error_ctx, functions::FunctionTranslator, module_error, types::ValueType, FunctionSymbol,
+    Symbol,
+};
+
+// a lowered value with its type
+type Lowered = (Value, ValueType);
+
+impl<'a, M: Module> FunctionTranslator<'a, '_, M> {
+    pub(super) fn lower_expression(
+        &mut self,
+        expr: &'a ExpressionNode,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        self.lower_expected(expr, None)
+    }
+
+    pub(super) fn lower_expression_as(
+        &mut self,
+        expr: &'a ExpressionNode,
+        expected: ValueType,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        self.lower_expected(expr, Some(expected))
+    }
+
+    // unsuffixed number literals take the numeric type they're expected to
+    // be, which reaches them through the operators that keep the type of
+    // their operands, like `x: uint8 = 2 * (1 + 3)`
+    fn lower_expected(
+        &mut self,
+        expr: &'a ExpressionNode,
+        expected: Option<ValueType>,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        if let Some(literal) = expr.number() {
+            return self.lower_number(expected, &literal, &expr.loc);
+        }
+
+        match &expr.kind {
+            ExpressionKind::Literal { kind, value, .. } => self.lower_literal(kind, value),
+            ExpressionKind::Resolution {
+                left,
+                right,
+                kind: ResolutionExpressionKind::Member,
+            } => self.lower_member(left, right),
+            ExpressionKind::Identifier { .. } | ExpressionKind::Resolution { .. } => {
+                self.lower_name(expr)
+            }
+            ExpressionKind::Interpolation { parts } => self.lower_interpolation(parts),
+            ExpressionKind::Call { res, args } => self.lower_call(res, args, &expr.loc),
+            ExpressionKind::MethodCall {
+                object,
+                method,
+                args,
+            } => self.lower_method_call(object, method, args, &expr.loc),
+            ExpressionKind::Struct { res, fields } => self.lower_struct(res, fields),
+            ExpressionKind::Array { elements } => self.lower_array(elements, expected, &expr.loc),
+            ExpressionKind::Tuple { elements } => self.lower_tuple(elements, expected, &expr.loc),
+            ExpressionKind::Index { object, index } => self.lower_index(object, index, &expr.loc),
+            ExpressionKind::Match { value, arms } => self.lower_match_expression(value, arms),
+            ExpressionKind::Range { .. } => Err(CodegenError::default()
+                .kind(CodegenErrorKind::Unsupported)
+                .msg("ranges can only be used in `for` loops")
+                .ctx(error_ctx(self.src, &expr.loc))),
+            ExpressionKind::Binary {
+                kind,
+                left,
+                op,
+                right,
+            } => match op {
+                ComplexPunctuationKind::OperatorAnd | ComplexPunctuationKind::OperatorOr => {
+                    self.lower_logical(left, op, right)
+                }
+                _ => {
+                    let expected = expected.filter(|_| kind.keeps_type());
+                    let (left, right) = self.lower_operands(expected, left, right)?;
+                    self.lower_operator(op, left, right, &expr.loc)
+                }
+            },
+            ExpressionKind::Unary { op, right, .. } => {
+                let (value, r#type) = match op {
+                    ComplexPunctuationKind::OperatorNot => self.lower_expression(right)?,
+                    _ => self.lower_expected(right, expected)?,
+                };
+
+                match (op, r#type) {
+                    (ComplexPunctuationKind::OperatorMinus, ValueType::Int(int))
+                        if int.is_signed() =>
+                    {
+                        Ok((self.lower_int_negation(value, int, &expr.loc)?, r#type))
+                    }
+                    (ComplexPunctuationKind::OperatorMinus, ValueType::Float(_)) => {
+                        Ok((self.builder.ins().fneg(value), r#type))
+                    }
+                    (ComplexPunctuationKind::OperatorNot, ValueType::Bool) => {
+                        Ok((self.builder.ins().icmp_imm(IntCC::Equal, value, 0), r#type))
+                    }
+                    (ComplexPunctuationKind::OperatorBitNot, ValueType::Int(_)) => {
+                        Ok((self.builder.ins().bnot(value), r#type))
+                    }
+                    _ => Err(self.operator_error(op, r#type, &expr.loc)),
+                }
+            }
+            ExpressionKind::Assignment { left, op, right } => {
+                self.lower_assignment(left, op, right, &expr.loc)
+            }
+            ExpressionKind::Cast { value, r#type } => self.lower_cast(value, r#type),
+        }
+    }
+
+    // values lowered before it's known what they're expected to be, like
+    // arguments of generic functions. their literals are lowered again by
+    // `retype_deferred` once it is, so they aren't checked here
+    pub(super) fn lower_deferred(
+        &mut self,
+        expr: &'a ExpressionNode,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        match expr.number() {
+            Some(literal) if literal.suffix.is_none() => {
+                let r#type = match literal.kind {
+                    LiteralKind::Float => ValueType::FLOAT,
+                    _ => ValueType::INT,
+                };
+                Ok((self.zero_value(r#type)?, r#type))
+            }
+            _ => self.lower_expression(expr),
+        }
+    }
+
+    pub(super) fn retype_deferred(
+        &mut self,
+        expr: &'a ExpressionNode,
+        lowered: (Value, ValueType),
+        expected: ValueType,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        match expr.number() {
+            Some(literal) if literal.suffix.is_none() => self.lower_expression_as(expr, expected),
+            _ => Ok(lowered),
+        }
+    }
+
+    // literals the type checker couldn't give a type, like the ones next to
+    // a value of a type parameter, are typed here
+    pub(super) fn lower_operands(
+        &mut self,
+        expected: Option<ValueType>,
+        left: &'a ExpressionNode,
+        right: &'a ExpressionNode,
+    ) -> Result<(Lowered, Lowered), CodegenError> {
+        if left.is_literal_arithmetic() && !right.is_literal_arithmetic() {
+            let right = self.lower_expected(right, expected)?;
+            return Ok((self.lower_expression_as(left, right.1)?, right));
+        }
+
+        let left = self.lower_expected(left, expected)?;
+        let right = self.lower_expression_as(right, left.1)?;
+
+        Ok((left, right))
+    }
+
+    pub(super) fn lower_number(
+        &mut self,
+        expected: Option<ValueType>,
+        literal: &NumberLiteral,
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let is_float = *literal.kind == LiteralKind::Float;
+        let checked = self
+            .literals
+            .get(&(self.namespace.node.source, loc.clone()));
+
+        let r#type = match (checked, expected, literal.suffix) {
+            (Some(primitive), ..) => ValueType::from(*primitive),
+            (_, _, Some(suffix)) => ValueType::from(suffix.r#type()),
+            (_, Some(ValueType::Int(int)), None) if !is_float => ValueType::Int(int),
+            (_, Some(ValueType::Float(float)), None) if is_float => ValueType::Float(float),
+            _ if is_float => ValueType::FLOAT,
+            _ => ValueType::INT,
+        };
+
+        let value = match r#type {
+            ValueType::Int(int) => int
+                .parse(literal.value, literal.negative)
+                .map(|value| self.int_const(int, value)),
+            ValueType::Float(float) => float
+                .parse(literal.value, literal.negative)
+                .map(|value| self.float_const(float, value)),
+            _ => unreachable!(),
+        };
+
+        let Some(value) = value else {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(format!(
+                    "literal `{}` does not fit in `{}`",
+                    literal.to_string(),
+                    r#type.to_string()
+                ))
+                .ctx(error_ctx(self.src, loc)));
+        };
+
+        Ok((value, r#type))
+    }
+
+    // narrow constants are given as their bits, without the sign extension
+    pub(super) fn int_const(&mut self, int: IntType, value: i64) -> Value {
+        let ty = ValueType::Int(int).clif(self.ptr_type());
+        let value = match int.bits() {
+            64 => value,
+            bits => value & ((1 << bits) - 1),
+        };
+
+        self.builder.ins().iconst(ty, value)
+    }
+
+    pub(super) fn float_const(&mut self, float: FloatType, value: f64) -> Value {
+        match float {
+            FloatType::Float32 => self.builder.ins().f32const(value as f32),
+            FloatType::Float64 => self.builder.ins().f64const(value),
+        }
+    }
+
+    fn lower_literal(
+        &mut self,
+        kind: &LiteralKind,
+        value: &str,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        match kind {
+            // numbers are lowered by `lower_number`
+            LiteralKind::Integer | LiteralKind::Float => unreachable!(),
+            LiteralKind::String => Ok((self.string_value(value)?, ValueType::String)),
+            LiteralKind::Char => {
+                let code = value.chars().next().unwrap() as i64;
+                Ok((self.builder.ins().iconst(types::I32, code), ValueType::Char))
+            }
+            LiteralKind::Boolean => {
+                let value = self
+                    .builder
+                    .ins()
+                    .iconst(types::I8, (value == "true") as i64);
+                Ok((value, ValueType::Bool))
+            }
+        }
+    }
+
+    fn lower_constant(&mut self, value: &ConstValue) -> Result<(Value, ValueType), CodegenError> {
+        let lowered = match value {
+            ConstValue::Int(int, value) => (self.int_const(*int, *value), ValueType::Int(*int)),
+            ConstValue::Float(float, value) => {
+                (self.float_const(*float, *value), ValueType::Float(*float))
+            }
+            ConstValue::Bool(value) => {
+                let value = self.builder.ins().iconst(types::I8, *value as i64);
+                (value, ValueType::Bool)
+            }
+            ConstValue::Char(value) => {
+                let value = self.builder.ins().iconst(types::I32, *value as i64);
+                (value, ValueType::Char)
+            }
+            ConstValue::String(value) => (self.string_value(value)?, ValueType::String),
+        };
+
+        Ok(lowered)
+    }
+
+    fn lower_name(&mut self, expr: &'a ExpressionNode) -> Result<(Value, ValueType), CodegenError> {
+        match self.lookup_expression(expr)? {
+            Symbol::Variable(var) => Ok((self.builder.use_var(var.var), var.r#type)),
+            Symbol::Global(global) => {
+                let r#type = self.global_type(global.r#type, expr)?;
+                Ok((self.load_global(global.data, r#type), r#type))
+            }
+            Symbol::Constant(value) => self.lower_constant(&value),
+            Symbol::Function(_) => Err(CodegenError::default()
+                .kind(CodegenErrorKind::Unsupported)
+                .msg("functions cannot be used as values")
+                .ctx(error_ctx(self.src, &expr.loc))),
+            Symbol::Struct(_) => Err(CodegenError::default()
+                .kind(CodegenErrorKind::Unsupported)
+                .msg("structs cannot be used as values")
+                .ctx(error_ctx(self.src, &expr.loc))),
+            Symbol::Enum(_) => Err(CodegenError::default()
+                .kind(CodegenErrorKind::Unsupported)
+                .msg("enums cannot be used as values")
+                .ctx(error_ctx(self.src, &expr.loc))),
+            Symbol::Variant(idx, variant) => self.lower_unit_variant(idx, variant),
+            Symbol::Template(_) => Err(CodegenError::default()
+                .kind(CodegenErrorKind::Unsupported)
+                .msg("functions cannot be used as values")
+                .ctx(error_ctx(self.src, &expr.loc))),
+            Symbol::Trait(_) => Err(CodegenError::default()
+                .kind(CodegenErrorKind::Unsupported)
+                .msg("traits cannot be used as values")
+                .ctx(error_ctx(self.src, &expr.loc))),
+        }
+    }
+
+    fn lower_call(
+        &mut self,
+        res: &'a ExpressionNode,
+        args: &'a [ExpressionNode],
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let Some(path) = res.path() else {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::Unsupported)
+                .msg("only named functions can be called")
+                .ctx(error_ctx(self.src, &res.loc)));
+        };
+
+        if self.lookup(&path, &res.loc).is_none()
+            && let Some(result) = self.lower_builtin(&path, args, loc)?
+        {
+            return Ok(result);
+        }
+
+        let func = match self.lookup_expression(res)? {
+            Symbol::Function(func) => func,
+            Symbol::Variant(idx, variant) => {
+                return self.lower_tuple_variant(idx, variant, args);
+            }
+            Symbol::Template(idx) => return self.lower_template_call(idx, args, &path, loc),
+            // the type checker only lets functions and tuple variants be called
+            _ => unreachable!(),
+        };
+
+        self.call_function(&func, None, args, &path, loc)
+    }
+
+    // a method receives the value it's called on before the written arguments.
+    // the types of the parameters are known here, so arguments are lowered
+    // as them
+    pub(super) fn call_function(
+        &mut self,
+        func: &FunctionSymbol<'a>,
+        receiver: Option<Value>,
+        args: &'a [ExpressionNode],
+        path: &str,
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let params = &func.args[receiver.is_some() as usize..];
+
+        let mut given = Vec::new();
+        for (i, arg) in args.iter().enumerate() {
+            given.push(match params.get(i) {
+                Some(param) => self.lower_expression_as(arg, param.r#type)?,
+                None => self.lower_deferred(arg)?,
+            });
+        }
+
+        self.call_lowered(func, receiver, args, given, path, loc)
+    }
+
+    pub(super) fn lower_values(
+        &mut self,
+        exprs: &'a [ExpressionNode],
+    ) -> Result<Vec<(Value, ValueType)>, CodegenError> {
+        let mut values = Vec::new();
+        for expr in exprs.iter() {
+            values.push(self.lower_deferred(expr)?);
+        }
+
+        return Ok(values);
+    }
+
+    // `given` holds the already lowered `args`, the defaults fill in the rest
+    pub(super) fn call_lowered(
+        &mut self,
+        func: &FunctionSymbol<'a>,
+        receiver: Option<Value>,
+        args: &'a [ExpressionNode],
+        given: Vec<(Value, ValueType)>,
+        path: &str,
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let mut values: Vec<Value> = receiver.into_iter().collect();
+        let params = &func.args[values.len()..];
+
+        if args.len() > params.len() {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(count_mismatch(path, params.len(), "argument", args.len()))
+                .ctx(error_ctx(self.src, loc)));
+        }
+
+        for (i, arg) in params.iter().enumerate() {
+            let (value, r#type, expr) = match (given.get(i), arg.default) {
+                (Some(lowered), _) => {
+                    let (value, r#type) = self.retype_deferred(&args[i], *lowered, arg.r#type)?;
+                    (value, r#type, &args[i])
+                }
+                (None, Some(default)) => {
+                    let (value, r#type) = self.lower_expression_as(default, arg.r#type)?;
+                    (value, r#type, default)
+                }
+                (None, None) => {
+                    return Err(CodegenError::default()
+                        .kind(CodegenErrorKind::MismatchedTypes)
+                        .msg(format!("missing argument {} of `{path}`", i + 1))
+                        .ctx(error_ctx(self.src, loc)));
+                }
+            };
+
+            self.expect_type(arg.r#type, r#type, &expr.loc)?;
+            values.push(value);
+        }
+
+        let func_ref = self.func_ref(func.id);
+        let call = self.builder.ins().call(func_ref, &values);
+
+        if func.r#type == ValueType::Void {
+            return Ok((self.zero_value(ValueType::Void)?, ValueType::Void));
+        }
+
+        Ok((self.builder.inst_results(call)[0], func.r#type))
+    }
+
+    fn lower_logical(
+        &mut self,
+        left: &'a ExpressionNode,
+        op: &ComplexPunctuationKind,
+        right: &'a ExpressionNode,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let (left_val, left_type) = self.lower_expression(left)?;
+        self.expect_type(ValueType::Bool, left_type, &left.loc)?;
+
+        let right_block = self.builder.create_block();
+        let merge_block = self.builder.create_block();
+        self.builder.append_block_param(merge_block, types::I8);
+
+        if *op == ComplexPunctuationKind::OperatorAnd {
+            self.builder
+                .ins()
+                .brif(left_val, right_block, &[], merge_block, &[left_val]);
+        } else {
+            self.builder
+                .ins()
+                .brif(left_val, merge_block, &[left_val], right_block, &[]);
+        }
+
+        self.builder.switch_to_block(right_block);
+        let (right_val, right_type) = self.lower_expression(right)?;
+        self.expect_type(ValueType::Bool, right_type, &right.loc)?;
+        self.builder.ins().jump(merge_block, &[right_val]);
+
+        self.builder.switch_to_block(merge_block);
+        Ok((self.builder.block_params(merge_block)[0], ValueType::Bool))
+    }
+
+    fn lower_assignment(
+        &mut self,
+        left: &'a ExpressionNode,
+        op: &AssignmentKind,
+        right: &'a ExpressionNode,
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        if let ExpressionKind::Resolution {
+            left: object,
+            right: field,
+            kind: ResolutionExpressionKind::Member,
+        } = &left.kind
+        {
+            return self.lower_member_assignment(object, field, op, right, loc);
+        }
+
+        if let ExpressionKind::Index { object, index } = &left.kind {
+            return self.lower_index_assignment(object, index, op, right, loc);
+        }
+
+        let symbol = self.lookup_expression(left)?;
+        let target_type = match &symbol {
+            Symbol::Variable(var) => var.r#type,
+            Symbol::Global(global) => self.global_type(global.r#type, left)?,
+            Symbol::Constant(_)
+            | Symbol::Function(_)
+            | Symbol::Struct(_)
+            | Symbol::Enum(_)
+            | Symbol::Variant(..)
+            | Symbol::Trait(_)
+            | Symbol::Template(_) => {
+                return Err(CodegenError::default()
+                    .kind(CodegenErrorKind::Unsupported)
+                    .msg("can only assign to variables, globals, fields and elements")
+                    .ctx(error_ctx(self.src, &left.loc)));
+            }
+        };
+
+        let (mut value, r#type) = self.lower_expression_as(right, target_type)?;
+
+        if let Some(bin_op) = op.operator() {
+            let (current, _) = self.lower_name(left)?;
+            (value, _) =
+                self.lower_operator(&bin_op, (current, target_type), (value, r#type), loc)?;
+        } else {
+            self.expect_type(target_type, r#type, &right.loc)?;
+        }
+
+        match symbol {
+            Symbol::Variable(var) => self.builder.def_var(var.var, value),
+            Symbol::Global(global) => self.store_global(global.data, value),
+            _ => unreachable!(),
+        }
+
+        Ok((value, target_type))
+    }
+
+    pub(super) fn lower_operator(
+        &mut self,
+        op: &ComplexPunctuationKind,
+        left: (Value, ValueType),
+        right: (Value, ValueType),
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        use ComplexPunctuationKind::*;
+
+        let ((lhs, r#type), (rhs, right_type)) = (left, right);
+        if r#type != right_type {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(format!(
+                    "cannot apply `{}` to `{}` and `{}`",
+                    op.to_string(),
+                    self.type_name(r#type),
+                    self.type_name(right_type)
+                ))
+                .ctx(error_ctx(self.src, loc)));
+        }
+
+        let value = match (r#type, op) {
+            (ValueType::Int(int), OperatorPlus | OperatorMinus | OperatorMultiply) => {
+                self.lower_int_arithmetic(op, lhs, rhs, int, loc)?
+            }
+            (ValueType::Int(int), OperatorDivide | OperatorModulo) => {
+                self.lower_int_division(op, lhs, rhs, int, loc)?
+            }
+            (ValueType::Int(int), OperatorPower) => self.lower_int_power(lhs, rhs, int, loc)?,
+            (ValueType::Int(_) | ValueType::Bool, OperatorBitAnd) => {
+                self.builder.ins().band(lhs, rhs)
+            }
+            (ValueType::Int(_) | ValueType::Bool, OperatorBitOr) => {
+                self.builder.ins().bor(lhs, rhs)
+            }
+            (ValueType::Int(_) | ValueType::Bool, OperatorBitXor) => {
+                self.builder.ins().bxor(lhs, rhs)
+            }
+            (ValueType::Int(_), OperatorShiftLeft) => self.builder.ins().ishl(lhs, rhs),
+            (ValueType::Int(int), OperatorShiftRight) if int.is_signed() => {
+                self.builder.ins().sshr(lhs, rhs)
+            }
+            (ValueType::Int(_), OperatorShiftRight) => self.builder.ins().ushr(lhs, rhs),
+            (ValueType::Int(int), _) if int_condition(op).is_some() => {
+                let cc = int_condition(op).unwrap();
+                let cc = if int.is_signed() { cc } else { cc.unsigned() };
+                self.builder.ins().icmp(cc, lhs, rhs)
+            }
+
+            (ValueType::Float(_), OperatorPlus) => self.builder.ins().fadd(lhs, rhs),
+            (ValueType::Float(_), OperatorMinus) => self.builder.ins().fsub(lhs, rhs),
+            (ValueType::Float(_), OperatorMultiply) => self.builder.ins().fmul(lhs, rhs),
+            (ValueType::Float(_), OperatorDivide) => self.builder.ins().fdiv(lhs, rhs),
+            (ValueType::Float(float), OperatorModulo) => {
+                self.call_float_import("fmod", float, lhs, rhs)?
+            }
+            (ValueType::Float(float), OperatorPower) => {
+                self.call_float_import("pow", float, lhs, rhs)?
+            }
+            (ValueType::Float(_), _) if float_condition(op).is_some() => {
+                self.builder
+                    .ins()
+                    .fcmp(float_condition(op).unwrap(), lhs, rhs)
+            }
+
+            (ValueType::Char, _) if int_condition(op).is_some() => {
+                self.builder
+                    .ins()
+                    .icmp(int_condition(op).unwrap(), lhs, rhs)
+            }
+
+            (ValueType::Bool, OperatorEqual) => self.builder.ins().icmp(IntCC::Equal, lhs, rhs),
+            (ValueType::Bool, OperatorNotEqual) => {
+                self.builder.ins().icmp(IntCC::NotEqual, lhs, rhs)
+            }
+
+            (ValueType::String, OperatorPlus) => self.lower_concat(lhs, rhs)?,
+            (ValueType::String, OperatorEqual | OperatorNotEqual) => {
+                let cmp = self.call_import("strcmp", &[lhs, rhs])?;
+                let cc = int_condition(op).unwrap();
+                self.builder.ins().icmp_imm(cc, cmp, 0)
+            }
+
+            _ => return Err(self.operator_error(op, r#type, loc)),
+        };
+
+        let r#type = if int_condition(op).is_some() {
+            ValueType::Bool
+        } else {
+            r#type
+        };
+
+        Ok((value, r#type))
+    }
+
+    // the C library only works with doubles, so narrower floats are
+    // promoted for the call
+    fn call_float_import(
+        &mut self,
+        name: &'static str,
+        float: FloatType,
+        lhs: Value,
+        rhs: Value,
+    ) -> Result<Value, CodegenError> {
+        if float == FloatType::Float64 {
+            return self.call_import(name, &[lhs, rhs]);
+        }
+
+        let lhs = self.builder.ins().fpromote(types::F64, lhs);
+        let rhs = self.builder.ins().fpromote(types::F64, rhs);
+        let result = self.call_import(name, &[lhs, rhs])?;
+
+        Ok(self.builder.ins().fdemote(types::F32, result))
+    }
+
+    // results that don't fit in the type stop the program, the same way the
+    // evaluator rejects them in constants
+    fn lower_int_arithmetic(
+        &mut self,
+        op: &ComplexPunctuationKind,
+        lhs: Value,
+        rhs: Value,
+        int: IntType,
+        loc: &Loc,
+    ) -> Result<Value, CodegenError> {
+        use ComplexPunctuationKind::*;
+
+        let ins = self.builder.ins();
+        let (value, overflows) = match (op, int.is_signed()) {
+            (OperatorPlus, true) => ins.sadd_overflow(lhs, rhs),
+            (OperatorPlus, false) => ins.uadd_overflow(lhs, rhs),
+            (OperatorMinus, true) => ins.ssub_overflow(lhs, rhs),
+            (OperatorMinus, false) => ins.usub_overflow(lhs, rhs),
+            (_, true) => ins.smul_overflow(lhs, rhs),
+            (_, false) => ins.umul_overflow(lhs, rhs),
+        };
+
+        let fits = self.builder.ins().icmp_imm(IntCC::Equal, overflows, 0);
+        self.lower_check(fits, loc, |t| {
+            let (left, mut args) = t.number_format(lhs, ValueType::Int(int))?;
+            let (right, right_args) = t.number_format(rhs, ValueType::Int(int))?;
+            args.extend(right_args);
+
+            let msg = format!(
+                "attempt to compute `{left} {} {right}`, which overflows `{}`",
+                op.to_string(),
+                int.name()
+            );
+            Ok((msg, args))
+        })?;
+
+        Ok(value)
+    }
+
+    fn lower_int_negation(
+        &mut self,
+        value: Value,
+        int: IntType,
+        loc: &Loc,
+    ) -> Result<Value, CodegenError> {
+        let min = self.int_const(int, int.min() as i64);
+        let fits = self.builder.ins().icmp(IntCC::NotEqual, value, min);
+        self.lower_check(fits, loc, |t| {
+            let (format, args) = t.number_format(value, ValueType::Int(int))?;
+            let msg = format!(
+                "attempt to negate `{format}`, which overflows `{}`",
+                int.name()
+            );
+            Ok((msg, args))
+        })?;
+
+        Ok(self.builder.ins().ineg(value))
+    }
+
+    // a zero divisor, or `MIN / -1` for signed integers, stops the program
+    // instead of trapping
+    fn lower_int_division(
+        &mut self,
+        op: &ComplexPunctuationKind,
+        lhs: Value,
+        rhs: Value,
+        int: IntType,
+        loc: &Loc,
+    ) -> Result<Value, CodegenError> {
+        let is_divide = *op == ComplexPunctuationKind::OperatorDivide;
+
+        let zero = self.int_const(int, 0);
+        let nonzero = self.builder.ins().icmp(IntCC::NotEqual, rhs, zero);
+        self.lower_check(nonzero, loc, |_| {
+            let msg = match is_divide {
+                true => "attempt to divide by zero",
+                false => "attempt to calculate the remainder with a divisor of zero",
+            };
+            Ok((msg.to_owned(), vec![]))
+        })?;
+
+        if is_divide && int.is_signed() {
+            let min = self.int_const(int, int.min() as i64);
+            let minus_one = self.int_const(int, -1);
+            let not_min = self.builder.ins().icmp(IntCC::NotEqual, lhs, min);
+            let not_minus_one = self.builder.ins().icmp(IntCC::NotEqual, rhs, minus_one);
+            let fits = self.builder.ins().bor(not_min, not_minus_one);
+            self.lower_check(fits, loc, |_| {
+                let msg = format!(
+                    "attempt to divide the minimum of `{}` by -1, which overflows",
+                    int.name()
+                );
+                Ok((msg, vec![]))
+            })?;
+        }
+
+        let value = match (is_divide, int.is_signed()) {
+            (true, true) => self.builder.ins().sdiv(lhs, rhs),
+            (true, false) => self.builder.ins().udiv(lhs, rhs),
+            (false, true) => self.builder.ins().srem(lhs, rhs),
+            (false, false) => self.builder.ins().urem(lhs, rhs),
+        };
+
+        Ok(value)
+    }
+
+    // unsigned exponents can't be negative, so only signed ones invert the
+    // result. a negative power of zero stops the program
+    fn lower_int_power(
+        &mut self,
+        base: Value,
+        exp: Value,
+        int: IntType,
+        loc: &Loc,
+    ) -> Result<Value, CodegenError> {
+        let ty = ValueType::Int(int).clif(self.ptr_type());
+
+        let header_block = self.builder.create_block();
+        let body_block = self.builder.create_block();
+        let exit_block = self.builder.create_block();
+        let negative_block = self.builder.create_block();
+        let merge_block = self.builder.create_block();
+
+        for _ in 0..3 {
+            self.builder.append_block_param(header_block, ty);
+        }
+        self.builder.append_block_param(exit_block, ty);
+        self.builder.append_block_param(merge_block, ty);
+
+        let one = self.int_const(int, 1);
+        let abs_exp = if int.is_signed() {
+            self.builder.ins().iabs(exp)
+        } else {
+            exp
+        };
+        self.builder.ins().jump(header_block, &[one, base, abs_exp]);
+        let original_base = base;
+
+        self.builder.switch_to_block(header_block);
+        let params = self.builder.block_params(header_block).to_vec();
+        let (result, base, exp_left) = (params[0], params[1], params[2]);
+        self.builder
+            .ins()
+            .brif(exp_left, body_block, &[], exit_block, &[result]);
+
+        self.builder.switch_to_block(body_block);
+        let odd = self.builder.ins().band_imm(exp_left, 1);
+        let multiplied = self.builder.ins().imul(result, base);
+        let result = self.builder.ins().select(odd, multiplied, result);
+        let base = self.builder.ins().imul(base, base);
+        let exp_left = self.builder.ins().ushr_imm(exp_left, 1);
+        self.builder
+            .ins()
+            .jump(header_block, &[result, base, exp_left]);
+
+        self.builder.switch_to_block(exit_block);
+        let result = self.builder.block_params(exit_block)[0];
+        let is_negative = match int.is_signed() {
+            true => self.builder.ins().icmp_imm(IntCC::SignedLessThan, exp, 0),
+            false => self.builder.ins().iconst(types::I8, 0),
+        };
+        self.builder
+            .ins()
+            .brif(is_negative, negative_block, &[], merge_block, &[result]);
+
+        self.builder.switch_to_block(negative_block);
+        let zero = self.int_const(int, 0);
+        let nonzero = self
+            .builder
+            .ins()
+            .icmp(IntCC::NotEqual, original_base, zero);
+        self.lower_check(nonzero, loc, |_| {
+            Ok((
+                "attempt to raise zero to a negative power".to_owned(),
+                vec![],
+            ))
+        })?;
+
+        // one divided by anything but 1 or -1 is 0, even when the positive
+        // power wrapped around
+        let one = self.int_const(int, 1);
+        let abs_result = self.builder.ins().iabs(result);
+        let is_unit = self.builder.ins().icmp(IntCC::Equal, abs_result, one);
+        let inverted = self.builder.ins().select(is_unit, result, zero);
+        self.builder.ins().jump(merge_block, &[inverted]);
+
+        self.builder.switch_to_block(merge_block);
+        Ok(self.builder.block_params(merge_block)[0])
+    }
+
+    // numbers convert into each other the way `PrimitiveType::can_cast`
+    // allows. floats saturate at the bounds of integer types
+    fn lower_cast(
+        &mut self,
+        value: &'a ExpressionNode,
+        node: &TypeNode,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let target = self.lower_type(node)?;
+        let (lowered, r#type) = self.lower_expression_as(value, target)?;
+
+        let valid = match (r#type.as_primitive(), target.as_primitive()) {
+            (Some(from), Some(to)) => from.can_cast(&to),
+            _ => r#type == target,
+        };
+
+        if !valid {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(format!(
+                    "cannot cast `{}` to `{}`",
+                    self.type_name(r#type),
+                    self.type_name(target)
+                ))
+                .ctx(error_ctx(self.src, &value.loc)));
+        }
+
+        let ty = target.clif(self.ptr_type());
+        let converted = match (r#type, target) {
+            (from, to) if from == to => lowered,
+            (ValueType::Int(from), ValueType::Int(to)) => self.convert_int(lowered, from, to),
+            (ValueType::Bool, ValueType::Int(to)) => self.convert_int(lowered, IntType::UInt8, to),
+            (ValueType::Char, ValueType::Int(to)) => self.convert_int(lowered, IntType::UInt32, to),
+            (ValueType::Int(from), ValueType::Char) => {
+                self.convert_int(lowered, from, IntType::UInt32)
+            }
+            (ValueType::Int(from), ValueType::Float(_)) if from.is_signed() => {
+                self.builder.ins().fcvt_from_sint(ty, lowered)
+            }
+            (ValueType::Int(_), ValueType::Float(_)) => {
+                self.builder.ins().fcvt_from_uint(ty, lowered)
+            }
+            (ValueType::Float(from), ValueType::Int(to)) => self.float_to_int(lowered, from, to),
+            (ValueType::Float(FloatType::Float32), ValueType::Float(_)) => {
+                self.builder.ins().fpromote(ty, lowered)
+            }
+            (ValueType::Float(_), ValueType::Float(_)) => self.builder.ins().fdemote(ty, lowered),
+            _ => unreachable!(),
+        };
+
+        Ok((converted, target))
+    }
+
+    // the bits are kept when the size stays the same, `-1 as uint8` is 255
+    pub(super) fn convert_int(&mut self, value: Value, from: IntType, to: IntType) -> Value {
+        let ty = ValueType::Int(to).clif(self.ptr_type());
+
+        match from.bits().cmp(&to.bits()) {
+            Ordering::Less if from.is_signed() => self.builder.ins().sextend(ty, value),
+            Ordering::Less => self.builder.ins().uextend(ty, value),
+            Ordering::Greater => self.builder.ins().ireduce(ty, value),
+            Ordering::Equal => value,
+        }
+    }
+
+    fn float_to_int(&mut self, value: Value, from: FloatType, to: IntType) -> Value {
+        let value = match from {
+            FloatType::Float32 => self.builder.ins().fpromote(types::F64, value),
+            FloatType::Float64 => value,
+        };
+
+        if to.bits() == 64 {
+            return match to.is_signed() {
+                true => self.builder.ins().fcvt_to_sint_sat(types::I64, value),
+                false => self.builder.ins().fcvt_to_uint_sat(types::I64, value),
+            };
+        }
+
+        // narrower types saturate at their own bounds rather than at the
+        // bounds of the 64-bit integer the float is converted to first
+        let wide = self.builder.ins().fcvt_to_sint_sat(types::I64, value);
+        let min = self.builder.ins().iconst(types::I64, to.min() as i64);
+        let max = self.builder.ins().iconst(types::I64, to.max() as i64);
+        let clamped = self.builder.ins().smax(wide, min);
+        let clamped = self.builder.ins().smin(clamped, max);
+
+        self.convert_int(clamped, IntType::Int64, to)
+    }
+
+    fn lower_interpolation(
+        &mut self,
+        parts: &'a [InterpolationPart],
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let mut result = None;
+
+        for part in parts.iter() {
+            let value = match part {
+                InterpolationPart::Literal(text) => self.string_value(text)?,
+                InterpolationPart::Expression(expr) => {
+                    let (value, r#type) = self.lower_expression(expr)?;
+                    self.lower_to_string(value, r#type, &expr.loc)?
+                }
+            };
+
+            result = Some(match result {
+                Some(left) => self.lower_concat(left, value)?,
+                None => value,
+            });
+        }
+
+        let value = match result {
+            Some(value) => value,
+            None => self.string_value("")?,
+        };
+
+        Ok((value, ValueType::String))
+    }
+
+    pub(super) fn lower_concat(
+        &mut self,
+        left: Value,
+        right: Value,
+    ) -> Result<Value, CodegenError> {
+        let left_len = self.call_import("strlen", &[left])?;
+        let right_len = self.call_import("strlen", &[right])?;
+
+        let len = self.builder.ins().iadd(left_len, right_len);
+        let size = self.builder.ins().iadd_imm(len, 1);
+        let dest = self.call_import("malloc", &[size])?;
+
+        let right_size = self.builder.ins().iadd_imm(right_len, 1);
+        let right_dest = self.builder.ins().iadd(dest, left_len);
+        self.call_import("memcpy", &[dest, left, left_len])?;
+        self.call_import("memcpy", &[right_dest, right, right_size])?;
+
+        Ok(dest)
+    }
+
+    pub(super) fn string_value(&mut self, value: &str) -> Result<Value, CodegenError> {
+        let data = if let Some(data) = self.strings.get(value) {
+            *data
+        } else {
+            let data = self
+                .module
+                .declare_anonymous_data(false, false)
+                .map_err(module_error)?;
+
+            let mut bytes = value.as_bytes().to_vec();
+            bytes.push(0);
+
+            let mut desc = DataDescription::new();
+            desc.define(bytes.into_boxed_slice());
+            self.module.define_data(data, &desc).map_err(module_error)?;
+
+            self.strings.insert(value.to_owned(), data);
+            data
+        };
+
+        Ok(self.global_addr(data))
+    }
+
+    pub(super) fn zero_value(&mut self, r#type: ValueType) -> Result<Value, CodegenError> {
+        Ok(match r#type {
+            ValueType::Int(int) => self.int_const(int, 0),
+            ValueType::Float(float) => self.float_const(float, 0.0),
+            ValueType::Char => self.builder.ins().iconst(types::I32, 0),
+            ValueType::Bool | ValueType::Void => self.builder.ins().iconst(types::I8, 0),
+            ValueType::String => self.string_value("")?,
+            ValueType::Struct(idx) => self.zero_struct(idx)?,
+            ValueType::Enum(idx) => self.zero_enum(idx)?,
+            ValueType::Array(idx) | ValueType::Tuple(idx) | ValueType::Dict(idx) => {
+                self.zero_collection(idx)?
+            }
+        })
+    }
+
+    pub(super) fn expect_type(
+        &self,
+        expected: ValueType,
+        found: ValueType,
+        loc: &Loc,
+    ) -> Result<(), CodegenError> {
+        if expected == found {
+            return Ok(());
+        }
+
+        Err(CodegenError::default()
+            .kind(CodegenErrorKind::MismatchedTypes)
+            .msg(format!(
+                "expected `{}`, found `{}`",
+                self.type_name(expected),
+                self.type_name(found)
+            ))
+            .ctx(error_ctx(self.src, loc)))
+    }
+
+    // items are found through what the resolver bound the name at `loc` to,
+    // locals by the name itself
+    pub(super) fn lookup(&self, name: &str, loc: &Loc) -> Option<Symbol<'a>> {
+        if let Some(path) = self.tree.item(self.namespace.node.source, loc) {
+            return self.globals.get(path).cloned();
+        }
+
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).cloned())
+    }
+
+    pub(super) fn lookup_expression(
+        &self,
+        expr: &ExpressionNode,
+    ) -> Result<Symbol<'a>, CodegenError> {
+        let Some(path) = expr.path() else {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::Unsupported)
+                .msg("member access is not supported yet")
+                .ctx(error_ctx(self.src, &expr.loc)));
+        };
+
+        self.lookup(&path, &expr.loc).ok_or_else(|| {
+            CodegenError::default()
+                .kind(CodegenErrorKind::Undefined)
+                .msg(format!("`{path}` is not defined"))
+                .ctx(error_ctx(self.src, &expr.loc))
+        })
+    }
+
+    fn global_type(
+        &self,
+        r#type: Option<ValueType>,
+        expr: &ExpressionNode,
+    ) -> Result<ValueType, CodegenError> {
+        r#type.ok_or_else(|| {
+            CodegenError::default()
+                .kind(CodegenErrorKind::Undefined)
+                .msg("global is used before it is initialized")
+                .ctx(error_ctx(self.src, &expr.loc))
+        })
+    }
+
+    fn operator_error(
+        &self,
+        op: &ComplexPunctuationKind,
+        r#type: ValueType,
+        loc: &Loc,
+    ) -> CodegenError {
+        CodegenError::default()
+            .kind(CodegenErrorKind::MismatchedTypes)
+            .msg(format!(
+                "operator `{}` cannot be applied to `{}`",
+                op.to_string(),
+                self.type_name(r#type)
+            ))
+            .ctx(error_ctx(self.src, loc))
+    }
+}
+
+fn int_condition(op: &ComplexPunctuationKind) -> Option<IntCC> {
+    match op {
+        ComplexPunctuationKind::OperatorEqual => Some(IntCC::Equal),
+        ComplexPunctuationKind::OperatorNotEqual => Some(IntCC::NotEqual),
+        ComplexPunctuationKind::AngleOpen => Some(IntCC::SignedLessThan),
+        ComplexPunctuationKind::AngleClose => Some(IntCC::SignedGreaterThan),
+        ComplexPunctuationKind::OperatorLessEqual => Some(IntCC::SignedLessThanOrEqual),
+        ComplexPunctuationKind::OperatorGreaterEqual => Some(IntCC::SignedGreaterThanOrEqual),
+        _ => None,
+    }
+}
+
+fn float_condition(op: &ComplexPunctuationKind) -> Option<FloatCC> {
+    match op {
+        ComplexPunctuationKind::OperatorEqual => Some(FloatCC::Equal),
+        ComplexPunctuationKind::OperatorNotEqual => Some(FloatCC::NotEqual),
+        ComplexPunctuationKind::AngleOpen => Some(FloatCC::LessThan),
+        ComplexPunctuationKind::AngleClose => Some(FloatCC::GreaterThan),
+        ComplexPunctuationKind::OperatorLessEqual => Some(FloatCC::LessThanOrEqual),
+        ComplexPunctuationKind::OperatorGreaterEqual => Some(FloatCC::GreaterThanOrEqual),
+        _ => None,
+    }
+}
@@ -0,0 +1,449 @@
+use std::collections::{HashMap, HashSet};
+
+use cranelift::{
+    codegen::ir::{FuncRef, Function, UserFuncName},
+    frontend::{FunctionBuilder, FunctionBuilderContext, Variable},
+    prelude::{types, AbiParam, InstBuilder, Signature, TrapCode, Type},
+};
+use cranelift_module::{DataId, FuncId, Linkage, Module};
+
+use crate::{
+    errors::codegen::{CodegenError, CodegenErrorKind},
+    frontend::{
+        checking::LiteralTypes,
+        parsing::ast::{
+            functions::{ArgumentNode, FunctionNode},
+            types::TypeNode,
+        },
+        resolving::ScopeTree,
+        Loc,
+    },
+    structs::ScriptSource,
+};
+
+use super::{
+    collections::CollectionType,
+    enums::EnumType,
+    error_ctx,
+    generics::{FunctionTemplate, Params},
+    loops::LoopFrame,
+    module_error,
+    namespaces::Namespace,
+    structs::StructType,
+    traits::{Methods, TraitType},
+    types::{TypeContext, ValueType},
+    ArgumentSymbol, Codegen, FunctionSymbol, PendingFunction, Scope, Symbol, VariableSymbol,
+};
+
+pub(super) struct FunctionTranslator<'a, 'b, M: Module> {
+    pub(super) builder: FunctionBuilder<'b>,
+    pub(super) module: &'b mut M,
+    pub(super) src: &'a ScriptSource<'a>,
+    pub(super) tree: &'a ScopeTree,
+    pub(super) sources: &'a [ScriptSource<'a>],
+    pub(super) literals: &'a LiteralTypes,
+    pub(super) namespace: &'b Namespace<'a>,
+    pub(super) namespaces: &'b [Namespace<'a>],
+
+    pub(super) globals: &'b mut Scope<'a>,
+    pub(super) structs: &'b mut Vec<StructType<'a>>,
+    pub(super) enums: &'b [EnumType<'a>],
+    pub(super) collections: &'b mut Vec<CollectionType>,
+    pub(super) traits: &'b [TraitType<'a>],
+    pub(super) methods: &'b Methods<'a>,
+    pub(super) impls: &'b HashSet<(ValueType, usize)>,
+    pub(super) templates: &'b mut Vec<FunctionTemplate<'a>>,
+    pub(super) instances: &'b mut HashMap<(usize, Vec<ValueType>), usize>,
+    pub(super) strings: &'b mut HashMap<String, DataId>,
+    pub(super) imports: &'b mut HashMap<&'static str, FuncId>,
+    pub(super) pending: &'b mut Vec<PendingFunction<'a>>,
+
+    pub(super) name: String,
+    pub(super) params: Params,
+    pub(super) scopes: Vec<Scope<'a>>,
+    pub(super) return_type: ValueType,
+    pub(super) return_var: Option<Variable>,
+    pub(super) loops: Vec<LoopFrame>,
+
+    func_refs: HashMap<FuncId, FuncRef>,
+    vars: usize,
+}
+
+impl<'a, M: Module> Codegen<'a, M> {
+    pub(super) fn declare_function(
+        &mut self,
+        func: &'a FunctionNode,
+        name: &str,
+        linkage: Linkage,
+        self_type: Option<ValueType>,
+    ) -> Result<FunctionSymbol<'a>, CodegenError> {
+        let src = self.src();
+        let args = lower_arguments(src, &func.args, self_type, &func.loc, |node| {
+            self.lower_type(node)
+        })?;
+        let r#type = match &func.r#type {
+            Some(r#type) => self.lower_type(r#type)?,
+            None => ValueType::Void,
+        };
+
+        declare_function(&mut self.module, args, r#type, name, linkage)
+    }
+
+    pub(super) fn lower_type(&mut self, node: &TypeNode) -> Result<ValueType, CodegenError> {
+        let current = self.current;
+        self.types().lower_type(current, &[], node)
+    }
+
+    pub(super) fn types(&mut self) -> TypeContext<'a, '_, M> {
+        TypeContext {
+            module: &mut self.module,
+            tree: self.tree,
+            sources: self.sources,
+            namespaces: &self.namespaces,
+            globals: &self.globals,
+            structs: &mut self.structs,
+            enums: &self.enums,
+            collections: &mut self.collections,
+            traits: &self.traits,
+            impls: &self.impls,
+            instances: &mut self.instances,
+        }
+    }
+
+    pub(super) fn define_function(
+        &mut self,
+        func: &'a FunctionNode,
+        symbol: &FunctionSymbol<'a>,
+        scope: Scope<'a>,
+        params: Params,
+    ) -> Result<(), CodegenError> {
+        let signature = make_signature(&self.module, symbol);
+        let name = self.module_name(symbol.id);
+
+        self.define_with(symbol.id, signature, symbol.r#type, scope, |t| {
+            t.params = params;
+
+            let block = t.builder.current_block().unwrap();
+            let params = t.builder.block_params(block).to_vec();
+
+            for (i, arg) in func.args.iter().enumerate() {
+                t.declare_variable(&arg.name, symbol.args[i].r#type, params[i]);
+            }
+
+            if let Some(return_var) = &func.return_var {
+                let value = t.zero_value(symbol.r#type)?;
+                let var = t.declare_variable(return_var, symbol.r#type, value);
+                t.return_var = Some(var);
+            }
+
+            t.name = name;
+            t.lower_body(&func.body)?;
+
+            if let Some(var) = t.return_var {
+                let value = t.builder.use_var(var);
+                t.builder.ins().return_(&[value]);
+            } else if symbol.r#type == ValueType::Void {
+                t.builder.ins().return_(&[]);
+            } else {
+                // the type checker makes every path return, this is only
+                // reached if it missed one
+                let reached = t.builder.ins().iconst(types::I8, 0);
+                t.lower_check(reached, &func.loc, |_| {
+                    let msg = format!("`{}` ended without returning a value", func.name);
+                    Ok((msg, vec![]))
+                })?;
+                t.builder.ins().trap(TrapCode::UnreachableCodeReached);
+            }
+
+            Ok(())
+        })
+    }
+
+    pub(super) fn define_pending(&mut self) {
+        while let Some(pending) = self.pending.pop() {
+            self.current = pending.namespace;
+            let result =
+                self.define_function(pending.node, &pending.symbol, pending.scope, pending.params);
+            self.push_error(result);
+        }
+    }
+
+    pub(super) fn define_with<F>(
+        &mut self,
+        id: FuncId,
+        signature: Signature,
+        return_type: ValueType,
+        scope: Scope<'a>,
+        lower: F,
+    ) -> Result<(), CodegenError>
+    where
+        F: FnOnce(&mut FunctionTranslator<'a, '_, M>) -> Result<(), CodegenError>,
+    {
+        self.ctx.func =
+            Function::with_name_signature(UserFuncName::user(0, id.as_u32()), signature);
+
+        let mut builder = FunctionBuilder::new(&mut self.ctx.func, &mut self.builder_ctx);
+        let entry = builder.create_block();
+        builder.append_block_params_for_function_params(entry);
+        builder.switch_to_block(entry);
+
+        let namespace = &self.namespaces[self.current];
+        let mut translator = FunctionTranslator {
+            builder,
+            module: &mut self.module,
+            src: &self.sources[namespace.node.source],
+            tree: self.tree,
+            sources: self.sources,
+            literals: self.literals,
+            namespace,
+            namespaces: &self.namespaces,
+
+            globals: &mut self.globals,
+            structs: &mut self.structs,
+            enums: &self.enums,
+            collections: &mut self.collections,
+            traits: &self.traits,
+            methods: &self.methods,
+            impls: &self.impls,
+            templates: &mut self.templates,
+            instances: &mut self.instances,
+            strings: &mut self.strings,
+            imports: &mut self.imports,
+            pending: &mut self.pending,
+
+            name: String::new(),
+            params: Vec::new(),
+            scopes: vec![scope],
+            return_type,
+            return_var: None,
+            loops: Vec::new(),
+
+            func_refs: HashMap::new(),
+            vars: 0,
+        };
+
+        if let Err(err) = lower(&mut translator) {
+            self.builder_ctx = FunctionBuilderContext::new();
+            self.module.clear_context(&mut self.ctx);
+            return Err(err);
+        }
+
+        translator.builder.seal_all_blocks();
+        translator.builder.finalize();
+
+        #[cfg(debug_assertions)]
+        self.ir.push(self.ctx.func.display().to_string());
+
+        let result = self.module.define_function(id, &mut self.ctx);
+        self.module.clear_context(&mut self.ctx);
+
+        result.map_err(module_error)
+    }
+
+    fn module_name(&self, id: FuncId) -> String {
+        self.module
+            .declarations()
+            .get_function_decl(id)
+            .linkage_name(id)
+            .to_string()
+    }
+}
+
+impl<'a, M: Module> FunctionTranslator<'a, '_, M> {
+    pub(super) fn ptr_type(&self) -> Type {
+        self.module.target_config().pointer_type()
+    }
+
+    pub(super) fn func_ref(&mut self, id: FuncId) -> FuncRef {
+        if let Some(func_ref) = self.func_refs.get(&id) {
+            return *func_ref;
+        }
+
+        let func_ref = self.module.declare_func_in_func(id, self.builder.func);
+        self.func_refs.insert(id, func_ref);
+
+        return func_ref;
+    }
+
+    pub(super) fn lower_type(&mut self, node: &TypeNode) -> Result<ValueType, CodegenError> {
+        let (namespace, params) = (self.namespace.index, self.params.clone());
+        self.types().lower_type(namespace, &params, node)
+    }
+
+    pub(super) fn types(&mut self) -> TypeContext<'a, '_, M> {
+        TypeContext {
+            module: self.module,
+            tree: self.tree,
+            sources: self.sources,
+            namespaces: self.namespaces,
+            globals: self.globals,
+            structs: self.structs,
+            enums: self.enums,
+            collections: self.collections,
+            traits: self.traits,
+            impls: self.impls,
+            instances: self.instances,
+        }
+    }
+
+    pub(super) fn declare_variable(
+        &mut self,
+        name: &str,
+        r#type: ValueType,
+        value: cranelift::prelude::Value,
+    ) -> Variable {
+        let var = Variable::from_u32(self.vars as u32);
+        self.vars += 1;
+
+        self.builder.declare_var(var, r#type.clif(self.ptr_type()));
+        self.builder.def_var(var, value);
+
+        self.scopes.last_mut().unwrap().insert(
+            name.to_owned(),
+            Symbol::Variable(VariableSymbol { var, r#type }),
+        );
+
+        return var;
+    }
+
+    pub(super) fn declare_nested_function(
+        &mut self,
+        func: &'a FunctionNode,
+    ) -> Result<(), CodegenError> {
+        if let Some(param) = func.generics.first() {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::Unsupported)
+                .msg("nested functions can't have type parameters yet")
+                .ctx(error_ctx(self.src, &param.loc)));
+        }
+
+        let mut name = format!("{}::{}", self.name, func.name);
+        let mut suffix = 1;
+        while self.module.get_name(&name).is_some() {
+            suffix += 1;
+            name = format!("{}::{}#{suffix}", self.name, func.name);
+        }
+
+        let src = self.src;
+        let args = lower_arguments(src, &func.args, None, &func.loc, |node| {
+            self.lower_type(node)
+        })?;
+        let r#type = match &func.r#type {
+            Some(r#type) => self.lower_type(r#type)?,
+            None => ValueType::Void,
+        };
+
+        let symbol = declare_function(self.module, args, r#type, &name, Linkage::Local)?;
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(func.name.clone(), Symbol::Function(symbol.clone()));
+
+        let mut scope = Scope::new();
+        for outer in self.scopes.iter() {
+            for (name, symbol) in outer {
+                if let Symbol::Function(_) = symbol {
+                    scope.insert(name.clone(), symbol.clone());
+                }
+            }
+        }
+
+        self.pending.push(PendingFunction {
+            node: func,
+            symbol,
+            scope,
+            namespace: self.namespace.index,
+            params: self.params.clone(),
+        });
+
+        Ok(())
+    }
+}
+
+pub(super) fn declare_function<'a, M: Module>(
+    module: &mut M,
+    args: Vec<ArgumentSymbol<'a>>,
+    r#type: ValueType,
+    name: &str,
+    linkage: Linkage,
+) -> Result<FunctionSymbol<'a>, CodegenError> {
+    let mut symbol = FunctionSymbol {
+        id: FuncId::from_u32(0),
+        args,
+        r#type,
+    };
+
+    let signature = make_signature(module, &symbol);
+    symbol.id = module
+        .declare_function(name, linkage, &signature)
+        .map_err(module_error)?;
+
+    return Ok(symbol);
+}
+
+// a receiver takes the type of whatever the method is implemented for, so
+// it's only allowed when `self_type` is given
+pub(super) fn lower_arguments<'a>(
+    src: &ScriptSource,
+    args: &'a [ArgumentNode],
+    self_type: Option<ValueType>,
+    loc: &Loc,
+    mut lower_type: impl FnMut(&TypeNode) -> Result<ValueType, CodegenError>,
+) -> Result<Vec<ArgumentSymbol<'a>>, CodegenError> {
+    let mut symbols = Vec::new();
+
+    for arg in args.iter() {
+        let r#type = if arg.is_receiver() {
+            self_type.ok_or_else(|| {
+                CodegenError::default()
+                    .kind(CodegenErrorKind::Unsupported)
+                    .msg("`self` can only be taken by methods")
+                    .ctx(error_ctx(src, loc))
+            })?
+        } else if let Some(r#type) = &arg.r#type {
+            lower_type(r#type)?
+        } else {
+            let default = arg.default.as_ref().unwrap();
+            ValueType::from_literal(default).map_err(|_| {
+                CodegenError::default()
+                    .kind(CodegenErrorKind::Unsupported)
+                    .msg(format!(
+                        "cannot infer type of argument `{}`, specify it explicitly",
+                        arg.name
+                    ))
+                    .ctx(error_ctx(src, &default.loc))
+            })?
+        };
+
+        if r#type == ValueType::Void {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(format!("argument `{}` cannot be of type void", arg.name))
+                .ctx(error_ctx(src, &arg.r#type.as_ref().unwrap().loc)));
+        }
+
+        symbols.push(ArgumentSymbol {
+            r#type,
+            default: arg.default.as_ref(),
+        });
+    }
+
+    return Ok(symbols);
+}
+
+pub(super) fn make_signature<M: Module>(module: &M, symbol: &FunctionSymbol) -> Signature {
+    let ptr = module.target_config().pointer_type();
+    let mut signature = module.make_signature();
+
+    for arg in symbol.args.iter() {
+        signature.params.push(AbiParam::new(arg.r#type.clif(ptr)));
+    }
+
+    if symbol.r#type != ValueType::Void {
+        signature
+            .returns
+            .push(AbiParam::new(symbol.r#type.clif(ptr)));
+    }
+
+    return signature;
+}
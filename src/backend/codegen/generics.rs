@@ -0,0 +1,507 @@
+use std::collections::HashMap;
+
+use cranelift::prelude::Value;
+use cranelift_module::{Linkage, Module};
+
+use crate::{
+    errors::codegen::{CodegenError, CodegenErrorKind},
+    frontend::{
+        parsing::ast::{
+            expressions::ExpressionNode,
+            functions::FunctionNode,
+            types::{GenericParamNode, TypeKind, TypeNode},
+        },
+        resolving::qualify,
+        Loc,
+    },
+    structs::ScriptSource,
+};
+
+use super::{
+    builtins::formatter_signature,
+    collections::CollectionKind,
+    enums::buildable_types,
+    error_ctx,
+    functions::{declare_function, lower_arguments, FunctionTranslator},
+    items::mangle,
+    module_error,
+    structs::{FieldSymbol, StructType},
+    types::{TypeContext, ValueType},
+    Codegen, FunctionSymbol, PendingFunction, Scope, Symbol,
+};
+
+// generic structs nested deeper than this are assumed to never stop growing,
+// like the fields of `struct A<T> { a: A<A<T>> }`
+const MAX_GENERIC_DEPTH: usize = 32;
+
+#[derive(Clone)]
+pub struct GenericParam {
+    pub name: String,
+    pub bounds: Vec<Bound>,
+}
+
+#[derive(Clone, Copy)]
+pub enum Bound {
+    Trait(usize),
+    // the comparisons builtin types support, since they can't implement traits
+    Eq,
+    Ord,
+}
+
+// a generic function is compiled again for every list of type arguments it's
+// called with
+pub struct FunctionTemplate<'a> {
+    pub name: String,
+    pub node: &'a FunctionNode,
+    pub generics: Vec<GenericParam>,
+    pub namespace: usize,
+    pub instances: HashMap<Vec<ValueType>, FunctionSymbol<'a>>,
+}
+
+pub type Params = Vec<(String, ValueType)>;
+
+impl<'a, M: Module> Codegen<'a, M> {
+    pub(super) fn declare_template(
+        &mut self,
+        func: &'a FunctionNode,
+        name: String,
+    ) -> Result<Symbol<'a>, CodegenError> {
+        let generics = self.lower_generics(&func.generics)?;
+
+        self.templates.push(FunctionTemplate {
+            name,
+            node: func,
+            generics,
+            namespace: self.current,
+            instances: HashMap::new(),
+        });
+
+        Ok(Symbol::Template(self.templates.len() - 1))
+    }
+
+    pub(super) fn lower_generics(
+        &self,
+        nodes: &[GenericParamNode],
+    ) -> Result<Vec<GenericParam>, CodegenError> {
+        let mut generics: Vec<GenericParam> = Vec::new();
+
+        for node in nodes.iter() {
+            if generics.iter().any(|other| other.name == node.name) {
+                return Err(CodegenError::default()
+                    .kind(CodegenErrorKind::Redefined)
+                    .msg(format!(
+                        "type parameter `{}` is defined multiple times",
+                        node.name
+                    ))
+                    .ctx(error_ctx(self.src(), &node.loc)));
+            }
+
+            let mut bounds = Vec::new();
+            for bound in node.bounds.iter() {
+                bounds.push(self.lower_bound(bound)?);
+            }
+
+            generics.push(GenericParam {
+                name: node.name.clone(),
+                bounds,
+            });
+        }
+
+        return Ok(generics);
+    }
+
+    fn lower_bound(&self, node: &TypeNode) -> Result<Bound, CodegenError> {
+        let err = match self.lookup_trait(node) {
+            Ok(idx) => return Ok(Bound::Trait(idx)),
+            Err(err) => err,
+        };
+
+        match node.name() {
+            Some("Eq") => Ok(Bound::Eq),
+            Some("Ord") => Ok(Bound::Ord),
+            _ => Err(err),
+        }
+    }
+}
+
+impl<'a, M: Module> TypeContext<'a, '_, M> {
+    pub fn instantiate_struct(
+        &mut self,
+        template: usize,
+        args: Vec<ValueType>,
+        namespace: usize,
+        loc: &Loc,
+    ) -> Result<usize, CodegenError> {
+        if let Some(idx) = self.instances.get(&(template, args.clone())) {
+            return Ok(*idx);
+        }
+
+        let name = format!(
+            "{}<{}>",
+            self.structs[template].name,
+            self.type_names(&args)
+        );
+        let src = self.src(namespace);
+
+        let generics = &self.structs[template].generics;
+        self.check_bounds(generics, &args, &self.structs[template].name, loc, src)?;
+
+        if args.iter().map(|arg| self.depth(*arg)).max().unwrap_or(0) >= MAX_GENERIC_DEPTH {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::Unsupported)
+                .msg(format!("`{name}` nests generic types too deeply"))
+                .ctx(error_ctx(src, loc)));
+        }
+
+        let signature = formatter_signature(self.module);
+        let formatter = self
+            .module
+            .declare_function(
+                &mangle(&qualify(&name, "<to_string>")),
+                Linkage::Local,
+                &signature,
+            )
+            .map_err(module_error)?;
+
+        let (node, owner) = (
+            self.structs[template].node,
+            self.structs[template].namespace,
+        );
+        let params: Params = node
+            .generics
+            .iter()
+            .map(|param| param.name.clone())
+            .zip(args.iter().copied())
+            .collect();
+
+        // the instance is registered before its fields, so that it can
+        // refer to itself
+        self.structs.push(StructType {
+            name: name.clone(),
+            node,
+            fields: Vec::new(),
+            namespace: owner,
+            formatter,
+            generics: Vec::new(),
+            template: Some(template),
+            args: args.clone(),
+        });
+
+        let idx = self.structs.len() - 1;
+        self.instances.insert((template, args), idx);
+
+        let mut fields: Vec<FieldSymbol<'a>> = Vec::new();
+        for field in node.fields.iter() {
+            let r#type = self.lower_type(owner, &params, &field.r#type)?;
+            if r#type == ValueType::Void {
+                return Err(CodegenError::default()
+                    .kind(CodegenErrorKind::MismatchedTypes)
+                    .msg(format!("field `{}` cannot be of type void", field.name))
+                    .ctx(error_ctx(self.src(owner), &field.r#type.loc)));
+            }
+
+            fields.push(FieldSymbol {
+                name: field.name.clone(),
+                r#type,
+                default: field.default.as_ref(),
+            });
+        }
+
+        self.structs[idx].fields = fields;
+
+        if !buildable_types(self.structs, self.enums, self.collections).0[idx] {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::Unsupported)
+                .msg(format!(
+                    "`{name}` contains itself, so none of its values can be built"
+                ))
+                .ctx(error_ctx(src, loc)));
+        }
+
+        Ok(idx)
+    }
+
+    // the type checker reports unimplemented bounds, except for types it
+    // can't tell, like the one of `self` in provided trait methods
+    pub fn check_bounds(
+        &self,
+        generics: &[GenericParam],
+        args: &[ValueType],
+        owner: &str,
+        loc: &Loc,
+        src: &ScriptSource,
+    ) -> Result<(), CodegenError> {
+        for (param, arg) in generics.iter().zip(args.iter()) {
+            for bound in param.bounds.iter() {
+                let (satisfied, bound_name) = match bound {
+                    Bound::Trait(idx) => (
+                        self.impls.contains(&(*arg, *idx)),
+                        self.traits[*idx].name.clone(),
+                    ),
+                    Bound::Eq => (
+                        matches!(
+                            arg,
+                            ValueType::Int(_)
+                                | ValueType::Float(_)
+                                | ValueType::Bool
+                                | ValueType::Char
+                                | ValueType::String
+                        ),
+                        "Eq".to_owned(),
+                    ),
+                    Bound::Ord => (
+                        matches!(
+                            arg,
+                            ValueType::Int(_) | ValueType::Float(_) | ValueType::Char
+                        ),
+                        "Ord".to_owned(),
+                    ),
+                };
+
+                if satisfied {
+                    continue;
+                }
+
+                return Err(CodegenError::default()
+                    .kind(CodegenErrorKind::MismatchedTypes)
+                    .msg(format!(
+                        "`{}` doesn't implement `{bound_name}`, which `{owner}` requires of `{}`",
+                        self.type_name(*arg),
+                        param.name
+                    ))
+                    .ctx(error_ctx(src, loc)));
+            }
+        }
+
+        Ok(())
+    }
+
+    // binds the type parameters appearing in `node` by matching it against the
+    // type of the value given for it
+    pub fn infer(
+        &self,
+        namespace: usize,
+        node: &TypeNode,
+        found: ValueType,
+        params: &mut [(String, Option<ValueType>)],
+    ) {
+        let generics = match (&node.kind, found) {
+            (TypeKind::Path { generics, .. }, _) => generics,
+            (TypeKind::Slice { element }, ValueType::Array(idx)) => {
+                let CollectionKind::Array(found) = self.collections[idx].kind else {
+                    unreachable!()
+                };
+
+                return self.infer(namespace, element, found, params);
+            }
+            (TypeKind::Tuple { elements }, ValueType::Tuple(idx)) => {
+                let CollectionKind::Tuple(found) = &self.collections[idx].kind else {
+                    unreachable!()
+                };
+
+                if elements.len() == found.len() {
+                    for (element, found) in elements.iter().zip(found.iter()) {
+                        self.infer(namespace, element, *found, params);
+                    }
+                }
+
+                return;
+            }
+            _ => return,
+        };
+
+        if let Some(name) = node.name()
+            && let Some((_, bound)) = params.iter_mut().find(|(param, _)| param == name)
+        {
+            bound.get_or_insert(found);
+            return;
+        }
+
+        if let ValueType::Dict(idx) = found
+            && let CollectionKind::Dict(key, value) = self.collections[idx].kind
+            && generics.len() == 2
+            && self.resolve(namespace, node).is_none()
+        {
+            self.infer(namespace, &generics[0], key, params);
+            self.infer(namespace, &generics[1], value, params);
+            return;
+        }
+
+        let ValueType::Struct(idx) = found else {
+            return;
+        };
+
+        let Some(Symbol::Struct(template)) = self.resolve(namespace, node) else {
+            return;
+        };
+
+        if self.structs[idx].template != Some(*template) {
+            return;
+        }
+
+        for (generic, arg) in generics.iter().zip(self.structs[idx].args.iter()) {
+            self.infer(namespace, generic, *arg, params);
+        }
+    }
+
+    pub fn type_names(&self, types: &[ValueType]) -> String {
+        types
+            .iter()
+            .map(|r#type| self.type_name(*r#type))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn depth(&self, r#type: ValueType) -> usize {
+        let args = match r#type {
+            ValueType::Struct(idx) => self.structs[idx].args.clone(),
+            ValueType::Array(idx) | ValueType::Tuple(idx) | ValueType::Dict(idx) => {
+                match &self.collections[idx].kind {
+                    CollectionKind::Array(element) => vec![*element],
+                    CollectionKind::Tuple(elements) => elements.clone(),
+                    CollectionKind::Dict(key, value) => vec![*key, *value],
+                }
+            }
+            _ => return 0,
+        };
+
+        1 + args.iter().map(|arg| self.depth(*arg)).max().unwrap_or(0)
+    }
+}
+
+impl<'a, M: Module> FunctionTranslator<'a, '_, M> {
+    pub(super) fn lower_template_call(
+        &mut self,
+        idx: usize,
+        args: &'a [ExpressionNode],
+        path: &str,
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let given = self.lower_values(args)?;
+
+        let (node, namespace) = (self.templates[idx].node, self.templates[idx].namespace);
+        let mut inferred = unbound(&node.generics);
+
+        for (arg, (_, r#type)) in node.args.iter().zip(given.iter()) {
+            if let Some(node) = &arg.r#type {
+                self.types().infer(namespace, node, *r#type, &mut inferred);
+            }
+        }
+
+        let types = self.bound_params(inferred, path, loc)?;
+        let func = self.instantiate_function(idx, types, loc)?;
+
+        self.call_lowered(&func, None, args, given, path, loc)
+    }
+
+    fn instantiate_function(
+        &mut self,
+        idx: usize,
+        args: Vec<ValueType>,
+        loc: &Loc,
+    ) -> Result<FunctionSymbol<'a>, CodegenError> {
+        if let Some(symbol) = self.templates[idx].instances.get(&args) {
+            return Ok(symbol.clone());
+        }
+
+        let template = &self.templates[idx];
+        let (node, namespace) = (template.node, template.namespace);
+        let (generics, owner) = (template.generics.clone(), template.name.clone());
+
+        let name = format!("{owner}<{}>", self.types().type_names(&args));
+        let src = self.src;
+        self.types()
+            .check_bounds(&generics, &args, &owner, loc, src)?;
+
+        let params: Params = generics
+            .iter()
+            .map(|param| param.name.clone())
+            .zip(args.iter().copied())
+            .collect();
+
+        let owner_src = self.types().src(namespace);
+        let symbol_args = lower_arguments(owner_src, &node.args, None, &node.loc, |node| {
+            self.types().lower_type(namespace, &params, node)
+        })?;
+        let r#type = match &node.r#type {
+            Some(r#type) => self.types().lower_type(namespace, &params, r#type)?,
+            None => ValueType::Void,
+        };
+
+        let symbol = declare_function(
+            self.module,
+            symbol_args,
+            r#type,
+            &mangle(&name),
+            Linkage::Local,
+        )?;
+
+        self.templates[idx].instances.insert(args, symbol.clone());
+        self.pending.push(PendingFunction {
+            node,
+            symbol: symbol.clone(),
+            scope: Scope::new(),
+            namespace,
+            params,
+        });
+
+        Ok(symbol)
+    }
+
+    // the type arguments of a generic struct literal come from its fields
+    pub(super) fn infer_struct(
+        &mut self,
+        template: usize,
+        given: &[(&str, ValueType)],
+        loc: &Loc,
+    ) -> Result<usize, CodegenError> {
+        let (node, namespace) = (
+            self.structs[template].node,
+            self.structs[template].namespace,
+        );
+        let mut inferred = unbound(&node.generics);
+
+        for (name, r#type) in given.iter() {
+            if let Some(field) = node.fields.iter().find(|field| field.name == *name) {
+                self.types()
+                    .infer(namespace, &field.r#type, *r#type, &mut inferred);
+            }
+        }
+
+        let path = self.structs[template].name.clone();
+        let args = self.bound_params(inferred, &path, loc)?;
+
+        let current = self.namespace.index;
+        self.types()
+            .instantiate_struct(template, args, current, loc)
+    }
+
+    fn bound_params(
+        &self,
+        inferred: Vec<(String, Option<ValueType>)>,
+        owner: &str,
+        loc: &Loc,
+    ) -> Result<Vec<ValueType>, CodegenError> {
+        let mut types = Vec::new();
+
+        for (name, r#type) in inferred {
+            let Some(r#type) = r#type else {
+                return Err(CodegenError::default()
+                    .kind(CodegenErrorKind::MismatchedTypes)
+                    .msg(format!("cannot infer type parameter `{name}` of `{owner}`"))
+                    .ctx(error_ctx(self.src, loc)));
+            };
+
+            types.push(r#type);
+        }
+
+        return Ok(types);
+    }
+}
+
+fn unbound(nodes: &[GenericParamNode]) -> Vec<(String, Option<ValueType>)> {
+    nodes
+        .iter()
+        .map(|param| (param.name.clone(), None))
+        .collect()
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use cranelift::prelude::{types, AbiParam, InstBuilder, MemFlags, Value};
+use cranelift_module::{DataDescription, DataId, Linkage, Module};
+
+use crate::{
+    errors::codegen::{CodegenError, CodegenErrorKind},
+    frontend::{
+        parsing::ast::{expressions::ExpressionNode, items::ItemKind, types::TypeNode},
+        resolving::qualify,
+    },
+};
+
+use super::{
+    error_ctx, functions::FunctionTranslator, module_error, types::ValueType, Codegen,
+    GlobalSymbol, Scope, Symbol,
+};
+
+impl<'a, M: Module> Codegen<'a, M> {
+    pub(super) fn declare_items(&mut self) {
+        for idx in 0..self.namespaces.len() {
+            self.current = idx;
+            self.declare_namespace_items();
+        }
+    }
+
+    fn declare_namespace_items(&mut self) {
+        let namespace = &self.namespaces[self.current];
+        let (node, path) = (namespace.node, namespace.path.clone());
+
+        for item in node.items.iter() {
+            let (name, result) = match &item.kind {
+                ItemKind::Function(func) if !func.generics.is_empty() => {
+                    let name = qualify(&path, &func.name);
+                    let result = self.declare_template(func, name.clone());
+                    (name, result)
+                }
+                ItemKind::Function(func) => {
+                    let name = qualify(&path, &func.name);
+                    let result = self
+                        .declare_function(func, &mangle(&name), Linkage::Local, None)
+                        .map(Symbol::Function);
+                    (name, result)
+                }
+                ItemKind::Static(node) => {
+                    let name = qualify(&path, &node.name);
+                    let result = self.declare_global(&name, node.r#type.as_ref());
+                    (name, result)
+                }
+                // constants were folded by the evaluator, uses of them are
+                // replaced with their values
+                ItemKind::Constant(node) => {
+                    let name = qualify(&path, &node.name);
+                    let Some(value) = self.constants.get(&name) else {
+                        continue;
+                    };
+
+                    (name, Ok(Symbol::Constant(value.clone())))
+                }
+                ItemKind::Struct(_)
+                | ItemKind::Enum(_)
+                | ItemKind::Trait(_)
+                | ItemKind::Impl(_)
+                | ItemKind::Use(_) => continue,
+            };
+
+            match result {
+                Ok(symbol) => self.insert_global(name, symbol),
+                Err(err) => self.errors.push(err),
+            }
+        }
+    }
+
+    pub(super) fn insert_global(&mut self, name: String, symbol: Symbol<'a>) {
+        if self.globals.contains_key(&name) {
+            self.errors.push(
+                CodegenError::default()
+                    .kind(CodegenErrorKind::Redefined)
+                    .msg(format!("`{name}` is defined multiple times")),
+            );
+            return;
+        }
+
+        self.globals.insert(name, symbol);
+    }
+
+    fn declare_global(
+        &mut self,
+        name: &str,
+        r#type: Option<&TypeNode>,
+    ) -> Result<Symbol<'a>, CodegenError> {
+        let r#type = match r#type {
+            Some(r#type) => Some(self.lower_type(r#type)?),
+            None => None,
+        };
+
+        let data = self
+            .module
+            .declare_data(&mangle(name), Linkage::Local, true, false)
+            .map_err(module_error)?;
+
+        let mut desc = DataDescription::new();
+        desc.define_zeroinit(8);
+        desc.set_align(8);
+        self.module.define_data(data, &desc).map_err(module_error)?;
+
+        Ok(Symbol::Global(GlobalSymbol { data, r#type }))
+    }
+
+    // every static gets its own initializer, called in the order the
+    // evaluator put them in
+    pub(super) fn define_initializers(&mut self) {
+        let mut statics = HashMap::new();
+        for (idx, namespace) in self.namespaces.iter().enumerate() {
+            for item in namespace.node.items.iter() {
+                if let ItemKind::Static(node) = &item.kind {
+                    statics.insert(qualify(&namespace.path, &node.name), (idx, node));
+                }
+            }
+        }
+
+        for name in self.statics.iter() {
+            let Some(&(idx, node)) = statics.get(name) else {
+                continue;
+            };
+
+            self.current = idx;
+            self.define_initializer(name, &node.value);
+        }
+    }
+
+    fn define_initializer(&mut self, name: &str, value: &'a ExpressionNode) {
+        let signature = self.module.make_signature();
+        let id = match self.module.declare_function(
+            &mangle(&qualify(name, "<init>")),
+            Linkage::Local,
+            &signature,
+        ) {
+            Ok(id) => id,
+            Err(err) => {
+                self.errors.push(module_error(err));
+                return;
+            }
+        };
+
+        let result = self.define_with(id, signature, ValueType::Void, Scope::new(), |t| {
+            t.lower_global_initializer(name, value)?;
+            t.builder.ins().return_(&[]);
+            Ok(())
+        });
+
+        self.push_error(result);
+        self.init.push(id);
+    }
+
+    pub(super) fn define_items(&mut self) {
+        for idx in 0..self.namespaces.len() {
+            self.current = idx;
+
+            let namespace = &self.namespaces[idx];
+            let (items, path) = (&namespace.node.items, namespace.path.clone());
+
+            for item in items.iter() {
+                let ItemKind::Function(func) = &item.kind else {
+                    continue;
+                };
+
+                let name = qualify(&path, &func.name);
+                let Some(Symbol::Function(symbol)) = self.globals.get(&name).cloned() else {
+                    continue;
+                };
+
+                let result = self.define_function(func, &symbol, Scope::new(), Vec::new());
+                self.push_error(result);
+            }
+        }
+    }
+
+    pub(super) fn define_entry(&mut self) {
+        let Some(Symbol::Function(main)) = self.globals.get("main").cloned() else {
+            self.errors.push(
+                CodegenError::default()
+                    .kind(CodegenErrorKind::Undefined)
+                    .msg("program does not have a `main` function"),
+            );
+            return;
+        };
+
+        let mut signature = self.module.make_signature();
+        signature.returns.push(AbiParam::new(types::I32));
+
+        let id = match self
+            .module
+            .declare_function("main", Linkage::Export, &signature)
+        {
+            Ok(id) => id,
+            Err(err) => {
+                self.errors.push(module_error(err));
+                return;
+            }
+        };
+
+        self.current = 0;
+        let init = self.init.clone();
+        let result = self.define_with(id, signature, ValueType::INT, Scope::new(), |t| {
+            for init in init {
+                let init_ref = t.func_ref(init);
+                t.builder.ins().call(init_ref, &[]);
+            }
+
+            let main_ref = t.func_ref(main.id);
+            let call = t.builder.ins().call(main_ref, &[]);
+
+            let code = if main.r#type == ValueType::INT {
+                let result = t.builder.inst_results(call)[0];
+                t.builder.ins().ireduce(types::I32, result)
+            } else {
+                t.builder.ins().iconst(types::I32, 0)
+            };
+
+            t.flush()?;
+            t.builder.ins().return_(&[code]);
+            Ok(())
+        });
+
+        self.push_error(result);
+        self.entry = Some(id);
+    }
+}
+
+impl<'a, M: Module> FunctionTranslator<'a, '_, M> {
+    fn lower_global_initializer(
+        &mut self,
+        name: &str,
+        value: &'a ExpressionNode,
+    ) -> Result<(), CodegenError> {
+        let Some(Symbol::Global(global)) = self.globals.get(name).cloned() else {
+            unreachable!();
+        };
+
+        let (value_val, value_type) = match global.r#type {
+            Some(r#type) => self.lower_expression_as(value, r#type)?,
+            None => self.lower_expression(value)?,
+        };
+
+        if let Some(r#type) = global.r#type {
+            self.expect_type(r#type, value_type, &value.loc)?;
+        } else if value_type == ValueType::Void {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(format!("`{name}` cannot be of type void"))
+                .ctx(error_ctx(self.src, &value.loc)));
+        }
+
+        self.globals.insert(
+            name.to_owned(),
+            Symbol::Global(GlobalSymbol {
+                data: global.data,
+                r#type: Some(value_type),
+            }),
+        );
+
+        self.store_global(global.data, value_val);
+        Ok(())
+    }
+
+    pub(super) fn load_global(&mut self, data: DataId, r#type: ValueType) -> Value {
+        let addr = self.global_addr(data);
+        let ty = r#type.clif(self.ptr_type());
+
+        self.builder.ins().load(ty, MemFlags::trusted(), addr, 0)
+    }
+
+    pub(super) fn store_global(&mut self, data: DataId, value: Value) {
+        let addr = self.global_addr(data);
+        self.builder
+            .ins()
+            .store(MemFlags::trusted(), value, addr, 0);
+    }
+
+    pub(super) fn global_addr(&mut self, data: DataId) -> Value {
+        let gv = self.module.declare_data_in_func(data, self.builder.func);
+        let ptr = self.ptr_type();
+
+        self.builder.ins().symbol_value(ptr, gv)
+    }
+}
+
+pub fn mangle(name: &str) -> String {
+    format!("meowind::{name}")
+}
@@ -0,0 +1,247 @@
+use cranelift::prelude::{types, Block, InstBuilder, IntCC, Value};
+use cranelift_module::Module;
+
+use crate::{
+    errors::codegen::{CodegenError, CodegenErrorKind},
+    frontend::{
+        parsing::ast::{
+            bodies::BodyNode,
+            expressions::{ExpressionKind, ExpressionNode},
+            statements::{ForLoopNode, LabelNode, LoopControlNode, WhileLoopKind, WhileLoopNode},
+        },
+        primitives::IntType,
+    },
+};
+
+use super::{error_ctx, functions::FunctionTranslator, types::ValueType, Scope};
+
+// where `continue` and `break` jump to in the loops around the current
+// statement, innermost last
+pub(super) struct LoopFrame {
+    label: Option<String>,
+    continue_block: Block,
+    break_block: Block,
+}
+
+// what a `for` loop counts through, the counter is the element for ranges and
+// the index for collections
+enum Iteration {
+    Range {
+        end: Value,
+        inclusive: bool,
+        int: IntType,
+    },
+    Collection {
+        value: Value,
+        idx: usize,
+    },
+}
+
+impl<'a, M: Module> FunctionTranslator<'a, '_, M> {
+    pub(super) fn lower_while_loop(&mut self, node: &'a WhileLoopNode) -> Result<(), CodegenError> {
+        let WhileLoopKind::While { cond, r#else } = &node.kind else {
+            return self.lower_body(&node.body);
+        };
+
+        let header_block = self.builder.create_block();
+        let body_block = self.builder.create_block();
+        let exit_block = self.builder.create_block();
+        let else_block = if r#else.is_some() {
+            self.builder.create_block()
+        } else {
+            exit_block
+        };
+
+        let first_cond = self.lower_condition(cond)?;
+        self.builder
+            .ins()
+            .brif(first_cond, body_block, &[], else_block, &[]);
+
+        self.builder.switch_to_block(header_block);
+        let cond = self.lower_condition(cond)?;
+        self.builder
+            .ins()
+            .brif(cond, body_block, &[], exit_block, &[]);
+
+        self.builder.switch_to_block(body_block);
+        self.lower_loop_body(&node.body, &node.label, header_block, exit_block)?;
+        self.builder.ins().jump(header_block, &[]);
+
+        if let Some(else_node) = r#else {
+            self.builder.switch_to_block(else_block);
+            self.lower_while_loop(else_node)?;
+            self.builder.ins().jump(exit_block, &[]);
+        }
+
+        self.builder.switch_to_block(exit_block);
+        Ok(())
+    }
+
+    pub(super) fn lower_for_loop(&mut self, node: &'a ForLoopNode) -> Result<(), CodegenError> {
+        let (start, iteration) = self.lower_iterable(&node.iterable)?;
+
+        let header_block = self.builder.create_block();
+        let body_block = self.builder.create_block();
+        let step_block = self.builder.create_block();
+        let exit_block = self.builder.create_block();
+        let counter = match iteration {
+            Iteration::Range { int, .. } => ValueType::Int(int).clif(self.ptr_type()),
+            Iteration::Collection { .. } => types::I64,
+        };
+        self.builder.append_block_param(header_block, counter);
+
+        self.builder.ins().jump(header_block, &[start]);
+
+        self.builder.switch_to_block(header_block);
+        let i = self.builder.block_params(header_block)[0];
+        let cond = match iteration {
+            Iteration::Range {
+                end,
+                inclusive,
+                int,
+            } => {
+                let cc = match (inclusive, int.is_signed()) {
+                    (true, true) => IntCC::SignedLessThanOrEqual,
+                    (true, false) => IntCC::UnsignedLessThanOrEqual,
+                    (false, true) => IntCC::SignedLessThan,
+                    (false, false) => IntCC::UnsignedLessThan,
+                };
+
+                self.builder.ins().icmp(cc, i, end)
+            }
+            Iteration::Collection { value, .. } => {
+                let len = self.iteration_len(value);
+                self.builder.ins().icmp(IntCC::SignedLessThan, i, len)
+            }
+        };
+        self.builder
+            .ins()
+            .brif(cond, body_block, &[], exit_block, &[]);
+
+        self.builder.switch_to_block(body_block);
+        let (element, element_type) = match iteration {
+            Iteration::Range { int, .. } => (i, ValueType::Int(int)),
+            Iteration::Collection { value, idx } => {
+                self.iteration_element(value, idx, i, &node.iterable.loc)?
+            }
+        };
+
+        self.scopes.push(Scope::new());
+        self.lower_irrefutable_pattern(&node.pattern, element, element_type)?;
+        self.lower_loop_body(&node.body, &node.label, step_block, exit_block)?;
+        self.scopes.pop();
+        self.builder.ins().jump(step_block, &[]);
+
+        // an inclusive range stops at its end rather than past it, so that
+        // ranges ending at the largest integer don't overflow
+        self.builder.switch_to_block(step_block);
+        let next = self.builder.ins().iadd_imm(i, 1);
+        match iteration {
+            Iteration::Range {
+                end,
+                inclusive: true,
+                ..
+            } => {
+                let last = self.builder.ins().icmp(IntCC::Equal, i, end);
+                self.builder
+                    .ins()
+                    .brif(last, exit_block, &[], header_block, &[next]);
+            }
+            _ => {
+                self.builder.ins().jump(header_block, &[next]);
+            }
+        }
+
+        self.builder.switch_to_block(exit_block);
+        Ok(())
+    }
+
+    // the first value of the counter and what it counts through
+    fn lower_iterable(
+        &mut self,
+        expr: &'a ExpressionNode,
+    ) -> Result<(Value, Iteration), CodegenError> {
+        if let ExpressionKind::Range {
+            start,
+            end,
+            inclusive,
+        } = &expr.kind
+        {
+            // the type checker made both bounds integers of the same type
+            let ((start_value, start_type), (end_value, _)) =
+                self.lower_operands(None, start, end)?;
+            let ValueType::Int(int) = start_type else {
+                unreachable!()
+            };
+
+            let iteration = Iteration::Range {
+                end: end_value,
+                inclusive: *inclusive,
+                int,
+            };
+            return Ok((start_value, iteration));
+        }
+
+        let (value, r#type) = self.lower_expression(expr)?;
+        let (ValueType::Array(idx) | ValueType::Dict(idx)) = r#type else {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(format!(
+                    "`{}` cannot be iterated over",
+                    self.type_name(r#type)
+                ))
+                .ctx(error_ctx(self.src, &expr.loc)));
+        };
+
+        let zero = self.builder.ins().iconst(types::I64, 0);
+        Ok((zero, Iteration::Collection { value, idx }))
+    }
+
+    fn lower_loop_body(
+        &mut self,
+        body: &'a BodyNode,
+        label: &Option<LabelNode>,
+        continue_block: Block,
+        break_block: Block,
+    ) -> Result<(), CodegenError> {
+        self.loops.push(LoopFrame {
+            label: label.as_ref().map(|label| label.name.clone()),
+            continue_block,
+            break_block,
+        });
+
+        let result = self.lower_body(body);
+        self.loops.pop();
+
+        result
+    }
+
+    pub(super) fn lower_loop_control(
+        &mut self,
+        node: &LoopControlNode,
+        is_break: bool,
+    ) -> Result<(), CodegenError> {
+        // the resolver made sure there's a loop to jump to. labels can be
+        // shadowed, the innermost loop with the label wins
+        let frame = match &node.label {
+            Some(label) => self
+                .loops
+                .iter()
+                .rev()
+                .find(|frame| frame.label.as_ref() == Some(&label.name))
+                .unwrap(),
+            None => self.loops.last().unwrap(),
+        };
+
+        let target = match is_break {
+            true => frame.break_block,
+            false => frame.continue_block,
+        };
+        self.builder.ins().jump(target, &[]);
+
+        let unreachable_block = self.builder.create_block();
+        self.builder.switch_to_block(unreachable_block);
+
+        Ok(())
+    }
+}
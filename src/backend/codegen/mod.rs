@@ -0,0 +1,247 @@
+pub mod bodies;
+pub mod builtins;
+pub mod collections;
+pub mod enums;
+pub mod expressions;
+pub mod functions;
+pub mod generics;
+pub mod items;
+pub mod loops;
+pub mod namespaces;
+pub mod patterns;
+pub mod statements;
+pub mod structs;
+pub mod traits;
+pub mod types;
+
+use std::collections::{HashMap, HashSet};
+
+use cranelift::{
+    codegen,
+    frontend::{FunctionBuilderContext, Variable},
+};
+use cranelift_module::{DataId, FuncId, Module};
+
+use crate::{
+    errors::{
+        codegen::{CodegenError, CodegenErrorKind},
+        context::{ErrorContext, ErrorContextBuilder},
+    },
+    frontend::{
+        checking::LiteralTypes,
+        evaluating::ConstValue,
+        parsing::ast::{
+            expressions::ExpressionNode, functions::FunctionNode, projects::ProjectNode,
+        },
+        resolving::ScopeTree,
+        Loc,
+    },
+    structs::ScriptSource,
+};
+
+use collections::CollectionType;
+use enums::EnumType;
+use generics::{FunctionTemplate, Params};
+use namespaces::Namespace;
+use structs::StructType;
+use traits::{Methods, TraitType};
+use types::ValueType;
+
+pub type Scope<'a> = HashMap<String, Symbol<'a>>;
+
+#[derive(Clone)]
+pub enum Symbol<'a> {
+    Variable(VariableSymbol),
+    Global(GlobalSymbol),
+    Constant(ConstValue),
+    Function(FunctionSymbol<'a>),
+    Struct(usize),
+    Enum(usize),
+    Variant(usize, usize),
+    Trait(usize),
+    Template(usize),
+}
+
+#[derive(Clone)]
+pub struct VariableSymbol {
+    pub var: Variable,
+    pub r#type: ValueType,
+}
+
+#[derive(Clone)]
+pub struct GlobalSymbol {
+    pub data: DataId,
+    pub r#type: Option<ValueType>,
+}
+
+#[derive(Clone)]
+pub struct FunctionSymbol<'a> {
+    pub id: FuncId,
+    pub args: Vec<ArgumentSymbol<'a>>,
+    pub r#type: ValueType,
+}
+
+#[derive(Clone)]
+pub struct ArgumentSymbol<'a> {
+    pub r#type: ValueType,
+    pub default: Option<&'a ExpressionNode>,
+}
+
+pub struct PendingFunction<'a> {
+    pub node: &'a FunctionNode,
+    pub symbol: FunctionSymbol<'a>,
+    pub scope: Scope<'a>,
+    pub namespace: usize,
+    pub params: Params,
+}
+
+pub struct Codegen<'a, M: Module> {
+    pub module: M,
+    pub errors: Vec<CodegenError>,
+    pub entry: Option<FuncId>,
+    // the lowered functions, only kept to be logged in debug builds
+    #[cfg(debug_assertions)]
+    pub ir: Vec<String>,
+
+    project: &'a ProjectNode,
+    // the symbols the resolver bound every name to
+    tree: &'a ScopeTree,
+    sources: &'a [ScriptSource<'a>],
+    // the types the type checker gave number literals
+    literals: &'a LiteralTypes,
+    // the values the evaluator folded constants to, by their full path
+    constants: &'a HashMap<String, ConstValue>,
+    // the full path of every static, in the order they're initialized
+    statics: &'a [String],
+    ctx: codegen::Context,
+    builder_ctx: FunctionBuilderContext,
+
+    globals: Scope<'a>,
+    structs: Vec<StructType<'a>>,
+    enums: Vec<EnumType<'a>>,
+    collections: Vec<CollectionType>,
+    traits: Vec<TraitType<'a>>,
+    methods: Methods<'a>,
+    impls: HashSet<(ValueType, usize)>,
+    templates: Vec<FunctionTemplate<'a>>,
+    instances: HashMap<(usize, Vec<ValueType>), usize>,
+    strings: HashMap<String, DataId>,
+    imports: HashMap<&'static str, FuncId>,
+    pending: Vec<PendingFunction<'a>>,
+    init: Vec<FuncId>,
+    namespaces: Vec<Namespace<'a>>,
+    current: usize,
+}
+
+impl<'a, M: Module> Codegen<'a, M> {
+    pub fn new(
+        module: M,
+        project: &'a ProjectNode,
+        tree: &'a ScopeTree,
+        sources: &'a [ScriptSource<'a>],
+        literals: &'a LiteralTypes,
+        constants: &'a HashMap<String, ConstValue>,
+        statics: &'a [String],
+    ) -> Codegen<'a, M> {
+        Codegen {
+            ctx: module.make_context(),
+            module,
+            errors: Vec::new(),
+            entry: None,
+            #[cfg(debug_assertions)]
+            ir: Vec::new(),
+
+            project,
+            tree,
+            sources,
+            literals,
+            constants,
+            statics,
+            builder_ctx: FunctionBuilderContext::new(),
+
+            globals: HashMap::new(),
+            structs: Vec::new(),
+            enums: Vec::new(),
+            collections: Vec::new(),
+            traits: Vec::new(),
+            methods: HashMap::new(),
+            impls: HashSet::new(),
+            templates: Vec::new(),
+            instances: HashMap::new(),
+            strings: HashMap::new(),
+            imports: HashMap::new(),
+            pending: Vec::new(),
+            init: Vec::new(),
+            namespaces: Vec::new(),
+            current: 0,
+        }
+    }
+
+    pub fn generate(
+        module: M,
+        project: &'a ProjectNode,
+        tree: &'a ScopeTree,
+        sources: &'a [ScriptSource<'a>],
+        literals: &'a LiteralTypes,
+        constants: &'a HashMap<String, ConstValue>,
+        statics: &'a [String],
+    ) -> Codegen<'a, M> {
+        let mut codegen =
+            Codegen::new(module, project, tree, sources, literals, constants, statics);
+        codegen.process();
+
+        return codegen;
+    }
+
+    fn process(&mut self) {
+        self.collect_namespaces(&self.project.root, &[]);
+
+        self.declare_structs();
+        self.declare_enums();
+        self.declare_traits();
+        self.define_struct_generics();
+        self.collect_impls();
+        self.define_structs();
+        self.define_enums();
+        self.define_traits();
+        self.check_recursive_types();
+        self.declare_formatters();
+        self.declare_items();
+        self.declare_impls();
+        if !self.errors.is_empty() {
+            return;
+        }
+
+        self.define_initializers();
+        self.define_items();
+        self.define_pending();
+        self.define_formatters();
+        self.define_collection_formatters();
+
+        if self.errors.is_empty() {
+            self.define_entry();
+        }
+    }
+
+    fn src(&self) -> &'a ScriptSource<'a> {
+        &self.sources[self.namespaces[self.current].node.source]
+    }
+
+    fn push_error(&mut self, result: Result<(), CodegenError>) {
+        if let Err(err) = result {
+            self.errors.push(err);
+        }
+    }
+}
+
+pub fn error_ctx(src: &ScriptSource, loc: &Loc) -> ErrorContext {
+    ErrorContextBuilder::loc(loc)
+        .from_src_and_ln(src, loc.ln)
+        .build()
+}
+
+pub fn module_error<T: ToString>(err: T) -> CodegenError {
+    CodegenError::default()
+        .kind(CodegenErrorKind::Module)
+        .msg(err.to_string())
+}
@@ -0,0 +1,63 @@
+use cranelift_module::Module;
+
+use crate::{
+    errors::codegen::{CodegenError, CodegenErrorKind},
+    frontend::{
+        parsing::ast::{items::UseImport, namespaces::NamespaceNode},
+        resolving::{candidates, ScopeTree},
+    },
+};
+
+use super::Codegen;
+
+pub struct Namespace<'a> {
+    pub node: &'a NamespaceNode,
+    pub path: String,
+    pub imports: Vec<UseImport>,
+    pub index: usize,
+}
+
+impl Namespace<'_> {
+    // every full path a name written inside this namespace can refer to
+    pub fn candidates(&self, name: &str) -> Vec<String> {
+        candidates(&self.path, &self.imports, name)
+    }
+}
+
+impl<'a, M: Module> Codegen<'a, M> {
+    pub(super) fn collect_namespaces(&mut self, node: &'a NamespaceNode, inherited: &[UseImport]) {
+        let mut imports = inherited.to_vec();
+        imports.extend(node.imports());
+
+        self.namespaces.push(Namespace {
+            node,
+            path: node.path(),
+            imports: imports.clone(),
+            index: self.namespaces.len(),
+        });
+
+        // namespaces declared in the same file see the imports around them
+        for child in node.children.iter() {
+            let inherited = if child.source == node.source {
+                imports.as_slice()
+            } else {
+                &[]
+            };
+
+            self.collect_namespaces(child, inherited);
+        }
+    }
+}
+
+// names are checked by the resolver, only methods called on values are left
+pub fn private_error(tree: &ScopeTree, symbol: usize) -> CodegenError {
+    let symbol = &tree.symbols[symbol];
+    let owner = match tree.namespace_path(symbol.scope) {
+        "" => "the root namespace",
+        owner => owner,
+    };
+
+    CodegenError::default()
+        .kind(CodegenErrorKind::Private)
+        .msg(format!("`{}` is private to `{owner}`", symbol.name))
+}
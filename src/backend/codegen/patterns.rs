@@ -0,0 +1,634 @@
+use cranelift::prelude::{Block, FloatCC, InstBuilder, IntCC, TrapCode, Value};
+use cranelift_module::Module;
+
+use crate::{
+    errors::codegen::{CodegenError, CodegenErrorKind},
+    frontend::{
+        lexing::LiteralKind,
+        parsing::ast::{
+            expressions::ExpressionNode,
+            items::VariantKind,
+            patterns::{MatchArmNode, PatternKind, PatternNode, PatternPayload},
+            statements::MatchNode,
+        },
+        primitives::{FloatType, IntType},
+        Loc,
+    },
+};
+
+use super::{
+    collections::CollectionKind, error_ctx, functions::FunctionTranslator, types::ValueType, Scope,
+    Symbol,
+};
+
+// patterns with their names resolved and their types checked
+enum Pattern {
+    Wildcard,
+    Binding(String),
+    Literal(Constant),
+    // omitted struct fields are wildcards, so `fields` always follows the
+    // order of the variant fields
+    Variant {
+        variant: usize,
+        fields: Vec<Pattern>,
+    },
+    Tuple(Vec<Pattern>),
+    // the bindings every alternative shares, sorted by name
+    Or {
+        alternatives: Vec<Pattern>,
+        bindings: Bindings,
+    },
+}
+
+enum Constant {
+    Int(IntType, i64),
+    Float(FloatType, f64),
+    Char(char),
+    Bool(bool),
+    String(String),
+}
+
+// the names a pattern binds, with their types
+type Bindings = Vec<(String, ValueType)>;
+
+impl<'a, M: Module> FunctionTranslator<'a, '_, M> {
+    pub(super) fn lower_match_statement(
+        &mut self,
+        node: &'a MatchNode,
+    ) -> Result<(), CodegenError> {
+        let (value, r#type) = self.lower_expression(&node.value)?;
+        let patterns = self.resolve_arms(&node.arms, r#type)?;
+
+        let merge_block = self.builder.create_block();
+
+        for (arm, (pattern, bindings)) in node.arms.iter().zip(patterns.iter()) {
+            let next_block = self.lower_arm(arm, pattern, bindings, value, r#type)?;
+            self.lower_body(&arm.body)?;
+            self.scopes.pop();
+
+            self.builder.ins().jump(merge_block, &[]);
+            self.builder.switch_to_block(next_block);
+        }
+
+        self.builder.ins().trap(TrapCode::UnreachableCodeReached);
+        self.builder.switch_to_block(merge_block);
+
+        Ok(())
+    }
+
+    pub(super) fn lower_match_expression(
+        &mut self,
+        node: &'a ExpressionNode,
+        arms: &'a [MatchArmNode<ExpressionNode>],
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let (value, r#type) = self.lower_expression(node)?;
+        let patterns = self.resolve_arms(arms, r#type)?;
+
+        let merge_block = self.builder.create_block();
+        let mut result_type = None;
+
+        for (arm, (pattern, bindings)) in arms.iter().zip(patterns.iter()) {
+            let next_block = self.lower_arm(arm, pattern, bindings, value, r#type)?;
+            let (arm_value, arm_type) = match result_type {
+                Some(expected) => self.lower_expression_as(&arm.body, expected)?,
+                None => self.lower_expression(&arm.body)?,
+            };
+            self.scopes.pop();
+
+            match result_type {
+                Some(expected) => self.expect_type(expected, arm_type, &arm.body.loc)?,
+                None => {
+                    let ty = arm_type.clif(self.ptr_type());
+                    self.builder.append_block_param(merge_block, ty);
+                    result_type = Some(arm_type);
+                }
+            }
+
+            self.builder.ins().jump(merge_block, &[arm_value]);
+            self.builder.switch_to_block(next_block);
+        }
+
+        self.builder.ins().trap(TrapCode::UnreachableCodeReached);
+        self.builder.switch_to_block(merge_block);
+
+        let Some(result_type) = result_type else {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg("match without arms has no value")
+                .ctx(error_ctx(self.src, &node.loc)));
+        };
+
+        Ok((self.builder.block_params(merge_block)[0], result_type))
+    }
+
+    fn resolve_arms<T>(
+        &self,
+        arms: &'a [MatchArmNode<T>],
+        r#type: ValueType,
+    ) -> Result<Vec<(Pattern, Bindings)>, CodegenError> {
+        let mut patterns = Vec::new();
+
+        for arm in arms.iter() {
+            let mut bindings = Vec::new();
+            let pattern = self.resolve_pattern(&arm.pattern, r#type, &mut bindings)?;
+            patterns.push((pattern, bindings));
+        }
+
+        Ok(patterns)
+    }
+
+    // tests the pattern and binds its names in a new scope, which the caller
+    // pops after lowering the arm body. returns the block of the next arm
+    fn lower_arm<T>(
+        &mut self,
+        arm: &'a MatchArmNode<T>,
+        pattern: &Pattern,
+        bindings: &[(String, ValueType)],
+        value: Value,
+        r#type: ValueType,
+    ) -> Result<Block, CodegenError> {
+        let next_block = self.builder.create_block();
+
+        let mut bound = Vec::new();
+        self.lower_pattern(pattern, value, r#type, next_block, &mut bound)?;
+
+        self.scopes.push(Scope::new());
+        for (name, r#type) in bindings.iter() {
+            let (_, value) = bound.iter().find(|(bound, _)| bound == name).unwrap();
+            self.declare_variable(name, *r#type, *value);
+        }
+
+        if let Some(guard) = &arm.guard {
+            let cond = self.lower_condition(guard)?;
+
+            let body_block = self.builder.create_block();
+            self.builder
+                .ins()
+                .brif(cond, body_block, &[], next_block, &[]);
+            self.builder.switch_to_block(body_block);
+        }
+
+        Ok(next_block)
+    }
+
+    // binds the names of a pattern that has to match every value, like the
+    // one of a `for` loop, in the current scope
+    pub(super) fn lower_irrefutable_pattern(
+        &mut self,
+        node: &PatternNode,
+        value: Value,
+        r#type: ValueType,
+    ) -> Result<(), CodegenError> {
+        let mut bindings = Vec::new();
+        let pattern = self.resolve_pattern(node, r#type, &mut bindings)?;
+
+        // or-patterns still test their alternatives, the last one can't fail
+        let fail_block = self.builder.create_block();
+        let mut bound = Vec::new();
+        self.lower_pattern(&pattern, value, r#type, fail_block, &mut bound)?;
+
+        let bound_block = self.builder.create_block();
+        self.builder.ins().jump(bound_block, &[]);
+
+        self.builder.switch_to_block(fail_block);
+        self.builder.ins().trap(TrapCode::UnreachableCodeReached);
+        self.builder.switch_to_block(bound_block);
+
+        for (name, r#type) in bindings.iter() {
+            let (_, value) = bound.iter().find(|(bound, _)| bound == name).unwrap();
+            self.declare_variable(name, *r#type, *value);
+        }
+
+        Ok(())
+    }
+
+    fn resolve_pattern(
+        &self,
+        node: &PatternNode,
+        r#type: ValueType,
+        bindings: &mut Bindings,
+    ) -> Result<Pattern, CodegenError> {
+        match &node.kind {
+            PatternKind::Wildcard => Ok(Pattern::Wildcard),
+            PatternKind::Binding { name } => {
+                // unit variants brought in with `use` match instead of binding
+                if let ValueType::Enum(idx) = r#type
+                    && let Some(Symbol::Variant(enum_idx, variant)) = self.lookup(name, &node.loc)
+                    && enum_idx == idx
+                {
+                    return self.resolve_variant(
+                        idx,
+                        variant,
+                        &PatternPayload::Unit,
+                        node,
+                        bindings,
+                    );
+                }
+
+                if bindings.iter().any(|(bound, _)| bound == name) {
+                    return Err(CodegenError::default()
+                        .kind(CodegenErrorKind::Redefined)
+                        .msg(format!(
+                            "`{name}` is bound multiple times in the same pattern"
+                        ))
+                        .ctx(error_ctx(self.src, &node.loc)));
+                }
+
+                bindings.push((name.clone(), r#type));
+                Ok(Pattern::Binding(name.clone()))
+            }
+            PatternKind::Literal {
+                kind,
+                value,
+                negative,
+            } => {
+                let (constant, found) =
+                    self.resolve_literal(kind, value, *negative, r#type, &node.loc)?;
+                self.expect_type(r#type, found, &node.loc)?;
+
+                Ok(Pattern::Literal(constant))
+            }
+            PatternKind::Variant { path, payload } => {
+                let path = path.join("::");
+
+                let Some(Symbol::Variant(idx, variant)) = self.lookup(&path, &node.loc) else {
+                    return Err(CodegenError::default()
+                        .kind(CodegenErrorKind::Undefined)
+                        .msg(format!("`{path}` is not an enum variant"))
+                        .ctx(error_ctx(self.src, &node.loc)));
+                };
+
+                self.expect_type(r#type, ValueType::Enum(idx), &node.loc)?;
+                self.resolve_variant(idx, variant, payload, node, bindings)
+            }
+            PatternKind::Tuple(nodes) => {
+                let elements = match r#type {
+                    ValueType::Tuple(idx) => self.tuple_elements(idx),
+                    _ => {
+                        return Err(CodegenError::default()
+                            .kind(CodegenErrorKind::MismatchedTypes)
+                            .msg(format!(
+                                "expected `{}`, found a tuple",
+                                self.type_name(r#type)
+                            ))
+                            .ctx(error_ctx(self.src, &node.loc)));
+                    }
+                };
+
+                if nodes.len() != elements.len() {
+                    return Err(CodegenError::default()
+                        .kind(CodegenErrorKind::MismatchedTypes)
+                        .msg(format!(
+                            "`{}` has {} elements, but the pattern has {}",
+                            self.type_name(r#type),
+                            elements.len(),
+                            nodes.len()
+                        ))
+                        .ctx(error_ctx(self.src, &node.loc)));
+                }
+
+                let mut fields = Vec::new();
+                for (pattern, element) in nodes.iter().zip(elements) {
+                    fields.push(self.resolve_pattern(pattern, element, bindings)?);
+                }
+
+                Ok(Pattern::Tuple(fields))
+            }
+            PatternKind::Or(nodes) => {
+                let mut alternatives = Vec::new();
+                let mut shared: Option<Bindings> = None;
+
+                for alternative in nodes.iter() {
+                    let mut own = Vec::new();
+                    alternatives.push(self.resolve_pattern(alternative, r#type, &mut own)?);
+                    own.sort_by(|a, b| a.0.cmp(&b.0));
+
+                    let Some(shared) = &shared else {
+                        shared = Some(own);
+                        continue;
+                    };
+
+                    // the type checker made sure they bind the same names,
+                    // but not their types when it couldn't tell them
+                    for ((name, expected), (_, found)) in shared.iter().zip(own.iter()) {
+                        if expected != found {
+                            return Err(CodegenError::default()
+                                .kind(CodegenErrorKind::MismatchedTypes)
+                                .msg(format!(
+                                    "`{name}` is `{}` in one alternative and `{}` in another",
+                                    self.type_name(*expected),
+                                    self.type_name(*found)
+                                ))
+                                .ctx(error_ctx(self.src, &alternative.loc)));
+                        }
+                    }
+                }
+
+                let shared = shared.unwrap_or_default();
+                for (name, r#type) in shared.iter() {
+                    if bindings.iter().any(|(bound, _)| bound == name) {
+                        return Err(CodegenError::default()
+                            .kind(CodegenErrorKind::Redefined)
+                            .msg(format!(
+                                "`{name}` is bound multiple times in the same pattern"
+                            ))
+                            .ctx(error_ctx(self.src, &node.loc)));
+                    }
+
+                    bindings.push((name.clone(), *r#type));
+                }
+
+                Ok(Pattern::Or {
+                    alternatives,
+                    bindings: shared,
+                })
+            }
+        }
+    }
+
+    fn resolve_variant(
+        &self,
+        idx: usize,
+        variant: usize,
+        payload: &PatternPayload,
+        node: &PatternNode,
+        bindings: &mut Bindings,
+    ) -> Result<Pattern, CodegenError> {
+        let layout = &self.enums[idx];
+        let symbol = &layout.variants[variant];
+        let name = layout.variant_name(variant);
+
+        let fields = match (symbol.kind, payload) {
+            (VariantKind::Unit, PatternPayload::Unit) => Vec::new(),
+            (VariantKind::Tuple(_), PatternPayload::Tuple(patterns)) => {
+                if patterns.len() != symbol.fields.len() {
+                    return Err(CodegenError::default()
+                        .kind(CodegenErrorKind::MismatchedTypes)
+                        .msg(format!(
+                            "`{name}` has {} fields, but the pattern has {}",
+                            symbol.fields.len(),
+                            patterns.len()
+                        ))
+                        .ctx(error_ctx(self.src, &node.loc)));
+                }
+
+                let mut fields = Vec::new();
+                for (pattern, field) in patterns.iter().zip(symbol.fields.iter()) {
+                    fields.push(self.resolve_pattern(pattern, field.r#type, bindings)?);
+                }
+
+                fields
+            }
+            (VariantKind::Struct(_), PatternPayload::Struct { fields, rest }) => {
+                let mut resolved: Vec<Option<Pattern>> =
+                    symbol.fields.iter().map(|_| None).collect();
+
+                for field in fields.iter() {
+                    let Some((i, symbol)) = super::structs::find_field(&symbol.fields, &field.name)
+                    else {
+                        return Err(self.unknown_field_error(&name, &field.name, &field.loc));
+                    };
+
+                    if resolved[i].is_some() {
+                        return Err(CodegenError::default()
+                            .kind(CodegenErrorKind::Redefined)
+                            .msg(format!("field `{}` is matched multiple times", field.name))
+                            .ctx(error_ctx(self.src, &field.loc)));
+                    }
+
+                    resolved[i] =
+                        Some(self.resolve_pattern(&field.pattern, symbol.r#type, bindings)?);
+                }
+
+                let missing: Vec<String> = symbol
+                    .fields
+                    .iter()
+                    .zip(resolved.iter())
+                    .filter(|(_, pattern)| pattern.is_none())
+                    .map(|(field, _)| format!("`{}`", field.name))
+                    .collect();
+
+                if !rest && !missing.is_empty() {
+                    return Err(CodegenError::default()
+                        .kind(CodegenErrorKind::MismatchedTypes)
+                        .msg(format!(
+                            "pattern does not mention {}, use `..` to ignore the rest",
+                            missing.join(", ")
+                        ))
+                        .ctx(error_ctx(self.src, &node.loc)));
+                }
+
+                resolved
+                    .into_iter()
+                    .map(|pattern| pattern.unwrap_or(Pattern::Wildcard))
+                    .collect()
+            }
+            (kind, _) => {
+                let form = match kind {
+                    VariantKind::Unit => name.clone(),
+                    VariantKind::Tuple(_) => format!("{name}(..)"),
+                    VariantKind::Struct(_) => format!("{name} {{ .. }}"),
+                };
+
+                return Err(CodegenError::default()
+                    .kind(CodegenErrorKind::MismatchedTypes)
+                    .msg(format!("`{name}` is matched with `{form}`"))
+                    .ctx(error_ctx(self.src, &node.loc)));
+            }
+        };
+
+        Ok(Pattern::Variant { variant, fields })
+    }
+
+    // number literals take the numeric type of the value they're matched
+    // against
+    fn resolve_literal(
+        &self,
+        kind: &LiteralKind,
+        value: &str,
+        negative: bool,
+        expected: ValueType,
+        loc: &Loc,
+    ) -> Result<(Constant, ValueType), CodegenError> {
+        let sign = if negative { "-" } else { "" };
+        let range_error = |r#type: ValueType| {
+            CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(format!(
+                    "literal `{sign}{value}` does not fit in `{}`",
+                    r#type.to_string()
+                ))
+                .ctx(error_ctx(self.src, loc))
+        };
+
+        match kind {
+            LiteralKind::Integer => {
+                let int = match expected {
+                    ValueType::Int(int) => int,
+                    _ => IntType::Int64,
+                };
+
+                match int.parse(value, negative) {
+                    Some(value) => Ok((Constant::Int(int, value), ValueType::Int(int))),
+                    None => Err(range_error(ValueType::Int(int))),
+                }
+            }
+            LiteralKind::Float => {
+                let float = match expected {
+                    ValueType::Float(float) => float,
+                    _ => FloatType::Float64,
+                };
+
+                match float.parse(value, negative) {
+                    Some(value) => Ok((Constant::Float(float, value), ValueType::Float(float))),
+                    None => Err(range_error(ValueType::Float(float))),
+                }
+            }
+            LiteralKind::Char => Ok((
+                Constant::Char(value.chars().next().unwrap()),
+                ValueType::Char,
+            )),
+            LiteralKind::Boolean => Ok((Constant::Bool(value == "true"), ValueType::Bool)),
+            LiteralKind::String => Ok((Constant::String(value.to_owned()), ValueType::String)),
+        }
+    }
+
+    // falls through to the current block when the value matches and jumps to
+    // `fail_block` otherwise
+    fn lower_pattern(
+        &mut self,
+        pattern: &Pattern,
+        value: Value,
+        r#type: ValueType,
+        fail_block: Block,
+        bound: &mut Vec<(String, Value)>,
+    ) -> Result<(), CodegenError> {
+        match pattern {
+            Pattern::Wildcard => {}
+            Pattern::Binding(name) => bound.push((name.clone(), value)),
+            Pattern::Literal(constant) => {
+                let cond = match constant {
+                    Constant::Int(int, constant) => {
+                        let constant = self.int_const(*int, *constant);
+                        self.builder.ins().icmp(IntCC::Equal, value, constant)
+                    }
+                    Constant::Float(float, constant) => {
+                        let constant = self.float_const(*float, *constant);
+                        self.builder.ins().fcmp(FloatCC::Equal, value, constant)
+                    }
+                    Constant::Char(char) => {
+                        self.builder
+                            .ins()
+                            .icmp_imm(IntCC::Equal, value, *char as i64)
+                    }
+                    Constant::Bool(bool) => {
+                        self.builder
+                            .ins()
+                            .icmp_imm(IntCC::Equal, value, *bool as i64)
+                    }
+                    Constant::String(string) => {
+                        let string = self.string_value(string)?;
+                        let cmp = self.call_import("strcmp", &[value, string])?;
+                        self.builder.ins().icmp_imm(IntCC::Equal, cmp, 0)
+                    }
+                };
+
+                self.branch(cond, fail_block);
+            }
+            Pattern::Variant { variant, fields } => {
+                let ValueType::Enum(idx) = r#type else {
+                    unreachable!()
+                };
+
+                let tag = self.load_tag(value);
+                let cond = self
+                    .builder
+                    .ins()
+                    .icmp_imm(IntCC::Equal, tag, *variant as i64);
+                self.branch(cond, fail_block);
+
+                let enums = self.enums;
+                let symbols = &enums[idx].variants[*variant].fields;
+
+                for (i, (field, symbol)) in fields.iter().zip(symbols.iter()).enumerate() {
+                    if let Pattern::Wildcard = field {
+                        continue;
+                    }
+
+                    let field_value = self.load_variant_field(value, i, symbol.r#type);
+                    self.lower_pattern(field, field_value, symbol.r#type, fail_block, bound)?;
+                }
+            }
+            Pattern::Tuple(fields) => {
+                let ValueType::Tuple(idx) = r#type else {
+                    unreachable!()
+                };
+
+                let elements = self.tuple_elements(idx);
+                for (i, (field, element)) in fields.iter().zip(elements).enumerate() {
+                    if let Pattern::Wildcard = field {
+                        continue;
+                    }
+
+                    let element_value = self.load_slot(value, i, element);
+                    self.lower_pattern(field, element_value, element, fail_block, bound)?;
+                }
+            }
+            Pattern::Or {
+                alternatives,
+                bindings,
+            } => {
+                // every alternative passes its bindings to a shared block
+                let join_block = self.builder.create_block();
+                for (_, r#type) in bindings.iter() {
+                    let ty = r#type.clif(self.ptr_type());
+                    self.builder.append_block_param(join_block, ty);
+                }
+
+                for (i, alternative) in alternatives.iter().enumerate() {
+                    let next_block = match i + 1 < alternatives.len() {
+                        true => self.builder.create_block(),
+                        false => fail_block,
+                    };
+
+                    let mut own = Vec::new();
+                    self.lower_pattern(alternative, value, r#type, next_block, &mut own)?;
+
+                    let args: Vec<Value> = bindings
+                        .iter()
+                        .map(|(name, _)| own.iter().find(|(bound, _)| bound == name).unwrap().1)
+                        .collect();
+                    self.builder.ins().jump(join_block, &args);
+
+                    if next_block != fail_block {
+                        self.builder.switch_to_block(next_block);
+                    }
+                }
+
+                self.builder.switch_to_block(join_block);
+                let params = self.builder.block_params(join_block).to_vec();
+                for ((name, _), param) in bindings.iter().zip(params) {
+                    bound.push((name.clone(), param));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn branch(&mut self, cond: Value, fail_block: Block) {
+        let next_block = self.builder.create_block();
+        self.builder
+            .ins()
+            .brif(cond, next_block, &[], fail_block, &[]);
+        self.builder.switch_to_block(next_block);
+    }
+
+    fn tuple_elements(&self, idx: usize) -> Vec<ValueType> {
+        let CollectionKind::Tuple(elements) = &self.collections[idx].kind else {
+            unreachable!()
+        };
+
+        elements.clone()
+    }
+}
@@ -0,0 +1,134 @@
+use cranelift::prelude::{InstBuilder, Value};
+use cranelift_module::Module;
+
+use crate::{
+    errors::codegen::{CodegenError, CodegenErrorKind},
+    frontend::parsing::ast::{
+        expressions::ExpressionNode,
+        statements::{IfKind, IfNode, StatementKind, StatementNode, VariableDeclarationNode},
+    },
+};
+
+use super::{error_ctx, functions::FunctionTranslator, types::ValueType};
+
+impl<'a, M: Module> FunctionTranslator<'a, '_, M> {
+    pub(super) fn lower_statement(&mut self, stmt: &'a StatementNode) -> Result<(), CodegenError> {
+        match &stmt.kind {
+            StatementKind::Expression(expr) => {
+                self.lower_expression(expr)?;
+            }
+            StatementKind::VariableDeclaration(var) => self.lower_variable_declaration(var)?,
+            StatementKind::FunctionDeclaration(func) => self.declare_nested_function(func)?,
+            StatementKind::If(if_stmt) => self.lower_if_statement(if_stmt)?,
+            StatementKind::WhileLoop(while_loop) => self.lower_while_loop(while_loop)?,
+            StatementKind::ForLoop(for_loop) => self.lower_for_loop(for_loop)?,
+            StatementKind::Match(node) => self.lower_match_statement(node)?,
+            StatementKind::Return(expr) => self.lower_return(expr)?,
+            StatementKind::Break(node) => self.lower_loop_control(node, true)?,
+            StatementKind::Continue(node) => self.lower_loop_control(node, false)?,
+        }
+
+        Ok(())
+    }
+
+    fn lower_variable_declaration(
+        &mut self,
+        var: &'a VariableDeclarationNode,
+    ) -> Result<(), CodegenError> {
+        let r#type = match &var.r#type {
+            Some(r#type) => Some(self.lower_type(r#type)?),
+            None => None,
+        };
+
+        let (value, r#type) = match &var.value {
+            Some(expr) => {
+                let (value, value_type) = match r#type {
+                    Some(r#type) => {
+                        let (value, value_type) = self.lower_expression_as(expr, r#type)?;
+                        self.expect_type(r#type, value_type, &expr.loc)?;
+                        (value, value_type)
+                    }
+                    None => self.lower_expression(expr)?,
+                };
+
+                if value_type == ValueType::Void {
+                    return Err(CodegenError::default()
+                        .kind(CodegenErrorKind::MismatchedTypes)
+                        .msg(format!("variable `{}` cannot be of type void", var.name))
+                        .ctx(error_ctx(self.src, &expr.loc)));
+                }
+
+                (value, value_type)
+            }
+            None => {
+                let r#type = r#type.unwrap();
+                (self.zero_value(r#type)?, r#type)
+            }
+        };
+
+        self.declare_variable(&var.name, r#type, value);
+        Ok(())
+    }
+
+    fn lower_if_statement(&mut self, node: &'a IfNode) -> Result<(), CodegenError> {
+        let IfKind::If { cond, r#else } = &node.kind else {
+            return self.lower_body(&node.body);
+        };
+
+        let cond = self.lower_condition(cond)?;
+
+        let then_block = self.builder.create_block();
+        let merge_block = self.builder.create_block();
+        let else_block = if r#else.is_some() {
+            self.builder.create_block()
+        } else {
+            merge_block
+        };
+
+        self.builder
+            .ins()
+            .brif(cond, then_block, &[], else_block, &[]);
+
+        self.builder.switch_to_block(then_block);
+        self.lower_body(&node.body)?;
+        self.builder.ins().jump(merge_block, &[]);
+
+        if let Some(else_node) = r#else {
+            self.builder.switch_to_block(else_block);
+            self.lower_if_statement(else_node)?;
+            self.builder.ins().jump(merge_block, &[]);
+        }
+
+        self.builder.switch_to_block(merge_block);
+        Ok(())
+    }
+
+    fn lower_return(&mut self, expr: &'a ExpressionNode) -> Result<(), CodegenError> {
+        let (value, r#type) = self.lower_expression_as(expr, self.return_type)?;
+
+        if self.return_type == ValueType::Void {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg("function does not return a value")
+                .ctx(error_ctx(self.src, &expr.loc)));
+        }
+
+        self.expect_type(self.return_type, r#type, &expr.loc)?;
+        self.builder.ins().return_(&[value]);
+
+        let unreachable_block = self.builder.create_block();
+        self.builder.switch_to_block(unreachable_block);
+
+        Ok(())
+    }
+
+    pub(super) fn lower_condition(
+        &mut self,
+        expr: &'a ExpressionNode,
+    ) -> Result<Value, CodegenError> {
+        let (value, r#type) = self.lower_expression(expr)?;
+        self.expect_type(ValueType::Bool, r#type, &expr.loc)?;
+
+        Ok(value)
+    }
+}
@@ -0,0 +1,458 @@
+use cranelift::prelude::{InstBuilder, MemFlags, Value};
+use cranelift_module::{FuncId, Module};
+
+use crate::{
+    errors::codegen::{CodegenError, CodegenErrorKind},
+    frontend::{
+        lexing::AssignmentKind,
+        parsing::ast::{
+            expressions::{ExpressionKind, ExpressionNode, FieldValueNode},
+            items::{FieldNode, ItemKind, StructNode},
+            types::TypeNode,
+        },
+        resolving::qualify,
+        Loc,
+    },
+};
+
+use super::{
+    error_ctx,
+    functions::FunctionTranslator,
+    generics::GenericParam,
+    types::{type_name, ValueType},
+    Codegen, Symbol,
+};
+
+// every field takes a whole word, so offsets don't depend on the field types
+pub(super) const SLOT_SIZE: i64 = 8;
+
+pub struct StructType<'a> {
+    pub name: String,
+    pub node: &'a StructNode,
+    pub fields: Vec<FieldSymbol<'a>>,
+    pub namespace: usize,
+    pub formatter: FuncId,
+    pub generics: Vec<GenericParam>,
+    // instances of generic structs remember what they were made from
+    pub template: Option<usize>,
+    pub args: Vec<ValueType>,
+}
+
+#[derive(Clone)]
+pub struct FieldSymbol<'a> {
+    pub name: String,
+    pub r#type: ValueType,
+    pub default: Option<&'a ExpressionNode>,
+}
+
+impl StructType<'_> {
+    // generic structs only describe their instances, they have no fields or
+    // values of their own
+    pub fn is_template(&self) -> bool {
+        !self.node.generics.is_empty() && self.template.is_none()
+    }
+
+    pub fn field(&self, name: &str) -> Option<(usize, &FieldSymbol<'_>)> {
+        find_field(&self.fields, name)
+    }
+}
+
+fn slot_offset(slot: usize) -> i32 {
+    (slot as i64 * SLOT_SIZE) as i32
+}
+
+pub fn find_field<'b, 'a>(
+    fields: &'b [FieldSymbol<'a>],
+    name: &str,
+) -> Option<(usize, &'b FieldSymbol<'a>)> {
+    fields
+        .iter()
+        .enumerate()
+        .find(|(_, field)| field.name == name)
+}
+
+impl<'a, M: Module> Codegen<'a, M> {
+    // struct names are declared before anything else, so that signatures and
+    // other structs can refer to them regardless of the order of the items
+    pub(super) fn declare_structs(&mut self) {
+        for idx in 0..self.namespaces.len() {
+            let namespace = &self.namespaces[idx];
+            let (node, path) = (namespace.node, namespace.path.clone());
+
+            for item in node.items.iter() {
+                let ItemKind::Struct(node) = &item.kind else {
+                    continue;
+                };
+
+                let name = qualify(&path, &node.name);
+                self.structs.push(StructType {
+                    name: name.clone(),
+                    node,
+                    fields: Vec::new(),
+                    namespace: idx,
+                    formatter: FuncId::from_u32(0),
+                    generics: Vec::new(),
+                    template: None,
+                    args: Vec::new(),
+                });
+
+                let symbol = Symbol::Struct(self.structs.len() - 1);
+                self.insert_global(name, symbol);
+            }
+        }
+    }
+
+    // bounds can name traits, so they're lowered once those are declared
+    pub(super) fn define_struct_generics(&mut self) {
+        for idx in 0..self.structs.len() {
+            self.current = self.structs[idx].namespace;
+
+            let node = self.structs[idx].node;
+            match self.lower_generics(&node.generics) {
+                Ok(generics) => self.structs[idx].generics = generics,
+                Err(err) => self.errors.push(err),
+            }
+        }
+    }
+
+    pub(super) fn define_structs(&mut self) {
+        for idx in 0..self.structs.len() {
+            if self.structs[idx].is_template() || self.structs[idx].template.is_some() {
+                continue;
+            }
+
+            self.current = self.structs[idx].namespace;
+
+            let node = self.structs[idx].node;
+            self.structs[idx].fields = self.lower_fields(&node.fields);
+        }
+    }
+
+    pub(super) fn lower_fields(&mut self, nodes: &'a [FieldNode]) -> Vec<FieldSymbol<'a>> {
+        let mut fields: Vec<FieldSymbol<'a>> = Vec::new();
+
+        for field in nodes.iter() {
+            if fields.iter().any(|other| other.name == field.name) {
+                self.errors.push(
+                    CodegenError::default()
+                        .kind(CodegenErrorKind::Redefined)
+                        .msg(format!("field `{}` is defined multiple times", field.name))
+                        .ctx(error_ctx(self.src(), &field.loc)),
+                );
+                continue;
+            }
+
+            let Some(r#type) = self.lower_field_type(&field.name, &field.r#type) else {
+                continue;
+            };
+
+            fields.push(FieldSymbol {
+                name: field.name.clone(),
+                r#type,
+                default: field.default.as_ref(),
+            });
+        }
+
+        return fields;
+    }
+
+    pub(super) fn lower_field_type(&mut self, name: &str, node: &TypeNode) -> Option<ValueType> {
+        let err = match self.lower_type(node) {
+            Ok(ValueType::Void) => CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(format!("field `{name}` cannot be of type void"))
+                .ctx(error_ctx(self.src(), &node.loc)),
+            Ok(r#type) => return Some(r#type),
+            Err(err) => err,
+        };
+
+        self.errors.push(err);
+        None
+    }
+
+    pub(super) fn type_name(&self, r#type: ValueType) -> String {
+        type_name(&self.structs, &self.enums, &self.collections, r#type)
+    }
+}
+
+impl<'a, M: Module> FunctionTranslator<'a, '_, M> {
+    pub(super) fn lower_struct(
+        &mut self,
+        res: &'a ExpressionNode,
+        fields: &'a [FieldValueNode],
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let mut idx = match self.lookup_expression(res)? {
+            Symbol::Struct(idx) => idx,
+            Symbol::Variant(idx, variant) => {
+                return self.lower_struct_variant(idx, variant, fields);
+            }
+            // the type checker only lets structs and struct variants be built
+            _ => unreachable!(),
+        };
+
+        let given = self.lower_field_values(fields)?;
+
+        if self.structs[idx].is_template() {
+            let types: Vec<_> = given
+                .iter()
+                .map(|(field, _, r#type)| (field.name.as_str(), *r#type))
+                .collect();
+            idx = self.infer_struct(idx, &types, &res.loc)?;
+        }
+
+        let (symbols, name) = (
+            self.structs[idx].fields.clone(),
+            self.structs[idx].name.clone(),
+        );
+        let ptr = self.allocate(symbols.len())?;
+        self.store_fields(ptr, 0, &symbols, given, &name)?;
+
+        Ok((ptr, ValueType::Struct(idx)))
+    }
+
+    // field values are lowered before the struct is built, since generic
+    // structs need their types to know which instance they're building
+    pub(super) fn lower_field_values(
+        &mut self,
+        fields: &'a [FieldValueNode],
+    ) -> Result<Vec<(&'a FieldValueNode, Value, ValueType)>, CodegenError> {
+        let mut given: Vec<(&'a FieldValueNode, Value, ValueType)> = Vec::new();
+
+        for field in fields.iter() {
+            if given.iter().any(|(other, ..)| other.name == field.name) {
+                return Err(CodegenError::default()
+                    .kind(CodegenErrorKind::Redefined)
+                    .msg(format!("field `{}` is given multiple times", field.name))
+                    .ctx(error_ctx(self.src, &field.loc)));
+            }
+
+            let (value, r#type) = self.lower_deferred(&field.value)?;
+            given.push((field, value, r#type));
+        }
+
+        return Ok(given);
+    }
+
+    // stores the given fields into consecutive slots starting at `first`,
+    // filling in the defaults of the ones left out
+    pub(super) fn store_fields(
+        &mut self,
+        ptr: Value,
+        first: usize,
+        symbols: &[FieldSymbol<'a>],
+        fields: Vec<(&'a FieldValueNode, Value, ValueType)>,
+        owner: &str,
+    ) -> Result<(), CodegenError> {
+        let mut given: Vec<&str> = Vec::new();
+        for (field, value, r#type) in fields {
+            let Some((i, symbol)) = find_field(symbols, &field.name) else {
+                return Err(self.unknown_field_error(owner, &field.name, &field.loc));
+            };
+            given.push(&field.name);
+
+            let (value, r#type) =
+                self.retype_deferred(&field.value, (value, r#type), symbol.r#type)?;
+            self.expect_type(symbol.r#type, r#type, &field.value.loc)?;
+            self.store_slot(ptr, first + i, value);
+        }
+
+        // the type checker made sure the fields without a default are given
+        for (i, field) in symbols.iter().enumerate() {
+            let Some(default) = field.default else {
+                continue;
+            };
+
+            if given.contains(&field.name.as_str()) {
+                continue;
+            }
+
+            let (value, r#type) = self.lower_expression_as(default, field.r#type)?;
+            self.expect_type(field.r#type, r#type, &default.loc)?;
+            self.store_slot(ptr, first + i, value);
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn lower_member(
+        &mut self,
+        left: &'a ExpressionNode,
+        right: &'a ExpressionNode,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let (ptr, slot, r#type) = self.member_slot(left, right)?;
+        Ok((self.load_slot(ptr, slot, r#type), r#type))
+    }
+
+    pub(super) fn lower_member_assignment(
+        &mut self,
+        left: &'a ExpressionNode,
+        right: &'a ExpressionNode,
+        op: &AssignmentKind,
+        value: &'a ExpressionNode,
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let (ptr, slot, target_type) = self.member_slot(left, right)?;
+        self.assign_slot(ptr, slot, target_type, op, value, loc)
+    }
+
+    pub(super) fn assign_slot(
+        &mut self,
+        ptr: Value,
+        slot: usize,
+        target_type: ValueType,
+        op: &AssignmentKind,
+        value: &'a ExpressionNode,
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let (mut new_value, r#type) = self.lower_expression_as(value, target_type)?;
+
+        if let Some(bin_op) = op.operator() {
+            let current = self.load_slot(ptr, slot, target_type);
+            (new_value, _) =
+                self.lower_operator(&bin_op, (current, target_type), (new_value, r#type), loc)?;
+        } else {
+            self.expect_type(target_type, r#type, &value.loc)?;
+        }
+
+        self.store_slot(ptr, slot, new_value);
+        Ok((new_value, target_type))
+    }
+
+    fn member_slot(
+        &mut self,
+        left: &'a ExpressionNode,
+        right: &'a ExpressionNode,
+    ) -> Result<(Value, usize, ValueType), CodegenError> {
+        let (ptr, r#type) = self.lower_expression(left)?;
+
+        let ValueType::Struct(idx) = r#type else {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(format!("`{}` has no fields", self.type_name(r#type)))
+                .ctx(error_ctx(self.src, &left.loc)));
+        };
+
+        let ExpressionKind::Identifier { name } = &right.kind else {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::Unsupported)
+                .msg("expected a field name")
+                .ctx(error_ctx(self.src, &right.loc)));
+        };
+
+        let layout = &self.structs[idx];
+        let Some((slot, field)) = layout.field(name) else {
+            return Err(self.unknown_field_error(&layout.name, name, &right.loc));
+        };
+
+        Ok((ptr, slot, field.r#type))
+    }
+
+    // aggregate values live on the heap and are shared by reference
+    pub(super) fn allocate(&mut self, slots: usize) -> Result<Value, CodegenError> {
+        let ptr = self.ptr_type();
+        let size = self
+            .builder
+            .ins()
+            .iconst(ptr, slots.max(1) as i64 * SLOT_SIZE);
+
+        self.call_import("malloc", &[size])
+    }
+
+    pub(super) fn load_slot(&mut self, ptr: Value, slot: usize, r#type: ValueType) -> Value {
+        let ty = r#type.clif(self.ptr_type());
+        self.builder
+            .ins()
+            .load(ty, MemFlags::trusted(), ptr, slot_offset(slot))
+    }
+
+    pub(super) fn store_slot(&mut self, ptr: Value, slot: usize, value: Value) {
+        self.builder
+            .ins()
+            .store(MemFlags::trusted(), value, ptr, slot_offset(slot));
+    }
+
+    pub(super) fn zero_struct(&mut self, idx: usize) -> Result<Value, CodegenError> {
+        let fields = self.structs[idx].fields.clone();
+
+        let ptr = self.allocate(fields.len())?;
+        self.zero_fields(ptr, 0, &fields)?;
+
+        Ok(ptr)
+    }
+
+    pub(super) fn zero_fields(
+        &mut self,
+        ptr: Value,
+        first: usize,
+        fields: &[FieldSymbol],
+    ) -> Result<(), CodegenError> {
+        for (i, field) in fields.iter().enumerate() {
+            let value = self.zero_value(field.r#type)?;
+            self.store_slot(ptr, first + i, value);
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn struct_to_string(
+        &mut self,
+        value: Value,
+        idx: usize,
+        loc: &Loc,
+    ) -> Result<Value, CodegenError> {
+        let (node, fields) = (self.structs[idx].node, self.structs[idx].fields.clone());
+
+        if fields.is_empty() {
+            return self.string_value(&format!("{} {{}}", node.name));
+        }
+
+        let start = self.string_value(&format!("{} {{ ", node.name))?;
+        let fields = self.fields_to_string(value, 0, &fields, true, loc)?;
+        let end = self.string_value(" }")?;
+
+        let result = self.lower_concat(start, fields)?;
+        self.lower_concat(result, end)
+    }
+
+    // joins the fields with commas, tuple-like payloads leave the names out
+    pub(super) fn fields_to_string(
+        &mut self,
+        value: Value,
+        first: usize,
+        fields: &[FieldSymbol],
+        named: bool,
+        loc: &Loc,
+    ) -> Result<Value, CodegenError> {
+        let mut result = self.string_value("")?;
+
+        for (i, field) in fields.iter().enumerate() {
+            let separator = if i == 0 { "" } else { ", " };
+            let label = match named {
+                true => format!("{separator}{}: ", field.name),
+                false => separator.to_owned(),
+            };
+
+            if !label.is_empty() {
+                let label = self.string_value(&label)?;
+                result = self.lower_concat(result, label)?;
+            }
+
+            let field_value = self.load_slot(value, first + i, field.r#type);
+            let text = self.lower_to_string(field_value, field.r#type, loc)?;
+            result = self.lower_concat(result, text)?;
+        }
+
+        Ok(result)
+    }
+
+    pub(super) fn type_name(&self, r#type: ValueType) -> String {
+        type_name(self.structs, self.enums, self.collections, r#type)
+    }
+
+    pub(super) fn unknown_field_error(&self, owner: &str, name: &str, loc: &Loc) -> CodegenError {
+        CodegenError::default()
+            .kind(CodegenErrorKind::Undefined)
+            .msg(format!("`{owner}` has no field `{name}`"))
+            .ctx(error_ctx(self.src, loc))
+    }
+}
@@ -0,0 +1,477 @@
+use std::collections::{HashMap, HashSet};
+
+use cranelift::prelude::Value;
+use cranelift_module::{Linkage, Module};
+
+use crate::{
+    errors::codegen::{CodegenError, CodegenErrorKind},
+    frontend::{
+        parsing::ast::{
+            expressions::ExpressionNode,
+            functions::{ArgumentNode, FunctionNode},
+            items::{ImplNode, ItemKind, TraitMethodNode, TraitNode},
+            types::{TypeKind, TypeNode},
+        },
+        resolving::qualify,
+        Loc,
+    },
+};
+
+use super::{
+    error_ctx,
+    functions::{lower_arguments, FunctionTranslator},
+    items::mangle,
+    namespaces::private_error,
+    types::ValueType,
+    Codegen, FunctionSymbol, PendingFunction, Scope, Symbol,
+};
+
+pub struct TraitType<'a> {
+    pub name: String,
+    pub node: &'a TraitNode,
+    pub methods: Vec<TraitMethod<'a>>,
+    pub namespace: usize,
+}
+
+// the signature every implementation has to repeat, not counting `self`
+pub struct TraitMethod<'a> {
+    pub node: &'a TraitMethodNode,
+    pub args: Vec<ValueType>,
+    pub r#type: ValueType,
+    pub takes_self: bool,
+}
+
+#[derive(Clone)]
+pub struct MethodSymbol<'a> {
+    // full path of the method, which decides where it can be called from
+    pub name: String,
+    pub function: FunctionSymbol<'a>,
+    pub takes_self: bool,
+    pub r#trait: Option<usize>,
+}
+
+pub type Methods<'a> = HashMap<(ValueType, String), Vec<MethodSymbol<'a>>>;
+
+impl<'a, M: Module> Codegen<'a, M> {
+    pub(super) fn declare_traits(&mut self) {
+        for idx in 0..self.namespaces.len() {
+            let namespace = &self.namespaces[idx];
+            let (node, path) = (namespace.node, namespace.path.clone());
+
+            for item in node.items.iter() {
+                let ItemKind::Trait(node) = &item.kind else {
+                    continue;
+                };
+
+                let name = qualify(&path, &node.name);
+                self.traits.push(TraitType {
+                    name: name.clone(),
+                    node,
+                    methods: Vec::new(),
+                    namespace: idx,
+                });
+
+                let symbol = Symbol::Trait(self.traits.len() - 1);
+                self.insert_global(name, symbol);
+            }
+        }
+    }
+
+    pub(super) fn define_traits(&mut self) {
+        for idx in 0..self.traits.len() {
+            self.current = self.traits[idx].namespace;
+
+            let mut methods: Vec<TraitMethod<'a>> = Vec::new();
+            for node in self.traits[idx].node.methods.iter() {
+                if methods.iter().any(|other| other.node.name() == node.name()) {
+                    self.errors.push(
+                        CodegenError::default()
+                            .kind(CodegenErrorKind::Redefined)
+                            .msg(format!(
+                                "method `{}` is defined multiple times",
+                                node.name()
+                            ))
+                            .ctx(error_ctx(self.src(), node.loc())),
+                    );
+                    continue;
+                }
+
+                match self.lower_trait_method(node) {
+                    Ok(method) => methods.push(method),
+                    Err(err) => self.errors.push(err),
+                }
+            }
+
+            self.traits[idx].methods = methods;
+        }
+    }
+
+    fn lower_trait_method(
+        &mut self,
+        node: &'a TraitMethodNode,
+    ) -> Result<TraitMethod<'a>, CodegenError> {
+        let takes_self = node.args().first().is_some_and(ArgumentNode::is_receiver);
+        let args = &node.args()[takes_self as usize..];
+
+        let src = self.src();
+        let args = lower_arguments(src, args, None, node.loc(), |node| self.lower_type(node))?;
+        let r#type = match node.r#type() {
+            Some(r#type) => self.lower_type(r#type)?,
+            None => ValueType::Void,
+        };
+
+        Ok(TraitMethod {
+            node,
+            args: args.iter().map(|arg| arg.r#type).collect(),
+            r#type,
+            takes_self,
+        })
+    }
+
+    // which types implement which traits is known before any signature is
+    // lowered, since generic types in them can require it
+    pub(super) fn collect_impls(&mut self) {
+        for idx in 0..self.namespaces.len() {
+            self.current = idx;
+
+            for item in self.namespaces[idx].node.items.iter() {
+                let ItemKind::Impl(node) = &item.kind else {
+                    continue;
+                };
+
+                let err = match self.resolve_impl(node) {
+                    Ok((target, Some(r#trait))) if !self.impls.insert((target, r#trait)) => {
+                        CodegenError::default()
+                            .kind(CodegenErrorKind::Redefined)
+                            .msg(format!(
+                                "`{}` is implemented for `{}` multiple times",
+                                self.traits[r#trait].name,
+                                self.type_name(target)
+                            ))
+                            .ctx(error_ctx(self.src(), &node.loc))
+                    }
+                    Ok(_) => continue,
+                    Err(err) => err,
+                };
+
+                self.errors.push(err);
+            }
+        }
+    }
+
+    // impls are declared once every function is, so their methods can be
+    // called from anywhere, including from each other
+    pub(super) fn declare_impls(&mut self) {
+        let mut declared = HashSet::new();
+
+        for idx in 0..self.namespaces.len() {
+            self.current = idx;
+
+            for item in self.namespaces[idx].node.items.iter() {
+                let ItemKind::Impl(node) = &item.kind else {
+                    continue;
+                };
+
+                // errors in the head were reported while collecting
+                match self.resolve_impl(node) {
+                    Ok((target, None)) => self.declare_inherent_methods(node, target),
+                    Ok((target, Some(r#trait))) if declared.insert((target, r#trait)) => {
+                        self.declare_trait_methods(node, target, r#trait)
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn resolve_impl(
+        &mut self,
+        node: &'a ImplNode,
+    ) -> Result<(ValueType, Option<usize>), CodegenError> {
+        let target = self.lower_type(&node.target)?;
+        if !matches!(target, ValueType::Struct(_) | ValueType::Enum(_)) {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::Unsupported)
+                .msg(format!(
+                    "methods can only be implemented for structs and enums, not `{}`",
+                    self.type_name(target)
+                ))
+                .ctx(error_ctx(self.src(), &node.target.loc)));
+        }
+
+        match &node.r#trait {
+            Some(r#trait) => Ok((target, Some(self.lookup_trait(r#trait)?))),
+            None => Ok((target, None)),
+        }
+    }
+
+    // inherent methods are items of the type itself, so `Type::method(..)`
+    // finds them like any other function
+    fn declare_inherent_methods(&mut self, node: &'a ImplNode, target: ValueType) {
+        let type_name = self.type_name(target);
+
+        for method in node.methods.iter() {
+            let func = &method.function;
+            let name = qualify(&type_name, &func.name);
+
+            if self.globals.contains_key(&name) {
+                self.errors.push(
+                    CodegenError::default()
+                        .kind(CodegenErrorKind::Redefined)
+                        .msg(format!("`{name}` is defined multiple times"))
+                        .ctx(error_ctx(self.src(), &func.loc)),
+                );
+                continue;
+            }
+
+            match self.declare_method(func, &name, target, None) {
+                Ok(symbol) => self.insert_global(name, Symbol::Function(symbol)),
+                Err(err) => self.errors.push(err),
+            }
+        }
+    }
+
+    fn declare_trait_methods(&mut self, node: &'a ImplNode, target: ValueType, r#trait: usize) {
+        let trait_name = self.traits[r#trait].name.clone();
+        let type_name = self.type_name(target);
+        let mut done: Vec<&str> = Vec::new();
+
+        for method in node.methods.iter() {
+            let func = &method.function;
+            let err = if done.contains(&func.name.as_str()) {
+                CodegenError::default()
+                    .kind(CodegenErrorKind::Redefined)
+                    .msg(format!(
+                        "method `{}` is implemented multiple times",
+                        func.name
+                    ))
+            } else if method.public {
+                CodegenError::default()
+                    .kind(CodegenErrorKind::Unsupported)
+                    .msg("methods of a trait are as public as the trait, remove `pub`")
+            } else if let Some(expected) = self.traits[r#trait]
+                .methods
+                .iter()
+                .find(|expected| expected.node.name() == func.name)
+            {
+                let name = qualify(&type_name, &format!("<{trait_name}>::{}", func.name));
+                let (args, r#type) = (expected.args.clone(), expected.r#type);
+                let takes_self = expected.takes_self;
+
+                done.push(&func.name);
+                match self.declare_method(func, &name, target, Some(r#trait)) {
+                    Ok(symbol) => {
+                        let offset = takes_self as usize;
+                        let found: Vec<_> = symbol.args.iter().map(|arg| arg.r#type).collect();
+
+                        if func.takes_self() == takes_self
+                            && found[offset..] == args
+                            && symbol.r#type == r#type
+                        {
+                            continue;
+                        }
+
+                        CodegenError::default()
+                            .kind(CodegenErrorKind::MismatchedTypes)
+                            .msg(format!(
+                                "method `{}` doesn't match its signature in `{trait_name}`",
+                                func.name
+                            ))
+                    }
+                    Err(err) => {
+                        self.errors.push(err);
+                        continue;
+                    }
+                }
+            } else {
+                CodegenError::default()
+                    .kind(CodegenErrorKind::Undefined)
+                    .msg(format!("`{}` is not a method of `{trait_name}`", func.name))
+            };
+
+            self.errors.push(err.ctx(error_ctx(self.src(), &func.loc)));
+        }
+
+        let mut missing = Vec::new();
+        let namespace = self.current;
+
+        for idx in 0..self.traits[r#trait].methods.len() {
+            let method = self.traits[r#trait].methods[idx].node;
+            if done.contains(&method.name()) {
+                continue;
+            }
+
+            let TraitMethodNode::Provided(func) = method else {
+                missing.push(format!("`{}`", method.name()));
+                continue;
+            };
+
+            // provided methods are compiled again for every type, in the
+            // namespace of the trait that wrote them
+            let name = qualify(&type_name, &format!("<{trait_name}>::{}", func.name));
+            self.current = self.traits[r#trait].namespace;
+            let result = self.declare_method(func, &name, target, Some(r#trait));
+            self.current = namespace;
+
+            if let Err(err) = result {
+                self.errors.push(err);
+            }
+        }
+
+        if !missing.is_empty() {
+            self.errors.push(
+                CodegenError::default()
+                    .kind(CodegenErrorKind::Undefined)
+                    .msg(format!(
+                        "`{type_name}` doesn't implement {} of `{trait_name}`",
+                        missing.join(", ")
+                    ))
+                    .ctx(error_ctx(self.src(), &node.loc)),
+            );
+        }
+    }
+
+    fn declare_method(
+        &mut self,
+        func: &'a FunctionNode,
+        name: &str,
+        target: ValueType,
+        r#trait: Option<usize>,
+    ) -> Result<FunctionSymbol<'a>, CodegenError> {
+        if let Some(param) = func.generics.first() {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::Unsupported)
+                .msg("methods can't have type parameters yet")
+                .ctx(error_ctx(self.src(), &param.loc)));
+        }
+
+        let symbol = self.declare_function(func, &mangle(name), Linkage::Local, Some(target))?;
+
+        // trait methods are as visible as their trait
+        let name = match r#trait {
+            Some(idx) => self.traits[idx].name.clone(),
+            None => name.to_owned(),
+        };
+
+        self.methods
+            .entry((target, func.name.clone()))
+            .or_default()
+            .push(MethodSymbol {
+                name,
+                function: symbol.clone(),
+                takes_self: func.takes_self(),
+                r#trait,
+            });
+
+        self.pending.push(PendingFunction {
+            node: func,
+            symbol: symbol.clone(),
+            scope: Scope::new(),
+            namespace: self.current,
+            params: Vec::new(),
+        });
+
+        Ok(symbol)
+    }
+
+    pub(super) fn lookup_trait(&self, node: &TypeNode) -> Result<usize, CodegenError> {
+        let source = self.namespaces[self.current].node.source;
+
+        let err = match &node.kind {
+            TypeKind::Path { path, generics } if generics.is_empty() => {
+                let path = path.join("::");
+                let found = self
+                    .tree
+                    .item(source, &node.loc)
+                    .and_then(|item| self.globals.get(item));
+
+                match found {
+                    Some(Symbol::Trait(idx)) => return Ok(*idx),
+                    Some(_) => CodegenError::default()
+                        .kind(CodegenErrorKind::MismatchedTypes)
+                        .msg(format!("`{path}` is not a trait")),
+                    None => CodegenError::default()
+                        .kind(CodegenErrorKind::Undefined)
+                        .msg(format!("unknown trait `{path}`")),
+                }
+            }
+            _ => CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(format!("`{}` is not a trait", node.to_string())),
+        };
+
+        Err(err.ctx(error_ctx(self.src(), &node.loc)))
+    }
+}
+
+impl<'a, M: Module> FunctionTranslator<'a, '_, M> {
+    pub(super) fn lower_method_call(
+        &mut self,
+        object: &'a ExpressionNode,
+        method: &str,
+        args: &'a [ExpressionNode],
+        loc: &Loc,
+    ) -> Result<(Value, ValueType), CodegenError> {
+        let (value, r#type) = self.lower_expression(object)?;
+        let type_name = self.type_name(r#type);
+
+        let methods = self.methods;
+        let found = methods
+            .get(&(r#type, method.to_owned()))
+            .map_or(&[][..], Vec::as_slice);
+
+        let visible: Vec<_> = found
+            .iter()
+            .filter(|symbol| {
+                self.tree
+                    .globals
+                    .get(&symbol.name)
+                    .is_none_or(|&item| self.tree.can_access(&self.namespace.path, item))
+            })
+            .collect();
+
+        // methods of the type itself win over the ones its traits add
+        let inherent = visible.iter().find(|symbol| symbol.r#trait.is_none());
+
+        let symbol = match (inherent, visible.as_slice()) {
+            (Some(symbol), _) | (None, [symbol]) => *symbol,
+            (None, []) => {
+                let err = match found.first() {
+                    Some(hidden) => private_error(self.tree, self.tree.globals[&hidden.name]),
+                    None => CodegenError::default()
+                        .kind(CodegenErrorKind::Undefined)
+                        .msg(format!("`{type_name}` has no method `{method}`")),
+                };
+
+                return Err(err.ctx(error_ctx(self.src, loc)));
+            }
+            (None, _) => {
+                let traits: Vec<_> = visible
+                    .iter()
+                    .filter_map(|symbol| symbol.r#trait)
+                    .map(|idx| format!("`{}`", self.traits[idx].name))
+                    .collect();
+
+                return Err(CodegenError::default()
+                    .kind(CodegenErrorKind::Redefined)
+                    .msg(format!(
+                        "method `{method}` of `{type_name}` is ambiguous, it's defined by {}",
+                        traits.join(" and ")
+                    ))
+                    .ctx(error_ctx(self.src, loc)));
+            }
+        };
+
+        if !symbol.takes_self {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(format!(
+                    "`{method}` doesn't take `self`, call it as `{type_name}::{method}(..)`"
+                ))
+                .ctx(error_ctx(self.src, loc)));
+        }
+
+        let path = format!("{type_name}::{method}");
+        self.call_function(&symbol.function, Some(value), args, &path, loc)
+    }
+}
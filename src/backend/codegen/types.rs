@@ -0,0 +1,270 @@
+use std::collections::{HashMap, HashSet};
+
+use cranelift::prelude::{types, Type};
+use cranelift_module::Module;
+
+use crate::{
+    errors::codegen::{CodegenError, CodegenErrorKind},
+    frontend::{
+        lexing::LiteralKind,
+        modules::is_builtin_type,
+        parsing::ast::{
+            expressions::{ExpressionKind, ExpressionNode},
+            types::{TypeKind, TypeNode},
+        },
+        primitives::{FloatType, IntType, PrimitiveType},
+        resolving::ScopeTree,
+    },
+    structs::ScriptSource,
+    utils::text::count_mismatch,
+};
+
+use super::{
+    collections::{CollectionKind, CollectionType},
+    enums::EnumType,
+    error_ctx,
+    namespaces::Namespace,
+    structs::StructType,
+    traits::TraitType,
+    Scope, Symbol,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueType {
+    Int(IntType),
+    Float(FloatType),
+    Bool,
+    Char,
+    String,
+    Void,
+    Struct(usize),
+    Enum(usize),
+    Array(usize),
+    Tuple(usize),
+    Dict(usize),
+}
+
+impl ValueType {
+    pub const INT: ValueType = ValueType::Int(IntType::Int64);
+    pub const FLOAT: ValueType = ValueType::Float(FloatType::Float64);
+
+    pub fn from_node(node: &TypeNode) -> Result<ValueType, ()> {
+        let Some(name) = node.name() else {
+            return Err(());
+        };
+
+        PrimitiveType::from_name(name)
+            .map(ValueType::from)
+            .ok_or(())
+    }
+
+    pub fn from_literal(expr: &ExpressionNode) -> Result<ValueType, ()> {
+        if let Some(literal) = expr.number() {
+            return Ok(match literal.suffix {
+                Some(suffix) => ValueType::from(suffix.r#type()),
+                None if *literal.kind == LiteralKind::Float => ValueType::FLOAT,
+                None => ValueType::INT,
+            });
+        }
+
+        match &expr.kind {
+            ExpressionKind::Literal { kind, .. } => match kind {
+                LiteralKind::String => Ok(ValueType::String),
+                LiteralKind::Char => Ok(ValueType::Char),
+                LiteralKind::Boolean => Ok(ValueType::Bool),
+                LiteralKind::Integer | LiteralKind::Float => Err(()),
+            },
+            _ => Err(()),
+        }
+    }
+
+    pub fn as_primitive(&self) -> Option<PrimitiveType> {
+        match self {
+            ValueType::Int(int) => Some(PrimitiveType::Int(*int)),
+            ValueType::Float(float) => Some(PrimitiveType::Float(*float)),
+            ValueType::Bool => Some(PrimitiveType::Bool),
+            ValueType::Char => Some(PrimitiveType::Char),
+            ValueType::String => Some(PrimitiveType::String),
+            ValueType::Void => Some(PrimitiveType::Void),
+            _ => None,
+        }
+    }
+
+    pub fn clif(&self, ptr: Type) -> Type {
+        match self {
+            ValueType::Int(int) => Type::int(int.bits() as u16).unwrap(),
+            ValueType::Float(FloatType::Float32) => types::F32,
+            ValueType::Float(FloatType::Float64) => types::F64,
+            ValueType::Bool => types::I8,
+            ValueType::Char => types::I32,
+            ValueType::String
+            | ValueType::Struct(_)
+            | ValueType::Enum(_)
+            | ValueType::Array(_)
+            | ValueType::Tuple(_)
+            | ValueType::Dict(_) => ptr,
+            ValueType::Void => types::I8,
+        }
+    }
+}
+
+impl From<PrimitiveType> for ValueType {
+    fn from(primitive: PrimitiveType) -> ValueType {
+        match primitive {
+            PrimitiveType::Int(int) => ValueType::Int(int),
+            PrimitiveType::Float(float) => ValueType::Float(float),
+            PrimitiveType::Bool => ValueType::Bool,
+            PrimitiveType::Char => ValueType::Char,
+            PrimitiveType::String => ValueType::String,
+            PrimitiveType::Void => ValueType::Void,
+        }
+    }
+}
+
+impl ToString for ValueType {
+    fn to_string(&self) -> String {
+        match self.as_primitive() {
+            Some(primitive) => primitive.name().to_string(),
+            None => format!("{:?}", self).to_lowercase(),
+        }
+    }
+}
+
+// everything needed to lower a type written anywhere in the program, which
+// can create instances of generic structs on the way
+pub(super) struct TypeContext<'a, 'b, M: Module> {
+    pub module: &'b mut M,
+    pub tree: &'a ScopeTree,
+    pub sources: &'a [ScriptSource<'a>],
+    pub namespaces: &'b [Namespace<'a>],
+    pub globals: &'b Scope<'a>,
+    pub structs: &'b mut Vec<StructType<'a>>,
+    pub enums: &'b [EnumType<'a>],
+    pub collections: &'b mut Vec<CollectionType>,
+    pub traits: &'b [TraitType<'a>],
+    pub impls: &'b HashSet<(ValueType, usize)>,
+    pub instances: &'b mut HashMap<(usize, Vec<ValueType>), usize>,
+}
+
+impl<'a, M: Module> TypeContext<'a, '_, M> {
+    // type parameters shadow every other name, the rest are the items the
+    // resolver bound them to
+    pub fn lower_type(
+        &mut self,
+        namespace: usize,
+        params: &[(String, ValueType)],
+        node: &TypeNode,
+    ) -> Result<ValueType, CodegenError> {
+        if let Some(name) = node.name()
+            && let Some((_, r#type)) = params.iter().find(|(param, _)| param == name)
+        {
+            return Ok(*r#type);
+        }
+
+        if let Ok(r#type) = ValueType::from_node(node) {
+            return Ok(r#type);
+        }
+
+        let src = self.src(namespace);
+        let (path, generics) = match &node.kind {
+            TypeKind::Path { path, generics } => (path.join("::"), generics),
+            TypeKind::Slice { element } => {
+                let element = self.lower_element_type(namespace, params, element)?;
+                return self.collection(CollectionKind::Array(element), namespace, &node.loc);
+            }
+            TypeKind::Tuple { elements } => {
+                let mut types = Vec::new();
+                for element in elements.iter() {
+                    types.push(self.lower_element_type(namespace, params, element)?);
+                }
+
+                return self.collection(CollectionKind::Tuple(types), namespace, &node.loc);
+            }
+            _ => {
+                return Err(CodegenError::default()
+                    .kind(CodegenErrorKind::Unsupported)
+                    .msg(format!("type `{}` is not supported yet", node.to_string()))
+                    .ctx(error_ctx(src, &node.loc)));
+            }
+        };
+
+        let symbol = self.resolve(namespace, node);
+        if symbol.is_none()
+            && self.namespaces[namespace]
+                .candidates(&path)
+                .iter()
+                .any(|candidate| is_builtin_type(candidate))
+        {
+            return self.lower_builtin_type(namespace, params, generics, &node.loc);
+        }
+        let expected = match symbol {
+            Some(Symbol::Struct(idx)) => self.structs[*idx].node.generics.len(),
+            Some(Symbol::Enum(_)) => 0,
+            _ => generics.len(),
+        };
+
+        if generics.len() != expected {
+            return Err(CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(count_mismatch(
+                    &path,
+                    expected,
+                    "type argument",
+                    generics.len(),
+                ))
+                .ctx(error_ctx(src, &node.loc)));
+        }
+
+        let err = match symbol.cloned() {
+            Some(Symbol::Struct(idx)) if generics.is_empty() => return Ok(ValueType::Struct(idx)),
+            Some(Symbol::Struct(idx)) => {
+                let mut args = Vec::new();
+                for generic in generics.iter() {
+                    args.push(self.lower_type(namespace, params, generic)?);
+                }
+
+                let instance = self.instantiate_struct(idx, args, namespace, &node.loc)?;
+                return Ok(ValueType::Struct(instance));
+            }
+            Some(Symbol::Enum(idx)) => return Ok(ValueType::Enum(idx)),
+            Some(_) => CodegenError::default()
+                .kind(CodegenErrorKind::MismatchedTypes)
+                .msg(format!("`{path}` is not a type")),
+            None => CodegenError::default()
+                .kind(CodegenErrorKind::Undefined)
+                .msg(format!("unknown type `{}`", node.to_string())),
+        };
+
+        Err(err.ctx(error_ctx(src, &node.loc)))
+    }
+
+    // the item the resolver bound a type to
+    pub fn resolve(&self, namespace: usize, node: &TypeNode) -> Option<&Symbol<'a>> {
+        let source = self.namespaces[namespace].node.source;
+        self.globals.get(self.tree.item(source, &node.loc)?)
+    }
+
+    pub fn src(&self, namespace: usize) -> &'a ScriptSource<'a> {
+        &self.sources[self.namespaces[namespace].node.source]
+    }
+
+    pub fn type_name(&self, r#type: ValueType) -> String {
+        type_name(self.structs, self.enums, self.collections, r#type)
+    }
+}
+
+pub(super) fn type_name(
+    structs: &[StructType],
+    enums: &[EnumType],
+    collections: &[CollectionType],
+    r#type: ValueType,
+) -> String {
+    match r#type {
+        ValueType::Struct(idx) => structs[idx].name.clone(),
+        ValueType::Enum(idx) => enums[idx].name.clone(),
+        ValueType::Array(idx) | ValueType::Tuple(idx) | ValueType::Dict(idx) => {
+            collections[idx].name.clone()
+        }
+        _ => r#type.to_string(),
+    }
+}
@@ -0,0 +1,46 @@
+use std::mem;
+
+use cranelift::prelude::{settings, Configurable};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{default_libcall_names, FuncId};
+
+use crate::errors::{self, codegen::CodegenError, codegen::CodegenErrorKind};
+
+use super::host_isa;
+
+pub fn new_module() -> JITModule {
+    let mut flag_builder = settings::builder();
+    flag_builder.set("use_colocated_libcalls", "false").unwrap();
+    flag_builder.set("is_pic", "false").unwrap();
+
+    let mut builder = JITBuilder::with_isa(host_isa(flag_builder), default_libcall_names());
+    builder.symbol("pow", pow as *const u8);
+    builder.symbol("fmod", fmod as *const u8);
+
+    JITModule::new(builder)
+}
+
+pub fn execute(mut module: JITModule, entry: FuncId) -> i32 {
+    if let Err(err) = module.finalize_definitions() {
+        errors::throw(
+            CodegenError::default()
+                .kind(CodegenErrorKind::Module)
+                .msg(err),
+        );
+    }
+
+    let entry_ptr = module.get_finalized_function(entry);
+
+    // SAFETY: entry is generated by codegen with the `fn() -> i32` signature
+    let entry_fn = unsafe { mem::transmute::<*const u8, extern "C" fn() -> i32>(entry_ptr) };
+
+    entry_fn()
+}
+
+extern "C" fn pow(base: f64, exp: f64) -> f64 {
+    base.powf(exp)
+}
+
+extern "C" fn fmod(lhs: f64, rhs: f64) -> f64 {
+    lhs % rhs
+}
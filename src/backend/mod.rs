@@ -0,0 +1,30 @@
+pub mod codegen;
+pub mod jit;
+pub mod object;
+
+use cranelift::{
+    codegen::isa::OwnedTargetIsa,
+    prelude::{settings, Configurable},
+};
+
+use crate::errors::{
+    self,
+    codegen::{CodegenError, CodegenErrorKind},
+};
+
+pub fn host_isa(mut flag_builder: settings::Builder) -> OwnedTargetIsa {
+    flag_builder.set("opt_level", "speed").unwrap();
+
+    let isa_builder = cranelift_native::builder().unwrap_or_else(|msg| {
+        errors::throw(
+            CodegenError::default()
+                .kind(CodegenErrorKind::Unsupported)
+                .msg(format!("host machine is not supported: {msg}")),
+        );
+        unreachable!();
+    });
+
+    isa_builder
+        .finish(settings::Flags::new(flag_builder))
+        .unwrap()
+}
@@ -0,0 +1,85 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, Command},
+};
+
+use cranelift::prelude::{settings, Configurable};
+use cranelift_module::default_libcall_names;
+use cranelift_object::{ObjectBuilder, ObjectModule};
+
+use crate::errors::{
+    self,
+    codegen::{CodegenError, CodegenErrorKind},
+};
+
+use super::host_isa;
+
+pub fn new_module(name: &str) -> ObjectModule {
+    let mut flag_builder = settings::builder();
+    flag_builder.set("is_pic", "true").unwrap();
+
+    let builder = ObjectBuilder::new(host_isa(flag_builder), name, default_libcall_names())
+        .unwrap_or_else(|err| {
+            errors::throw(
+                CodegenError::default()
+                    .kind(CodegenErrorKind::Module)
+                    .msg(err),
+            );
+            unreachable!();
+        });
+
+    ObjectModule::new(builder)
+}
+
+pub fn emit(module: ObjectModule, output: &Path) -> PathBuf {
+    let bytes = module.finish().emit().unwrap_or_else(|err| {
+        errors::throw(
+            CodegenError::default()
+                .kind(CodegenErrorKind::Module)
+                .msg(err),
+        );
+        unreachable!();
+    });
+
+    // the object only lives until it's linked, so it stays out of the way
+    // of the user's files
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let object_path = env::temp_dir().join(format!("meowind-{}-{stem}.o", process::id()));
+    if let Err(err) = fs::write(&object_path, bytes) {
+        errors::throw(
+            CodegenError::default()
+                .kind(CodegenErrorKind::Module)
+                .msg(format!("failed to write {}: {err}", object_path.display())),
+        );
+    }
+
+    return object_path;
+}
+
+pub fn link(object_path: &Path, output: &Path) {
+    let linker = env::var("CC").unwrap_or_else(|_| String::from("cc"));
+
+    let mut command = Command::new(&linker);
+    command.arg(object_path).arg("-o").arg(output);
+
+    #[cfg(not(windows))]
+    command.arg("-lm");
+
+    let status = command.status();
+    let _ = fs::remove_file(object_path);
+
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => errors::throw(
+            CodegenError::default()
+                .kind(CodegenErrorKind::Link)
+                .msg(format!("`{linker}` exited with {status}")),
+        ),
+        Err(err) => errors::throw(
+            CodegenError::default()
+                .kind(CodegenErrorKind::Link)
+                .msg(format!("failed to run `{linker}`: {err}")),
+        ),
+    }
+}
@@ -0,0 +1,91 @@
+use crate::utils::colors::*;
+use std::fmt;
+
+use super::{context::ErrorContext, MeowindError};
+
+#[derive(Clone)]
+pub struct CodegenError {
+    kind: Option<CodegenErrorKind>,
+    msg: Option<String>,
+    ctx: Option<ErrorContext>,
+}
+
+impl Default for CodegenError {
+    fn default() -> Self {
+        Self {
+            kind: None,
+            msg: None,
+            ctx: None,
+        }
+    }
+}
+
+impl CodegenError {
+    pub fn kind(&self, kind: CodegenErrorKind) -> CodegenError {
+        CodegenError {
+            kind: Some(kind),
+            ..self.clone()
+        }
+    }
+
+    pub fn msg<T: ToString>(&self, msg: T) -> CodegenError {
+        CodegenError {
+            msg: Some(msg.to_string()),
+            ..self.clone()
+        }
+    }
+
+    pub fn ctx(&self, ctx: ErrorContext) -> CodegenError {
+        CodegenError {
+            ctx: Some(ctx),
+            ..self.clone()
+        }
+    }
+}
+
+impl MeowindError for CodegenError {
+    fn to_string(&self) -> String {
+        let mut error_body = format!("{RED}{BOLD}codegen error{RESET}");
+
+        if let Some(kind) = &self.kind {
+            error_body += format!(": {kind}").as_str();
+        }
+
+        if let Some(msg) = &self.msg {
+            error_body += format!(": {msg}").as_str();
+        }
+
+        if let Some(ctx) = &self.ctx {
+            error_body = format!("{error_body}\n{}", ctx.to_string());
+        }
+
+        return error_body;
+    }
+}
+
+#[derive(Clone)]
+pub enum CodegenErrorKind {
+    Undefined,
+    Redefined,
+    Private,
+    MismatchedTypes,
+    Unsupported,
+    Module,
+    Link,
+}
+
+impl fmt::Display for CodegenErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            CodegenErrorKind::Undefined => "undefined name",
+            CodegenErrorKind::Redefined => "redefined name",
+            CodegenErrorKind::Private => "private name",
+            CodegenErrorKind::MismatchedTypes => "mismatched types",
+            CodegenErrorKind::Unsupported => "unsupported",
+            CodegenErrorKind::Module => "module error",
+            CodegenErrorKind::Link => "link error",
+        };
+
+        write!(f, "{text}")
+    }
+}
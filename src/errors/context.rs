@@ -150,12 +150,33 @@ impl ErrorContextBuilder {
         }
     }
 
+    pub fn loc(loc: &Loc) -> ErrorContextBuilder {
+        // spans over multiple lines are highlighted until the end of the first one
+        let end = if loc.end_ln == loc.ln {
+            loc.end_col
+        } else {
+            usize::MAX
+        };
+
+        ErrorContextBuilder::span(loc.start_col, end)
+    }
+
     pub fn from_src_and_ln(&self, src: &ScriptSource, ln: usize) -> ErrorContextBuilder {
+        let ln_text = src.lines[ln - 1];
+
+        let kind = match self.kind {
+            Some(ErrorContextKind::PointOnSpan { start, end }) => {
+                let end = end.min(ln_text.graphemes(true).count() + 1);
+                Some(ErrorContextKind::PointOnSpan { start, end })
+            }
+            ref kind => kind.clone(),
+        };
+
         ErrorContextBuilder {
+            kind,
             ln: Some(ln),
-            ln_text: Some(src.lines[ln - 1].to_owned()),
+            ln_text: Some(ln_text.to_owned()),
             src_path: Some(src.path.clone()),
-            ..self.clone()
         }
     }
 
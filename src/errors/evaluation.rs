@@ -0,0 +1,85 @@
+use crate::utils::colors::*;
+use std::fmt;
+
+use super::{context::ErrorContext, MeowindError};
+
+#[derive(Clone)]
+pub struct EvaluationError {
+    kind: Option<EvaluationErrorKind>,
+    msg: Option<String>,
+    ctx: Option<ErrorContext>,
+}
+
+impl Default for EvaluationError {
+    fn default() -> Self {
+        Self {
+            kind: None,
+            msg: None,
+            ctx: None,
+        }
+    }
+}
+
+impl EvaluationError {
+    pub fn kind(&self, kind: EvaluationErrorKind) -> EvaluationError {
+        EvaluationError {
+            kind: Some(kind),
+            ..self.clone()
+        }
+    }
+
+    pub fn msg<T: ToString>(&self, msg: T) -> EvaluationError {
+        EvaluationError {
+            msg: Some(msg.to_string()),
+            ..self.clone()
+        }
+    }
+
+    pub fn ctx(&self, ctx: ErrorContext) -> EvaluationError {
+        EvaluationError {
+            ctx: Some(ctx),
+            ..self.clone()
+        }
+    }
+}
+
+impl MeowindError for EvaluationError {
+    fn to_string(&self) -> String {
+        let mut error_body = format!("{RED}{BOLD}evaluation error{RESET}");
+
+        if let Some(kind) = &self.kind {
+            error_body += format!(": {kind}").as_str();
+        }
+
+        if let Some(msg) = &self.msg {
+            error_body += format!(": {msg}").as_str();
+        }
+
+        if let Some(ctx) = &self.ctx {
+            error_body = format!("{error_body}\n{}", ctx.to_string());
+        }
+
+        return error_body;
+    }
+}
+
+#[derive(Clone)]
+pub enum EvaluationErrorKind {
+    NotConstant,
+    DivisionByZero,
+    Overflow,
+    Cycle,
+}
+
+impl fmt::Display for EvaluationErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            EvaluationErrorKind::NotConstant => "not a constant expression",
+            EvaluationErrorKind::DivisionByZero => "division by zero",
+            EvaluationErrorKind::Overflow => "arithmetic overflow",
+            EvaluationErrorKind::Cycle => "dependency cycle",
+        };
+
+        write!(f, "{text}")
+    }
+}
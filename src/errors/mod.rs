@@ -1,8 +1,14 @@
 use std::process;
 
+pub mod codegen;
 pub mod command_line;
 pub mod context;
+pub mod evaluation;
+pub mod module;
+pub mod mutability;
+pub mod resolution;
 pub mod syntax;
+pub mod types;
 
 impl<T: MeowindError> ErrorList for Vec<T> {
     fn throw_if_there(&self) {
@@ -0,0 +1,81 @@
+use crate::utils::colors::*;
+use std::fmt;
+
+use super::{context::ErrorContext, MeowindError};
+
+#[derive(Clone)]
+pub struct ModuleError {
+    kind: Option<ModuleErrorKind>,
+    msg: Option<String>,
+    ctx: Option<ErrorContext>,
+}
+
+impl Default for ModuleError {
+    fn default() -> Self {
+        Self {
+            kind: None,
+            msg: None,
+            ctx: None,
+        }
+    }
+}
+
+impl ModuleError {
+    pub fn kind(&self, kind: ModuleErrorKind) -> ModuleError {
+        ModuleError {
+            kind: Some(kind),
+            ..self.clone()
+        }
+    }
+
+    pub fn msg<T: ToString>(&self, msg: T) -> ModuleError {
+        ModuleError {
+            msg: Some(msg.to_string()),
+            ..self.clone()
+        }
+    }
+
+    pub fn ctx(&self, ctx: ErrorContext) -> ModuleError {
+        ModuleError {
+            ctx: Some(ctx),
+            ..self.clone()
+        }
+    }
+}
+
+impl MeowindError for ModuleError {
+    fn to_string(&self) -> String {
+        let mut error_body = format!("{RED}{BOLD}module error{RESET}");
+
+        if let Some(kind) = &self.kind {
+            error_body += format!(": {kind}").as_str();
+        }
+
+        if let Some(msg) = &self.msg {
+            error_body += format!(": {msg}").as_str();
+        }
+
+        if let Some(ctx) = &self.ctx {
+            error_body = format!("{error_body}\n{}", ctx.to_string());
+        }
+
+        return error_body;
+    }
+}
+
+#[derive(Clone)]
+pub enum ModuleErrorKind {
+    NotFound,
+    FailedToRead,
+}
+
+impl fmt::Display for ModuleErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            ModuleErrorKind::NotFound => "module not found",
+            ModuleErrorKind::FailedToRead => "failed to read module",
+        };
+
+        write!(f, "{text}")
+    }
+}
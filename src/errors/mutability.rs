@@ -0,0 +1,98 @@
+use crate::utils::colors::*;
+use std::fmt;
+
+use super::{context::ErrorContext, MeowindError};
+
+#[derive(Clone)]
+pub struct MutabilityError {
+    kind: Option<MutabilityErrorKind>,
+    msg: Option<String>,
+    ctx: Option<ErrorContext>,
+    // points at another place the error is about, like a declaration
+    note: Option<(String, ErrorContext)>,
+}
+
+impl Default for MutabilityError {
+    fn default() -> Self {
+        Self {
+            kind: None,
+            msg: None,
+            ctx: None,
+            note: None,
+        }
+    }
+}
+
+impl MutabilityError {
+    pub fn kind(&self, kind: MutabilityErrorKind) -> MutabilityError {
+        MutabilityError {
+            kind: Some(kind),
+            ..self.clone()
+        }
+    }
+
+    pub fn msg<T: ToString>(&self, msg: T) -> MutabilityError {
+        MutabilityError {
+            msg: Some(msg.to_string()),
+            ..self.clone()
+        }
+    }
+
+    pub fn ctx(&self, ctx: ErrorContext) -> MutabilityError {
+        MutabilityError {
+            ctx: Some(ctx),
+            ..self.clone()
+        }
+    }
+
+    pub fn note<T: ToString>(&self, msg: T, ctx: ErrorContext) -> MutabilityError {
+        MutabilityError {
+            note: Some((msg.to_string(), ctx)),
+            ..self.clone()
+        }
+    }
+}
+
+impl MeowindError for MutabilityError {
+    fn to_string(&self) -> String {
+        let mut error_body = format!("{RED}{BOLD}mutability error{RESET}");
+
+        if let Some(kind) = &self.kind {
+            error_body += format!(": {kind}").as_str();
+        }
+
+        if let Some(msg) = &self.msg {
+            error_body += format!(": {msg}").as_str();
+        }
+
+        if let Some(ctx) = &self.ctx {
+            error_body = format!("{error_body}\n{}", ctx.to_string());
+        }
+
+        if let Some((msg, ctx)) = &self.note {
+            error_body = format!(
+                "{error_body}\n{CYAN}{BOLD}note{RESET}: {msg}\n{}",
+                ctx.to_string()
+            );
+        }
+
+        return error_body;
+    }
+}
+
+#[derive(Clone)]
+pub enum MutabilityErrorKind {
+    Immutable,
+    InvalidTarget,
+}
+
+impl fmt::Display for MutabilityErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            MutabilityErrorKind::Immutable => "assignment to immutable value",
+            MutabilityErrorKind::InvalidTarget => "invalid assignment target",
+        };
+
+        write!(f, "{text}")
+    }
+}
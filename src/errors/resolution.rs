@@ -0,0 +1,87 @@
+use crate::utils::colors::*;
+use std::fmt;
+
+use super::{context::ErrorContext, MeowindError};
+
+#[derive(Clone)]
+pub struct ResolutionError {
+    kind: Option<ResolutionErrorKind>,
+    msg: Option<String>,
+    ctx: Option<ErrorContext>,
+}
+
+impl Default for ResolutionError {
+    fn default() -> Self {
+        Self {
+            kind: None,
+            msg: None,
+            ctx: None,
+        }
+    }
+}
+
+impl ResolutionError {
+    pub fn kind(&self, kind: ResolutionErrorKind) -> ResolutionError {
+        ResolutionError {
+            kind: Some(kind),
+            ..self.clone()
+        }
+    }
+
+    pub fn msg<T: ToString>(&self, msg: T) -> ResolutionError {
+        ResolutionError {
+            msg: Some(msg.to_string()),
+            ..self.clone()
+        }
+    }
+
+    pub fn ctx(&self, ctx: ErrorContext) -> ResolutionError {
+        ResolutionError {
+            ctx: Some(ctx),
+            ..self.clone()
+        }
+    }
+}
+
+impl MeowindError for ResolutionError {
+    fn to_string(&self) -> String {
+        let mut error_body = format!("{RED}{BOLD}resolution error{RESET}");
+
+        if let Some(kind) = &self.kind {
+            error_body += format!(": {kind}").as_str();
+        }
+
+        if let Some(msg) = &self.msg {
+            error_body += format!(": {msg}").as_str();
+        }
+
+        if let Some(ctx) = &self.ctx {
+            error_body = format!("{error_body}\n{}", ctx.to_string());
+        }
+
+        return error_body;
+    }
+}
+
+#[derive(Clone)]
+pub enum ResolutionErrorKind {
+    Undefined,
+    NotAValue,
+    Duplicate,
+    Private,
+    ControlFlow,
+}
+
+impl fmt::Display for ResolutionErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            ResolutionErrorKind::Undefined => "undefined name",
+            ResolutionErrorKind::NotAValue => "not a value",
+            ResolutionErrorKind::Duplicate => "duplicate definition",
+            ResolutionErrorKind::Private => "private name",
+            ResolutionErrorKind::ControlFlow => "invalid control flow",
+        };
+
+        write!(f, "{text}")
+    }
+}
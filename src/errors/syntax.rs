@@ -48,6 +48,10 @@ impl SyntaxError {
             ..self.clone()
         }
     }
+
+    pub fn ln(&self) -> usize {
+        self.ctx.as_ref().map_or(0, |ctx| ctx.ln)
+    }
 }
 
 impl MeowindError for SyntaxError {
@@ -0,0 +1,91 @@
+use crate::utils::colors::*;
+use std::fmt;
+
+use super::{context::ErrorContext, MeowindError};
+
+#[derive(Clone)]
+pub struct TypeError {
+    kind: Option<TypeErrorKind>,
+    msg: Option<String>,
+    ctx: Option<ErrorContext>,
+}
+
+impl Default for TypeError {
+    fn default() -> Self {
+        Self {
+            kind: None,
+            msg: None,
+            ctx: None,
+        }
+    }
+}
+
+impl TypeError {
+    pub fn kind(&self, kind: TypeErrorKind) -> TypeError {
+        TypeError {
+            kind: Some(kind),
+            ..self.clone()
+        }
+    }
+
+    pub fn msg<T: ToString>(&self, msg: T) -> TypeError {
+        TypeError {
+            msg: Some(msg.to_string()),
+            ..self.clone()
+        }
+    }
+
+    pub fn ctx(&self, ctx: ErrorContext) -> TypeError {
+        TypeError {
+            ctx: Some(ctx),
+            ..self.clone()
+        }
+    }
+}
+
+impl MeowindError for TypeError {
+    fn to_string(&self) -> String {
+        let mut error_body = format!("{RED}{BOLD}type error{RESET}");
+
+        if let Some(kind) = &self.kind {
+            error_body += format!(": {kind}").as_str();
+        }
+
+        if let Some(msg) = &self.msg {
+            error_body += format!(": {msg}").as_str();
+        }
+
+        if let Some(ctx) = &self.ctx {
+            error_body = format!("{error_body}\n{}", ctx.to_string());
+        }
+
+        return error_body;
+    }
+}
+
+#[derive(Clone)]
+pub enum TypeErrorKind {
+    MismatchedTypes,
+    ArgumentCount,
+    LiteralOutOfRange,
+    InvalidCast,
+    NonExhaustive,
+    UnknownMember,
+    MissingReturn,
+}
+
+impl fmt::Display for TypeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            TypeErrorKind::MismatchedTypes => "mismatched types",
+            TypeErrorKind::ArgumentCount => "wrong number of arguments",
+            TypeErrorKind::LiteralOutOfRange => "literal out of range",
+            TypeErrorKind::InvalidCast => "invalid cast",
+            TypeErrorKind::NonExhaustive => "non-exhaustive match",
+            TypeErrorKind::UnknownMember => "unknown member",
+            TypeErrorKind::MissingReturn => "missing return",
+        };
+
+        write!(f, "{text}")
+    }
+}
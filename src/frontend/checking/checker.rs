@@ -0,0 +1,868 @@
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+};
+
+use crate::{
+    errors::{
+        context::ErrorContextBuilder,
+        types::{TypeError, TypeErrorKind},
+    },
+    frontend::{
+        parsing::ast::{
+            bodies::{BodyElementKind, BodyKind, BodyNode},
+            expressions::{ExpressionKind, ExpressionNode, NumberLiteral},
+            functions::FunctionNode,
+            items::{FieldNode, ItemKind, TraitMethodNode, VariantKind},
+            namespaces::NamespaceNode,
+            patterns::{MatchArmNode, PatternKind, PatternNode, PatternPayload},
+            projects::ProjectNode,
+            statements::{
+                IfKind, IfNode, StatementKind, StatementNode, VariableDeclarationNode,
+                WhileLoopKind, WhileLoopNode,
+            },
+            types::{GenericParamNode, TypeKind, TypeNode},
+        },
+        primitives::PrimitiveType,
+        resolving::{ScopeTree, SymbolKind},
+        Loc,
+    },
+    structs::ScriptSource,
+};
+
+use super::types::Type;
+
+// the type of number literals by their source and location
+pub type LiteralTypes = HashMap<(usize, Loc), PrimitiveType>;
+
+// the expressions whose values are shared, by their source and location
+pub type SharedValues = HashSet<(usize, Loc)>;
+
+// infers the type of every value in the project and checks that they're
+// used the way their types allow, before anything is compiled
+pub struct TypeChecker<'a> {
+    pub errors: Vec<TypeError>,
+    // the type of every number literal that takes it from a suffix or from
+    // what it's expected to be, by source and location
+    pub literals: LiteralTypes,
+    // the expressions whose values are structs, arrays, tuples or dicts,
+    // binding one to another name shares it rather than copying it
+    pub shared: SharedValues,
+
+    tree: &'a ScopeTree,
+    sources: &'a [ScriptSource<'a>],
+    pub(super) source: usize,
+
+    // constants, statics, arguments, variables and bindings by their symbol
+    types: HashMap<usize, Type>,
+    pub(super) functions: HashMap<usize, FunctionType>,
+    pub(super) structs: HashMap<usize, StructType>,
+    pub(super) variants: HashMap<usize, VariantType>,
+    // the variants of every enum, in the order they're declared
+    pub(super) enums: HashMap<usize, Vec<usize>>,
+    // the fields without a default of every struct and struct variant
+    pub(super) required: HashMap<usize, Vec<String>>,
+    // the traits every type parameter has to implement
+    pub(super) bounds: HashMap<usize, Vec<Bound>>,
+    // the types every trait is implemented for, by the trait's symbol
+    pub(super) impls: HashMap<usize, Vec<Type>>,
+    pub(super) return_type: Type,
+}
+
+#[derive(Clone)]
+pub(super) struct FunctionType {
+    pub params: Vec<ParamType>,
+    pub r#type: Type,
+    pub receiver: bool,
+}
+
+#[derive(Clone)]
+pub(super) struct ParamType {
+    pub r#type: Type,
+    pub default: bool,
+}
+
+#[derive(Clone)]
+pub(super) struct StructType {
+    pub generics: Vec<usize>,
+    pub fields: Vec<(String, Type)>,
+}
+
+#[derive(Clone)]
+pub(super) struct VariantType {
+    pub owner: usize,
+    pub kind: VariantShape,
+}
+
+#[derive(Clone)]
+pub(super) enum Bound {
+    Trait(usize),
+    Eq,
+    Ord,
+}
+
+#[derive(Clone)]
+pub(super) enum VariantShape {
+    Unit,
+    Tuple(Vec<Type>),
+    Struct(Vec<(String, Type)>),
+}
+
+impl<'a> TypeChecker<'a> {
+    pub fn check(
+        project: &ProjectNode,
+        tree: &'a ScopeTree,
+        sources: &'a [ScriptSource<'a>],
+    ) -> TypeChecker<'a> {
+        let mut checker = TypeChecker {
+            errors: Vec::new(),
+            literals: HashMap::new(),
+            shared: HashSet::new(),
+            tree,
+            sources,
+            source: 0,
+            types: HashMap::new(),
+            functions: HashMap::new(),
+            structs: HashMap::new(),
+            variants: HashMap::new(),
+            enums: HashMap::new(),
+            required: HashMap::new(),
+            bounds: HashMap::new(),
+            impls: HashMap::new(),
+            return_type: Type::Void,
+        };
+        checker.process(project);
+
+        return checker;
+    }
+
+    fn process(&mut self, project: &ProjectNode) {
+        let mut namespaces = Vec::new();
+        collect_namespaces(&project.root, &mut namespaces);
+
+        // types are known before signatures and signatures before bodies, so
+        // that items can be used before they're declared
+        for node in namespaces.iter() {
+            self.source = node.source;
+            self.collect_types(node);
+        }
+
+        for node in namespaces.iter() {
+            self.source = node.source;
+            self.collect_signatures(node);
+        }
+
+        for node in namespaces.iter() {
+            self.source = node.source;
+            self.check_items(node);
+        }
+    }
+
+    fn collect_types(&mut self, node: &NamespaceNode) {
+        for item in node.items.iter() {
+            match &item.kind {
+                ItemKind::Constant(node) => {
+                    let r#type = self.lower_type(&node.r#type);
+                    self.declare_type(&node.loc, r#type);
+                }
+                ItemKind::Static(node) => {
+                    if let Some(r#type) = &node.r#type {
+                        let r#type = self.lower_type(r#type);
+                        self.declare_type(&node.loc, r#type);
+                    }
+                }
+                ItemKind::Struct(node) => {
+                    let Some(symbol) = self.declared(&node.loc) else {
+                        continue;
+                    };
+
+                    let generics = node
+                        .generics
+                        .iter()
+                        .filter_map(|param| self.declared(&param.loc))
+                        .collect();
+                    let fields = self.lower_fields(&node.fields);
+                    self.declare_bounds(&node.generics);
+
+                    self.structs.insert(symbol, StructType { generics, fields });
+                    self.required.insert(symbol, required_fields(&node.fields));
+                }
+                ItemKind::Enum(node) => {
+                    let Some(owner) = self.declared(&node.loc) else {
+                        continue;
+                    };
+
+                    for variant in node.variants.iter() {
+                        let Some(symbol) = self.declared(&variant.loc) else {
+                            continue;
+                        };
+
+                        let kind = match &variant.kind {
+                            VariantKind::Unit => VariantShape::Unit,
+                            VariantKind::Tuple(types) => VariantShape::Tuple(
+                                types.iter().map(|t| self.lower_type(t)).collect(),
+                            ),
+                            VariantKind::Struct(fields) => {
+                                self.required.insert(symbol, required_fields(fields));
+                                VariantShape::Struct(self.lower_fields(fields))
+                            }
+                        };
+
+                        self.variants.insert(symbol, VariantType { owner, kind });
+                        self.enums.entry(owner).or_default().push(symbol);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn collect_signatures(&mut self, node: &NamespaceNode) {
+        for item in node.items.iter() {
+            match &item.kind {
+                ItemKind::Function(func) => self.declare_function(func, &Type::Unknown),
+                ItemKind::Trait(node) => {
+                    for method in node.methods.iter() {
+                        if let TraitMethodNode::Provided(func) = method {
+                            self.declare_function(func, &Type::Unknown);
+                        }
+                    }
+                }
+                ItemKind::Impl(node) => {
+                    let self_type = self.lower_type(&node.target);
+                    for method in node.methods.iter() {
+                        self.declare_function(&method.function, &self_type);
+                    }
+
+                    if let Some(r#trait) = &node.r#trait
+                        && let Some(symbol) = self.used(&r#trait.loc)
+                    {
+                        self.impls.entry(symbol).or_default().push(self_type);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // statics without a type can call any function
+        for item in node.items.iter() {
+            if let ItemKind::Static(node) = &item.kind
+                && node.r#type.is_none()
+            {
+                let r#type = self.check_expression(&node.value);
+                self.declare_type(&node.loc, r#type);
+            }
+        }
+    }
+
+    fn check_items(&mut self, node: &NamespaceNode) {
+        for item in node.items.iter() {
+            match &item.kind {
+                ItemKind::Constant(node) => {
+                    let expected = self.lower_type(&node.r#type);
+                    self.check_value(&expected, &node.value);
+                }
+                ItemKind::Static(node) => {
+                    if let Some(r#type) = &node.r#type {
+                        let expected = self.lower_type(r#type);
+                        self.check_value(&expected, &node.value);
+                    }
+                }
+                ItemKind::Function(func) => {
+                    self.check_main(func);
+                    self.check_function(func);
+                }
+                ItemKind::Struct(node) => self.check_defaults(&node.fields),
+                ItemKind::Enum(node) => {
+                    for variant in node.variants.iter() {
+                        if let VariantKind::Struct(fields) = &variant.kind {
+                            self.check_defaults(fields);
+                        }
+                    }
+                }
+                ItemKind::Trait(node) => {
+                    for method in node.methods.iter() {
+                        if let TraitMethodNode::Provided(func) = method {
+                            self.check_function(func);
+                        }
+                    }
+                }
+                ItemKind::Impl(node) => {
+                    for method in node.methods.iter() {
+                        self.check_function(&method.function);
+                    }
+                }
+                ItemKind::Use(_) => {}
+            }
+        }
+    }
+
+    // arguments without a type take the type of their default, which is
+    // checked here since it's only evaluated where the function is called
+    fn declare_function(&mut self, func: &FunctionNode, self_type: &Type) {
+        self.declare_bounds(&func.generics);
+
+        let mut params = Vec::new();
+
+        for arg in func.args.iter() {
+            let r#type = match (&arg.r#type, &arg.default) {
+                _ if arg.is_receiver() => self_type.clone(),
+                (Some(r#type), _) => self.lower_type(r#type),
+                (None, Some(default)) => self.check_expression(default),
+                (None, None) => Type::Unknown,
+            };
+
+            self.declare_type(&arg.loc, r#type.clone());
+            params.push(ParamType {
+                r#type,
+                default: arg.default.is_some(),
+            });
+        }
+
+        let signature = FunctionType {
+            params,
+            r#type: self.return_type_of(func),
+            receiver: func.takes_self(),
+        };
+
+        if let Some(symbol) = self.declared(&func.loc) {
+            self.functions.insert(symbol, signature);
+        }
+    }
+
+    fn check_function(&mut self, func: &FunctionNode) {
+        for arg in func.args.iter() {
+            if let (Some(r#type), Some(default)) = (&arg.r#type, &arg.default) {
+                let expected = self.lower_type(r#type);
+                self.check_value(&expected, default);
+            }
+        }
+
+        let r#type = self.return_type_of(func);
+        if func.return_var.is_some()
+            && let Some(node) = &func.r#type
+        {
+            self.declare_type(&node.loc, r#type.clone());
+        }
+
+        self.check_returns(func, &r#type);
+
+        let outer = mem::replace(&mut self.return_type, r#type);
+        self.check_body(&func.body);
+        self.return_type = outer;
+    }
+
+    // builtin bounds aren't bound to a symbol
+    fn declare_bounds(&mut self, generics: &[GenericParamNode]) {
+        for param in generics.iter() {
+            let Some(symbol) = self.declared(&param.loc) else {
+                continue;
+            };
+
+            let bounds = param
+                .bounds
+                .iter()
+                .filter_map(|bound| match self.used(&bound.loc) {
+                    Some(symbol) if self.symbol_kind(symbol) == SymbolKind::Trait => {
+                        Some(Bound::Trait(symbol))
+                    }
+                    Some(_) => None,
+                    None => match bound.name() {
+                        Some("Eq") => Some(Bound::Eq),
+                        Some("Ord") => Some(Bound::Ord),
+                        _ => None,
+                    },
+                })
+                .collect();
+
+            self.bounds.insert(symbol, bounds);
+        }
+    }
+
+    // the program starts at the `main` function of the main file
+    fn check_main(&mut self, func: &FunctionNode) {
+        let is_main = self
+            .declared(&func.loc)
+            .is_some_and(|symbol| self.symbol_name(symbol) == "main");
+        if !is_main {
+            return;
+        }
+
+        let r#type = self.return_type_of(func);
+        if func.args.is_empty()
+            && func.generics.is_empty()
+            && matches!(r#type, Type::INT | Type::Void | Type::Unknown)
+        {
+            return;
+        }
+
+        let msg = "`main` function cannot take arguments or type parameters and can only return int or void";
+        self.mismatch_error(msg, &func.loc);
+    }
+
+    fn return_type_of(&self, func: &FunctionNode) -> Type {
+        match &func.r#type {
+            Some(r#type) => self.lower_type(r#type),
+            None => Type::Void,
+        }
+    }
+
+    fn lower_fields(&self, fields: &[FieldNode]) -> Vec<(String, Type)> {
+        fields
+            .iter()
+            .map(|field| (field.name.clone(), self.lower_type(&field.r#type)))
+            .collect()
+    }
+
+    fn check_defaults(&mut self, fields: &[FieldNode]) {
+        for field in fields.iter() {
+            if let Some(default) = &field.default {
+                let expected = self.lower_type(&field.r#type);
+                self.check_value(&expected, default);
+            }
+        }
+    }
+
+    fn check_body(&mut self, body: &BodyNode) {
+        match &body.kind {
+            BodyKind::Inline(el) => self.check_body_element(&el.kind),
+            BodyKind::Multiline(els) => els.iter().for_each(|el| self.check_body_element(&el.kind)),
+        }
+    }
+
+    fn check_body_element(&mut self, el: &BodyElementKind) {
+        match el {
+            BodyElementKind::Statement(stmt) => self.check_statement(stmt),
+            BodyElementKind::Body(body) => self.check_body(body),
+            BodyElementKind::Empty => {}
+        }
+    }
+
+    fn check_statement(&mut self, stmt: &StatementNode) {
+        match &stmt.kind {
+            StatementKind::Expression(expr) => {
+                self.check_expression(expr);
+            }
+            StatementKind::VariableDeclaration(var) => self.check_variable_declaration(var),
+            StatementKind::FunctionDeclaration(func) => {
+                self.declare_function(func, &Type::Unknown);
+                self.check_function(func);
+            }
+            StatementKind::If(node) => self.check_if(node),
+            StatementKind::WhileLoop(node) => self.check_while(node),
+            StatementKind::ForLoop(node) => {
+                let element = self.check_iterable(&node.iterable);
+                self.check_pattern(&node.pattern, &element);
+                self.check_irrefutable(&node.pattern, &element, "`for` loop");
+                self.check_body(&node.body);
+            }
+            StatementKind::Match(node) => {
+                let r#type = self.check_expression(&node.value);
+                for arm in node.arms.iter() {
+                    self.check_arm(arm, &r#type);
+                    self.check_body(&arm.body);
+                }
+
+                self.check_exhaustive(&node.arms, &r#type, &node.value.loc);
+            }
+            StatementKind::Return(expr) => {
+                let expected = self.return_type.clone();
+                let found = self.check_as(&expected, expr);
+                if self.return_type == Type::Void {
+                    self.mismatch_error("function does not return a value", &expr.loc);
+                    return;
+                }
+
+                self.expect(&expected, &found, &expr.loc);
+            }
+            StatementKind::Break(_) | StatementKind::Continue(_) => {}
+        }
+    }
+
+    fn check_variable_declaration(&mut self, var: &VariableDeclarationNode) {
+        let expected = var.r#type.as_ref().map(|r#type| self.lower_type(r#type));
+
+        let r#type = match &var.value {
+            Some(value) => {
+                let found = match &expected {
+                    Some(expected) => self.check_value(expected, value),
+                    None => self.check_expression(value),
+                };
+
+                if found == Type::Void {
+                    let msg = format!("variable `{}` cannot be of type void", var.name);
+                    self.mismatch_error(msg, &value.loc);
+                }
+
+                expected.unwrap_or(found)
+            }
+            None => expected.unwrap_or(Type::Unknown),
+        };
+
+        self.declare_type(&var.loc, r#type);
+    }
+
+    fn check_if(&mut self, node: &IfNode) {
+        if let IfKind::If { cond, .. } = &node.kind {
+            self.check_condition(cond);
+        }
+
+        self.check_body(&node.body);
+
+        if let IfKind::If {
+            r#else: Some(r#else),
+            ..
+        } = &node.kind
+        {
+            self.check_if(r#else);
+        }
+    }
+
+    fn check_while(&mut self, node: &WhileLoopNode) {
+        if let WhileLoopKind::While { cond, .. } = &node.kind {
+            self.check_condition(cond);
+        }
+
+        self.check_body(&node.body);
+
+        if let WhileLoopKind::While {
+            r#else: Some(r#else),
+            ..
+        } = &node.kind
+        {
+            self.check_while(r#else);
+        }
+    }
+
+    fn check_condition(&mut self, expr: &ExpressionNode) {
+        self.check_value(&Type::Bool, expr);
+    }
+
+    // the type of the elements a `for` loop goes through
+    fn check_iterable(&mut self, expr: &ExpressionNode) -> Type {
+        if let ExpressionKind::Range { start, end, .. } = &expr.kind {
+            return self.check_range(start, end);
+        }
+
+        match self.check_expression(expr) {
+            Type::Array(element) => *element,
+            Type::Dict(key, value) => Type::Tuple(vec![*key, *value]),
+            r#type if r#type.is_known() => {
+                let msg = format!("`{}` cannot be iterated over", r#type.name(self.tree));
+                self.mismatch_error(msg, &expr.loc);
+                Type::Unknown
+            }
+            _ => Type::Unknown,
+        }
+    }
+
+    pub(super) fn check_arm<T>(&mut self, arm: &MatchArmNode<T>, r#type: &Type) {
+        self.check_pattern(&arm.pattern, r#type);
+
+        if let Some(guard) = &arm.guard {
+            self.check_condition(guard);
+        }
+    }
+
+    // gives the bindings in the pattern their types
+    fn check_pattern(&mut self, pattern: &PatternNode, r#type: &Type) {
+        match &pattern.kind {
+            PatternKind::Wildcard => {}
+            PatternKind::Literal {
+                kind,
+                value,
+                negative,
+            } => {
+                let found = match kind.is_number() {
+                    true => {
+                        let literal = NumberLiteral {
+                            kind,
+                            value,
+                            suffix: None,
+                            negative: *negative,
+                        };
+                        self.check_number(r#type, &literal, &pattern.loc)
+                    }
+                    false => Type::literal(kind),
+                };
+
+                self.expect(r#type, &found, &pattern.loc);
+            }
+            PatternKind::Binding { .. } => match self.used(&pattern.loc) {
+                // a unit variant, or the same binding in another alternative
+                // of an or-pattern
+                Some(symbol) => match self.variants.get(&symbol).map(|v| v.owner) {
+                    Some(owner) => self.expect(r#type, &Type::Enum(owner), &pattern.loc),
+                    None => {
+                        let bound = self.type_of(symbol);
+                        if !bound.fits(r#type) {
+                            let msg = format!(
+                                "`{}` is `{}` in one alternative and `{}` in another",
+                                self.symbol_name(symbol),
+                                self.type_name(&bound),
+                                self.type_name(r#type)
+                            );
+                            self.mismatch_error(msg, &pattern.loc);
+                        }
+                    }
+                },
+                None => self.declare_type(&pattern.loc, r#type.clone()),
+            },
+            PatternKind::Variant { payload, .. } => {
+                let variant = self
+                    .used(&pattern.loc)
+                    .and_then(|symbol| self.variants.get(&symbol))
+                    .cloned();
+
+                let Some(variant) = variant else {
+                    self.check_payload(payload, |_| Type::Unknown);
+                    return;
+                };
+
+                self.expect(r#type, &Type::Enum(variant.owner), &pattern.loc);
+
+                match &variant.kind {
+                    VariantShape::Tuple(types) => self.check_payload(payload, |idx| {
+                        types.get(idx).cloned().unwrap_or(Type::Unknown)
+                    }),
+                    VariantShape::Struct(fields) => {
+                        let PatternPayload::Struct {
+                            fields: patterns, ..
+                        } = payload
+                        else {
+                            self.check_payload(payload, |_| Type::Unknown);
+                            return;
+                        };
+
+                        for field in patterns.iter() {
+                            let r#type = fields
+                                .iter()
+                                .find(|(name, _)| *name == field.name)
+                                .map_or(Type::Unknown, |(_, r#type)| r#type.clone());
+
+                            self.check_pattern(&field.pattern, &r#type);
+                        }
+                    }
+                    VariantShape::Unit => self.check_payload(payload, |_| Type::Unknown),
+                }
+            }
+            PatternKind::Tuple(patterns) => {
+                for (idx, pattern) in patterns.iter().enumerate() {
+                    let r#type = match r#type {
+                        Type::Tuple(types) if types.len() == patterns.len() => types[idx].clone(),
+                        _ => Type::Unknown,
+                    };
+
+                    self.check_pattern(pattern, &r#type);
+                }
+            }
+            PatternKind::Or(alternatives) => {
+                let mut first = None;
+                for alternative in alternatives.iter() {
+                    self.check_pattern(alternative, r#type);
+
+                    let mut names = Vec::new();
+                    self.binding_names(alternative, &mut names);
+                    names.sort();
+
+                    match &first {
+                        None => first = Some(names),
+                        Some(first) if *first != names => {
+                            let msg = "every alternative has to bind the same names";
+                            self.mismatch_error(msg, &alternative.loc);
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+    }
+
+    // unit variants look like bindings, but they're bound to their variant
+    fn binding_names(&self, pattern: &PatternNode, names: &mut Vec<String>) {
+        match &pattern.kind {
+            PatternKind::Wildcard | PatternKind::Literal { .. } => {}
+            PatternKind::Binding { name } => {
+                let is_variant = self
+                    .used(&pattern.loc)
+                    .is_some_and(|symbol| self.variants.contains_key(&symbol));
+                if !is_variant {
+                    names.push(name.clone());
+                }
+            }
+            PatternKind::Variant { payload, .. } => match payload {
+                PatternPayload::Unit => {}
+                PatternPayload::Tuple(patterns) => {
+                    patterns.iter().for_each(|p| self.binding_names(p, names));
+                }
+                PatternPayload::Struct { fields, .. } => {
+                    fields
+                        .iter()
+                        .for_each(|field| self.binding_names(&field.pattern, names));
+                }
+            },
+            PatternKind::Tuple(patterns) => {
+                patterns.iter().for_each(|p| self.binding_names(p, names));
+            }
+            // nested alternatives were checked to bind the same names
+            PatternKind::Or(alternatives) => {
+                if let Some(first) = alternatives.first() {
+                    self.binding_names(first, names);
+                }
+            }
+        }
+    }
+
+    // payloads that don't match the shape of their variant are reported by
+    // codegen, their bindings are still given a type
+    fn check_payload(&mut self, payload: &PatternPayload, type_of: impl Fn(usize) -> Type) {
+        match payload {
+            PatternPayload::Unit => {}
+            PatternPayload::Tuple(patterns) => {
+                for (idx, pattern) in patterns.iter().enumerate() {
+                    self.check_pattern(pattern, &type_of(idx));
+                }
+            }
+            PatternPayload::Struct { fields, .. } => {
+                for field in fields.iter() {
+                    self.check_pattern(&field.pattern, &Type::Unknown);
+                }
+            }
+        }
+    }
+
+    // names were already resolved, so types the resolver didn't bind to a
+    // symbol are builtins
+    pub(super) fn lower_type(&self, node: &TypeNode) -> Type {
+        match &node.kind {
+            TypeKind::Path { path, generics } => {
+                if let Some(name) = node.name()
+                    && let Some(r#type) = Type::primitive(name)
+                {
+                    return r#type;
+                }
+
+                let mut generics: Vec<Type> = generics.iter().map(|t| self.lower_type(t)).collect();
+
+                let Some(symbol) = self.used(&node.loc) else {
+                    return match (path.last().map(String::as_str), generics.len()) {
+                        (Some("Dict"), 2) => {
+                            let value = generics.pop().unwrap();
+                            let key = generics.pop().unwrap();
+                            Type::Dict(Box::new(key), Box::new(value))
+                        }
+                        _ => Type::Unknown,
+                    };
+                };
+
+                match self.tree.symbols[symbol].kind {
+                    SymbolKind::Struct => Type::Struct(symbol, generics),
+                    SymbolKind::Enum => Type::Enum(symbol),
+                    SymbolKind::TypeParam => Type::Param(symbol),
+                    _ => Type::Unknown,
+                }
+            }
+            TypeKind::Array { element, .. } | TypeKind::Slice { element } => {
+                Type::Array(Box::new(self.lower_type(element)))
+            }
+            TypeKind::Tuple { elements } => {
+                Type::Tuple(elements.iter().map(|t| self.lower_type(t)).collect())
+            }
+            TypeKind::Function { .. } | TypeKind::Optional { .. } => Type::Unknown,
+        }
+    }
+
+    pub(super) fn type_of(&self, symbol: usize) -> Type {
+        if let Some(variant) = self.variants.get(&symbol) {
+            return Type::Enum(variant.owner);
+        }
+
+        self.types.get(&symbol).cloned().unwrap_or(Type::Unknown)
+    }
+
+    fn declare_type(&mut self, loc: &Loc, r#type: Type) {
+        if let Some(symbol) = self.declared(loc) {
+            self.types.insert(symbol, r#type);
+        }
+    }
+
+    fn declared(&self, loc: &Loc) -> Option<usize> {
+        self.tree
+            .declarations
+            .get(&(self.source, loc.clone()))
+            .copied()
+    }
+
+    pub(super) fn used(&self, loc: &Loc) -> Option<usize> {
+        self.tree.uses.get(&(self.source, loc.clone())).copied()
+    }
+
+    pub(super) fn symbol_name(&self, symbol: usize) -> &str {
+        &self.tree.symbols[symbol].name
+    }
+
+    pub(super) fn symbol_kind(&self, symbol: usize) -> SymbolKind {
+        self.tree.symbols[symbol].kind
+    }
+
+    pub(super) fn members(&self, symbol: usize) -> Option<&HashMap<String, usize>> {
+        let scope = self.tree.members.get(&symbol)?;
+        Some(&self.tree.scopes[*scope].names)
+    }
+
+    pub(super) fn type_name(&self, r#type: &Type) -> String {
+        r#type.name(self.tree)
+    }
+
+    pub(super) fn check_value(&mut self, expected: &Type, expr: &ExpressionNode) -> Type {
+        let found = self.check_as(expected, expr);
+        self.expect(expected, &found, &expr.loc);
+
+        return found;
+    }
+
+    pub(super) fn expect(&mut self, expected: &Type, found: &Type, loc: &Loc) {
+        if expected.fits(found) {
+            return;
+        }
+
+        let msg = format!(
+            "expected `{}`, found `{}`",
+            self.type_name(expected),
+            self.type_name(found)
+        );
+        self.mismatch_error(msg, loc);
+    }
+
+    pub(super) fn mismatch_error<T: ToString>(&mut self, msg: T, loc: &Loc) {
+        let err = TypeError::default()
+            .kind(TypeErrorKind::MismatchedTypes)
+            .msg(msg);
+        self.push_error(err, loc);
+    }
+
+    pub(super) fn push_error(&mut self, err: TypeError, loc: &Loc) {
+        let src = &self.sources[self.source];
+        let ctx = ErrorContextBuilder::loc(loc)
+            .from_src_and_ln(src, loc.ln)
+            .build();
+
+        self.errors.push(err.ctx(ctx));
+    }
+}
+
+fn collect_namespaces<'n>(node: &'n NamespaceNode, namespaces: &mut Vec<&'n NamespaceNode>) {
+    namespaces.push(node);
+
+    for child in node.children.iter() {
+        collect_namespaces(child, namespaces);
+    }
+}
+
+fn required_fields(fields: &[FieldNode]) -> Vec<String> {
+    fields
+        .iter()
+        .filter(|field| field.default.is_none())
+        .map(|field| field.name.clone())
+        .collect()
+}
@@ -0,0 +1,374 @@
+use crate::{
+    errors::types::{TypeError, TypeErrorKind},
+    frontend::{
+        lexing::LiteralKind,
+        parsing::ast::patterns::{MatchArmNode, PatternKind, PatternNode, PatternPayload},
+        Loc,
+    },
+};
+
+use super::{
+    checker::{TypeChecker, VariantShape},
+    types::Type,
+};
+
+const MAX_LISTED_WITNESSES: usize = 3;
+
+// patterns reduced to the values they cover. patterns that don't fit the
+// type they're matched against were already reported, they're wildcards here
+// so that they don't get reported again
+enum Pattern {
+    Wildcard,
+    // literals other than bools, which never cover all values of their type
+    Literal,
+    Bool(bool),
+    // omitted struct fields are wildcards, so the fields always follow the
+    // order of the variant fields
+    Variant(usize, Vec<Pattern>),
+    Tuple(Vec<Pattern>),
+    Or(Vec<Pattern>),
+}
+
+static WILDCARD: Pattern = Pattern::Wildcard;
+
+// what a value missing from a match looks like, used for error messages
+enum Witness {
+    Any,
+    Bool(bool),
+    Variant(usize, Vec<Witness>),
+    Tuple(Vec<Witness>),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Constructor {
+    Bool(bool),
+    Variant(usize),
+    Tuple,
+}
+
+impl TypeChecker<'_> {
+    // guards can fail, so guarded arms don't count towards exhaustiveness
+    pub(super) fn check_exhaustive<T>(
+        &mut self,
+        arms: &[MatchArmNode<T>],
+        r#type: &Type,
+        loc: &Loc,
+    ) {
+        let patterns: Vec<Pattern> = arms
+            .iter()
+            .filter(|arm| arm.guard.is_none())
+            .map(|arm| self.reduce_pattern(&arm.pattern, r#type))
+            .collect();
+
+        let rows = patterns.iter().map(|pattern| vec![pattern]).collect();
+        let missing = self.missing_patterns(rows, std::slice::from_ref(r#type));
+        if missing.is_empty() {
+            return;
+        }
+
+        let mut listed: Vec<String> = missing
+            .iter()
+            .take(MAX_LISTED_WITNESSES)
+            .map(|witness| format!("`{}`", self.witness_to_string(&witness[0])))
+            .collect();
+
+        if missing.len() > MAX_LISTED_WITNESSES {
+            listed.push(format!("and {} more", missing.len() - MAX_LISTED_WITNESSES));
+        }
+
+        let err = TypeError::default()
+            .kind(TypeErrorKind::NonExhaustive)
+            .msg(format!("match does not cover {}", listed.join(", ")));
+        self.push_error(err, loc);
+    }
+
+    // patterns that bind without testing, like the one of a `for` loop, have
+    // to match every value
+    pub(super) fn check_irrefutable(&mut self, node: &PatternNode, r#type: &Type, context: &str) {
+        let pattern = self.reduce_pattern(node, r#type);
+        let missing = self.missing_patterns(vec![vec![&pattern]], std::slice::from_ref(r#type));
+
+        if let Some(witness) = missing.first() {
+            let err = TypeError::default()
+                .kind(TypeErrorKind::NonExhaustive)
+                .msg(format!(
+                    "pattern in {context} does not cover `{}`",
+                    self.witness_to_string(&witness[0])
+                ));
+            self.push_error(err, &node.loc);
+        }
+    }
+
+    fn reduce_pattern(&self, node: &PatternNode, r#type: &Type) -> Pattern {
+        match &node.kind {
+            PatternKind::Wildcard => Pattern::Wildcard,
+            // unit variants brought in with `use` match instead of binding
+            PatternKind::Binding { .. } => match self.variant_of(&node.loc, r#type) {
+                Some(variant) => Pattern::Variant(variant, Vec::new()),
+                None => Pattern::Wildcard,
+            },
+            PatternKind::Literal { kind, value, .. } => match (kind, r#type) {
+                (LiteralKind::Boolean, Type::Bool) => Pattern::Bool(value == "true"),
+                (LiteralKind::Integer, Type::Int(_))
+                | (LiteralKind::Float, Type::Float(_))
+                | (LiteralKind::Char, Type::Char)
+                | (LiteralKind::String, Type::String) => Pattern::Literal,
+                _ => Pattern::Wildcard,
+            },
+            PatternKind::Variant { payload, .. } => {
+                let Some(variant) = self.variant_of(&node.loc, r#type) else {
+                    return Pattern::Wildcard;
+                };
+
+                let fields = match (&self.variants[&variant].kind, payload) {
+                    (VariantShape::Unit, PatternPayload::Unit) => Vec::new(),
+                    (VariantShape::Tuple(types), PatternPayload::Tuple(patterns))
+                        if types.len() == patterns.len() =>
+                    {
+                        patterns
+                            .iter()
+                            .zip(types.iter())
+                            .map(|(pattern, r#type)| self.reduce_pattern(pattern, r#type))
+                            .collect()
+                    }
+                    (
+                        VariantShape::Struct(fields),
+                        PatternPayload::Struct {
+                            fields: patterns, ..
+                        },
+                    ) => fields
+                        .iter()
+                        .map(|(name, r#type)| {
+                            match patterns.iter().find(|field| field.name == *name) {
+                                Some(field) => self.reduce_pattern(&field.pattern, r#type),
+                                None => Pattern::Wildcard,
+                            }
+                        })
+                        .collect(),
+                    _ => return Pattern::Wildcard,
+                };
+
+                Pattern::Variant(variant, fields)
+            }
+            PatternKind::Tuple(patterns) => match r#type {
+                Type::Tuple(types) if types.len() == patterns.len() => Pattern::Tuple(
+                    patterns
+                        .iter()
+                        .zip(types.iter())
+                        .map(|(pattern, r#type)| self.reduce_pattern(pattern, r#type))
+                        .collect(),
+                ),
+                _ => Pattern::Wildcard,
+            },
+            PatternKind::Or(alternatives) => Pattern::Or(
+                alternatives
+                    .iter()
+                    .map(|alternative| self.reduce_pattern(alternative, r#type))
+                    .collect(),
+            ),
+        }
+    }
+
+    // the variant the pattern at `loc` names, if it's one of `r#type`
+    fn variant_of(&self, loc: &Loc, r#type: &Type) -> Option<usize> {
+        let symbol = self.used(loc)?;
+        let variant = self.variants.get(&symbol)?;
+
+        match r#type {
+            Type::Enum(owner) if *owner == variant.owner => Some(symbol),
+            _ => None,
+        }
+    }
+
+    // the values no row of the matrix covers, each as one witness per
+    // column. this is the usefulness algorithm from Maranget's "Warnings for
+    // pattern matching", asking whether a row of wildcards would be useful
+    fn missing_patterns(&self, rows: Vec<Vec<&Pattern>>, types: &[Type]) -> Vec<Vec<Witness>> {
+        let Some((r#type, rest_types)) = types.split_first() else {
+            return match rows.is_empty() {
+                true => vec![Vec::new()],
+                false => Vec::new(),
+            };
+        };
+
+        let rows = expand_or_patterns(rows);
+
+        let constructors = match r#type {
+            Type::Bool => vec![Constructor::Bool(false), Constructor::Bool(true)],
+            Type::Enum(owner) => self
+                .enums
+                .get(owner)
+                .map(|variants| variants.iter().copied().map(Constructor::Variant).collect())
+                .unwrap_or_default(),
+            Type::Tuple(_) => vec![Constructor::Tuple],
+            _ => Vec::new(),
+        };
+
+        // splitting on every constructor once any of them is used, rather than
+        // only when all of them are, names each missing case in full.
+        // columns of wildcards are never split, which keeps recursive types
+        // from being unfolded forever
+        let used = rows.iter().any(|row| head_constructor(row[0]).is_some());
+
+        if used {
+            let mut missing = Vec::new();
+
+            for ctor in constructors {
+                let field_types = self.constructor_fields(r#type, ctor);
+                let specialized = specialize(&rows, ctor, field_types.len());
+
+                let mut types = field_types.clone();
+                types.extend_from_slice(rest_types);
+
+                for mut witness in self.missing_patterns(specialized, &types) {
+                    let rest = witness.split_off(field_types.len());
+                    let mut row = vec![constructor_witness(ctor, witness)];
+                    row.extend(rest);
+                    missing.push(row);
+                }
+            }
+
+            return missing;
+        }
+
+        let defaults: Vec<Vec<&Pattern>> = rows
+            .iter()
+            .filter(|row| matches!(row[0], Pattern::Wildcard))
+            .map(|row| row[1..].to_vec())
+            .collect();
+
+        let mut missing = self.missing_patterns(defaults, rest_types);
+        for witness in missing.iter_mut() {
+            witness.insert(0, Witness::Any);
+        }
+
+        return missing;
+    }
+
+    fn constructor_fields(&self, r#type: &Type, ctor: Constructor) -> Vec<Type> {
+        match (r#type, ctor) {
+            (_, Constructor::Variant(variant)) => match &self.variants[&variant].kind {
+                VariantShape::Unit => Vec::new(),
+                VariantShape::Tuple(types) => types.clone(),
+                VariantShape::Struct(fields) => {
+                    fields.iter().map(|(_, r#type)| r#type.clone()).collect()
+                }
+            },
+            (Type::Tuple(types), Constructor::Tuple) => types.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn witness_to_string(&self, witness: &Witness) -> String {
+        let (variant, fields) = match witness {
+            Witness::Any => return "_".to_owned(),
+            Witness::Bool(bool) => return bool.to_string(),
+            Witness::Variant(variant, fields) => (*variant, fields),
+            Witness::Tuple(fields) => {
+                let fields: Vec<String> =
+                    fields.iter().map(|f| self.witness_to_string(f)).collect();
+
+                return match fields.len() {
+                    1 => format!("({},)", fields[0]),
+                    _ => format!("({})", fields.join(", ")),
+                };
+            }
+        };
+
+        // variants are named after their enum, without its namespace
+        let path = self.symbol_name(variant);
+        let name = match path.rsplitn(3, "::").collect::<Vec<_>>()[..] {
+            [variant, owner, ..] => format!("{owner}::{variant}"),
+            _ => path.to_owned(),
+        };
+
+        match &self.variants[&variant].kind {
+            VariantShape::Unit => name,
+            VariantShape::Tuple(_) => {
+                let fields: Vec<String> =
+                    fields.iter().map(|f| self.witness_to_string(f)).collect();
+                format!("{name}({})", fields.join(", "))
+            }
+            VariantShape::Struct(shape) => {
+                let mut named: Vec<String> = shape
+                    .iter()
+                    .zip(fields.iter())
+                    .filter(|(_, witness)| !matches!(witness, Witness::Any))
+                    .map(|((field, _), witness)| {
+                        format!("{field}: {}", self.witness_to_string(witness))
+                    })
+                    .collect();
+
+                if named.len() < shape.len() {
+                    named.push("..".to_owned());
+                }
+
+                match named.is_empty() {
+                    true => format!("{name} {{}}"),
+                    false => format!("{name} {{ {} }}", named.join(", ")),
+                }
+            }
+        }
+    }
+}
+
+fn expand_or_patterns(rows: Vec<Vec<&Pattern>>) -> Vec<Vec<&Pattern>> {
+    let mut expanded = Vec::new();
+
+    for row in rows {
+        let Pattern::Or(alternatives) = row[0] else {
+            expanded.push(row);
+            continue;
+        };
+
+        let alternatives = alternatives.iter().map(|alternative| {
+            let mut row = row.clone();
+            row[0] = alternative;
+            row
+        });
+        expanded.extend(expand_or_patterns(alternatives.collect()));
+    }
+
+    return expanded;
+}
+
+fn head_constructor(pattern: &Pattern) -> Option<Constructor> {
+    match pattern {
+        Pattern::Bool(bool) => Some(Constructor::Bool(*bool)),
+        Pattern::Variant(variant, _) => Some(Constructor::Variant(*variant)),
+        Pattern::Tuple(_) => Some(Constructor::Tuple),
+        _ => None,
+    }
+}
+
+fn constructor_witness(ctor: Constructor, fields: Vec<Witness>) -> Witness {
+    match ctor {
+        Constructor::Bool(bool) => Witness::Bool(bool),
+        Constructor::Variant(variant) => Witness::Variant(variant, fields),
+        Constructor::Tuple => Witness::Tuple(fields),
+    }
+}
+
+fn specialize<'p>(
+    rows: &[Vec<&'p Pattern>],
+    ctor: Constructor,
+    arity: usize,
+) -> Vec<Vec<&'p Pattern>> {
+    let mut specialized = Vec::new();
+
+    for row in rows.iter() {
+        let mut fields: Vec<&Pattern> = match row[0] {
+            Pattern::Wildcard => vec![&WILDCARD; arity],
+            pattern if head_constructor(pattern) == Some(ctor) => match pattern {
+                Pattern::Variant(_, fields) | Pattern::Tuple(fields) => fields.iter().collect(),
+                _ => Vec::new(),
+            },
+            _ => continue,
+        };
+
+        fields.extend_from_slice(&row[1..]);
+        specialized.push(fields);
+    }
+
+    return specialized;
+}
@@ -0,0 +1,967 @@
+use std::collections::HashMap;
+
+use crate::{
+    errors::types::{TypeError, TypeErrorKind},
+    frontend::{
+        evaluating::ConstValue,
+        lexing::{AssignmentKind, ComplexPunctuationKind, LiteralKind},
+        modules::{is_builtin_namespace, is_builtin_type, std_path},
+        parsing::ast::{
+            expressions::{
+                BinaryExpressionKind, ExpressionKind, ExpressionNode, FieldValueNode,
+                InterpolationPart, NumberLiteral, ResolutionExpressionKind, UnaryExpressionKind,
+            },
+            patterns::MatchArmNode,
+            types::TypeNode,
+        },
+        primitives::PrimitiveType,
+        resolving::SymbolKind,
+        Loc,
+    },
+    utils::text::count_mismatch,
+};
+
+use super::{
+    checker::{Bound, TypeChecker, VariantShape},
+    types::Type,
+};
+
+impl TypeChecker<'_> {
+    pub(super) fn check_expression(&mut self, expr: &ExpressionNode) -> Type {
+        self.check_as(&Type::Unknown, expr)
+    }
+
+    // unsuffixed number literals take the numeric type they're expected to
+    // be, which reaches them through the operators that keep the type of
+    // their operands, like `x: uint8 = 2 * (1 + 3)`
+    pub(super) fn check_as(&mut self, expected: &Type, expr: &ExpressionNode) -> Type {
+        if let Some(literal) = expr.number() {
+            return self.check_number(expected, &literal, &expr.loc);
+        }
+
+        let r#type = match &expr.kind {
+            ExpressionKind::Literal { kind, .. } => Type::literal(kind),
+            ExpressionKind::Resolution {
+                left,
+                right,
+                kind: ResolutionExpressionKind::Member,
+            } => self.check_member(left, right),
+            ExpressionKind::Identifier { .. } | ExpressionKind::Resolution { .. } => {
+                self.check_name(expr)
+            }
+            ExpressionKind::Interpolation { parts } => {
+                for part in parts.iter() {
+                    if let InterpolationPart::Expression(expr) = part {
+                        self.check_expression(expr);
+                    }
+                }
+
+                Type::String
+            }
+            ExpressionKind::Call { res, args } => self.check_call(res, args, &expr.loc),
+            ExpressionKind::MethodCall {
+                object,
+                method,
+                args,
+            } => self.check_method_call(object, method, args, &expr.loc),
+            ExpressionKind::Struct { res, fields } => self.check_struct(res, fields, &expr.loc),
+            ExpressionKind::Array { elements } => {
+                let expected = match expected {
+                    Type::Array(element) => *element.clone(),
+                    _ => Type::Unknown,
+                };
+
+                let element = self.check_elements(expected, elements.iter());
+                Type::Array(Box::new(element))
+            }
+            ExpressionKind::Tuple { elements } => {
+                let expected = match expected {
+                    Type::Tuple(types) if types.len() == elements.len() => types.clone(),
+                    _ => vec![Type::Unknown; elements.len()],
+                };
+
+                Type::Tuple(
+                    elements
+                        .iter()
+                        .zip(&expected)
+                        .map(|(el, expected)| self.check_as(expected, el))
+                        .collect(),
+                )
+            }
+            ExpressionKind::Index { object, index } => self.check_index(object, index),
+            // codegen only allows ranges in `for` loops
+            ExpressionKind::Range { start, end, .. } => {
+                self.check_range(start, end);
+                Type::Unknown
+            }
+            ExpressionKind::Binary {
+                kind,
+                left,
+                op,
+                right,
+            } => match kind {
+                BinaryExpressionKind::LogicalOr | BinaryExpressionKind::LogicalAnd => {
+                    self.check_value(&Type::Bool, left);
+                    self.check_value(&Type::Bool, right);
+                    Type::Bool
+                }
+                _ => {
+                    let expected = if kind.keeps_type() {
+                        expected
+                    } else {
+                        &Type::Unknown
+                    };
+
+                    let (left, right) = self.check_operands(expected, left, right);
+                    let r#type = self.check_operator(kind, op, &left, &right, &expr.loc);
+                    self.check_folded(expr, &r#type);
+
+                    r#type
+                }
+            },
+            ExpressionKind::Unary { kind, op, right } => {
+                let r#type = match kind {
+                    UnaryExpressionKind::LogicalNegation => self.check_expression(right),
+                    _ => self.check_as(expected, right),
+                };
+                let supported = match kind {
+                    UnaryExpressionKind::ArithmeticNegation => match r#type {
+                        Type::Int(int) => int.is_signed(),
+                        Type::Float(_) => true,
+                        _ => false,
+                    },
+                    UnaryExpressionKind::LogicalNegation => r#type == Type::Bool,
+                    UnaryExpressionKind::BitwiseNegation => matches!(r#type, Type::Int(_)),
+                };
+
+                if r#type.is_known() && !supported {
+                    self.operator_error(op, &r#type, &expr.loc);
+                } else {
+                    self.check_folded(expr, &r#type);
+                }
+
+                r#type
+            }
+            ExpressionKind::Assignment { left, op, right } => {
+                self.check_assignment(left, op, right, &expr.loc)
+            }
+            ExpressionKind::Match { value, arms } => self.check_match(value, arms),
+            ExpressionKind::Cast { value, r#type } => self.check_cast(value, r#type),
+        };
+
+        if r#type.is_shared() {
+            self.shared.insert((self.source, expr.loc.clone()));
+        }
+
+        return r#type;
+    }
+
+    pub(super) fn check_number(
+        &mut self,
+        expected: &Type,
+        literal: &NumberLiteral,
+        loc: &Loc,
+    ) -> Type {
+        let r#type = match (expected, literal.suffix) {
+            (_, Some(suffix)) => Type::from(suffix.r#type()),
+            (Type::Int(_), None) if *literal.kind == LiteralKind::Integer => expected.clone(),
+            (Type::Float(_), None) if *literal.kind == LiteralKind::Float => expected.clone(),
+            // the rest are left to codegen, inside generic functions they
+            // can take the type a type parameter stands for
+            _ => return self.check_fits(Type::literal(literal.kind), literal, loc),
+        };
+
+        if let Some(primitive) = r#type.as_primitive() {
+            self.literals.insert((self.source, loc.clone()), primitive);
+        }
+
+        return self.check_fits(r#type, literal, loc);
+    }
+
+    fn check_fits(&mut self, r#type: Type, literal: &NumberLiteral, loc: &Loc) -> Type {
+        let fits = match &r#type {
+            Type::Int(int) => int.parse(literal.value, literal.negative).is_some(),
+            Type::Float(float) => float.parse(literal.value, literal.negative).is_some(),
+            _ => true,
+        };
+
+        if !fits {
+            let err = TypeError::default()
+                .kind(TypeErrorKind::LiteralOutOfRange)
+                .msg(format!(
+                    "literal `{}` does not fit in `{}`",
+                    literal.to_string(),
+                    self.type_name(&r#type)
+                ));
+            self.push_error(err, loc);
+        }
+
+        return r#type;
+    }
+
+    // arithmetic on nothing but literals is worked out, so what overflows
+    // its type is reported like a literal that doesn't fit in it. dividing
+    // by zero is left to the checks made at runtime
+    fn check_folded(&mut self, expr: &ExpressionNode, r#type: &Type) {
+        let Type::Int(int) = r#type else {
+            return;
+        };
+
+        let expression = match &expr.kind {
+            ExpressionKind::Binary {
+                left, op, right, ..
+            } => {
+                let (Some(left), Some(right)) = (self.fold(left), self.fold(right)) else {
+                    return;
+                };
+
+                let divides_by_zero = matches!(
+                    (op, &left, &right),
+                    (
+                        ComplexPunctuationKind::OperatorDivide
+                            | ComplexPunctuationKind::OperatorModulo,
+                        _,
+                        ConstValue::Int(_, 0),
+                    ) | (
+                        ComplexPunctuationKind::OperatorPower,
+                        ConstValue::Int(_, 0),
+                        _
+                    )
+                );
+
+                if divides_by_zero || left.binary(op, &right).is_ok() {
+                    return;
+                }
+
+                format!("{left} {} {right}", op.to_string())
+            }
+            ExpressionKind::Unary { kind, op, right } => {
+                let Some(right) = self.fold(right) else {
+                    return;
+                };
+
+                if right.unary(kind).is_ok() {
+                    return;
+                }
+
+                format!("{}{right}", op.to_string())
+            }
+            _ => return,
+        };
+
+        let err = TypeError::default()
+            .kind(TypeErrorKind::LiteralOutOfRange)
+            .msg(format!(
+                "`{expression}` does not fit in `{}`",
+                PrimitiveType::Int(*int).name()
+            ));
+        self.push_error(err, &expr.loc);
+    }
+
+    // the value of literal arithmetic, with the types the literals were given
+    fn fold(&self, expr: &ExpressionNode) -> Option<ConstValue> {
+        if let Some(literal) = expr.number() {
+            let r#type = match self.literals.get(&(self.source, expr.loc.clone())) {
+                Some(primitive) => *primitive,
+                None => Type::literal(literal.kind).as_primitive()?,
+            };
+
+            return match r#type {
+                PrimitiveType::Int(int) => int
+                    .parse(literal.value, literal.negative)
+                    .map(|value| ConstValue::Int(int, value)),
+                PrimitiveType::Float(float) => float
+                    .parse(literal.value, literal.negative)
+                    .map(|value| ConstValue::Float(float, float.round(value))),
+                _ => None,
+            };
+        }
+
+        match &expr.kind {
+            ExpressionKind::Binary {
+                kind,
+                left,
+                op,
+                right,
+            } if kind.keeps_type() => self.fold(left)?.binary(op, &self.fold(right)?).ok(),
+            ExpressionKind::Unary { kind, right, .. } => self.fold(right)?.unary(kind).ok(),
+            _ => None,
+        }
+    }
+
+    // builtins aren't bound to a symbol and stay unknown, names that aren't
+    // values were reported by the resolver
+    fn check_name(&mut self, expr: &ExpressionNode) -> Type {
+        let Some(symbol) = self.used(&expr.loc) else {
+            return Type::Unknown;
+        };
+
+        let hint = match self.variants.get(&symbol).map(|variant| &variant.kind) {
+            Some(VariantShape::Tuple(_)) => "(..)",
+            Some(VariantShape::Struct(_)) => " { .. }",
+            _ => return self.type_of(symbol),
+        };
+
+        let path = expr.path().unwrap();
+        let msg = format!("`{path}` has fields, build it with `{path}{hint}`");
+        self.mismatch_error(msg, &expr.loc);
+
+        return self.type_of(symbol);
+    }
+
+    fn not_a_function_error(&mut self, res: &ExpressionNode) {
+        let msg = format!("`{}` is not a function", res.path().unwrap());
+        self.mismatch_error(msg, &res.loc);
+    }
+
+    // values of generic types are checked before it's known what they're
+    // expected to be, so the range of their literals is left to `expect_value`
+    fn check_deferred(&mut self, expr: &ExpressionNode) -> Type {
+        match expr.number() {
+            Some(literal) if literal.suffix.is_none() => Type::literal(literal.kind),
+            _ => self.check_expression(expr),
+        }
+    }
+
+    fn expect_value(&mut self, expected: &Type, found: &Type, expr: &ExpressionNode) {
+        match expr.number() {
+            Some(literal) if literal.suffix.is_none() => {
+                self.check_value(expected, expr);
+            }
+            _ => self.expect(expected, found, &expr.loc),
+        }
+    }
+
+    // literals on either side take the type of the other operand
+    fn check_operands(
+        &mut self,
+        expected: &Type,
+        left: &ExpressionNode,
+        right: &ExpressionNode,
+    ) -> (Type, Type) {
+        if left.is_literal_arithmetic() && !right.is_literal_arithmetic() {
+            let right = self.check_as(expected, right);
+            return (self.check_as(&right, left), right);
+        }
+
+        let left = self.check_as(expected, left);
+        let right = self.check_as(&left, right);
+
+        return (left, right);
+    }
+
+    // the bounds of a range are integers of the same type, an unsuffixed
+    // bound takes the type of the other one
+    pub(super) fn check_range(&mut self, start: &ExpressionNode, end: &ExpressionNode) -> Type {
+        let (start_type, end_type) = self.check_operands(&Type::Unknown, start, end);
+        let r#type = if start_type.is_known() {
+            start_type
+        } else {
+            end_type.clone()
+        };
+
+        if !r#type.is_known() {
+            return Type::Unknown;
+        }
+
+        if !matches!(r#type, Type::Int(_)) {
+            let msg = format!("cannot make a range of `{}`", self.type_name(&r#type));
+            self.mismatch_error(msg, &start.loc);
+            return Type::Unknown;
+        }
+
+        self.expect(&r#type, &end_type, &end.loc);
+        return r#type;
+    }
+
+    fn check_cast(&mut self, value: &ExpressionNode, node: &TypeNode) -> Type {
+        let target = self.lower_type(node);
+        let found = self.check_as(&target, value);
+
+        if !found.is_known() || !target.is_known() || found == target {
+            return target;
+        }
+
+        let valid = match (found.as_primitive(), target.as_primitive()) {
+            (Some(from), Some(to)) => from.can_cast(&to),
+            _ => false,
+        };
+
+        if !valid {
+            let err = TypeError::default()
+                .kind(TypeErrorKind::InvalidCast)
+                .msg(format!(
+                    "cannot cast `{}` to `{}`",
+                    self.type_name(&found),
+                    self.type_name(&target)
+                ));
+            self.push_error(err, &value.loc);
+        }
+
+        return target;
+    }
+
+    // both operands have the same type for every operator, the result is
+    // the type of the operands except for comparisons
+    fn check_operator(
+        &mut self,
+        kind: &BinaryExpressionKind,
+        op: &ComplexPunctuationKind,
+        left: &Type,
+        right: &Type,
+        loc: &Loc,
+    ) -> Type {
+        use BinaryExpressionKind::*;
+
+        let r#type = if left.is_known() { left } else { right };
+        let result = match kind {
+            Equality | Relational => Type::Bool,
+            _ => r#type.clone(),
+        };
+
+        if !left.is_known() || !right.is_known() {
+            return result;
+        }
+
+        if !left.fits(right) {
+            let msg = format!(
+                "cannot apply `{}` to `{}` and `{}`",
+                op.to_string(),
+                self.type_name(left),
+                self.type_name(right)
+            );
+            self.mismatch_error(msg, loc);
+            return result;
+        }
+
+        let supported = match kind {
+            LogicalOr | LogicalAnd => *r#type == Type::Bool,
+            Equality => matches!(
+                r#type,
+                Type::Int(_) | Type::Float(_) | Type::Char | Type::Bool | Type::String
+            ),
+            Relational => matches!(r#type, Type::Int(_) | Type::Float(_) | Type::Char),
+            BitwiseOr | BitwiseXor | BitwiseAnd => matches!(r#type, Type::Int(_) | Type::Bool),
+            Shift => matches!(r#type, Type::Int(_)),
+            Additive => {
+                matches!(r#type, Type::Int(_) | Type::Float(_))
+                    || *r#type == Type::String && *op == ComplexPunctuationKind::OperatorPlus
+            }
+            Multiplicative | Exponential => matches!(r#type, Type::Int(_) | Type::Float(_)),
+        };
+
+        if !supported {
+            self.operator_error(op, r#type, loc);
+        }
+
+        return result;
+    }
+
+    fn check_assignment(
+        &mut self,
+        left: &ExpressionNode,
+        op: &AssignmentKind,
+        right: &ExpressionNode,
+        loc: &Loc,
+    ) -> Type {
+        let target = self.check_expression(left);
+        let found = self.check_as(&target, right);
+
+        match op.operator() {
+            Some(op) => {
+                let kind = BinaryExpressionKind::from_punct(&op).unwrap();
+                self.check_operator(&kind, &op, &target, &found, loc);
+            }
+            None => self.expect(&target, &found, &right.loc),
+        }
+
+        return target;
+    }
+
+    fn check_call(&mut self, res: &ExpressionNode, args: &[ExpressionNode], loc: &Loc) -> Type {
+        let Some(symbol) = self.used(&res.loc) else {
+            let found = self.check_expressions(args);
+            if names_type(res) {
+                self.not_a_function_error(res);
+            }
+
+            return builtin_call(res, &found);
+        };
+
+        match self.symbol_kind(symbol) {
+            SymbolKind::Function | SymbolKind::Method => {
+                self.check_arguments(symbol, false, args, loc)
+            }
+            SymbolKind::Variant => {
+                let Some(variant) = self.variants.get(&symbol).cloned() else {
+                    self.check_expressions(args);
+                    return Type::Unknown;
+                };
+
+                match &variant.kind {
+                    VariantShape::Tuple(types) if args.len() == types.len() => {
+                        for (expected, arg) in types.iter().zip(args) {
+                            self.check_value(expected, arg);
+                        }
+                    }
+                    VariantShape::Tuple(types) => {
+                        self.check_expressions(args);
+
+                        let path = self.symbol_name(symbol).to_owned();
+                        self.count_error(&path, types.len(), args.len(), loc);
+                    }
+                    _ => {
+                        self.check_expressions(args);
+
+                        let path = self.symbol_name(symbol);
+                        let msg = format!("`{path}` is not a tuple variant");
+                        self.mismatch_error(msg, &res.loc);
+                    }
+                }
+
+                Type::Enum(variant.owner)
+            }
+            _ => {
+                self.check_expressions(args);
+                self.not_a_function_error(res);
+                Type::Unknown
+            }
+        }
+    }
+
+    fn check_method_call(
+        &mut self,
+        object: &ExpressionNode,
+        method: &str,
+        args: &[ExpressionNode],
+        loc: &Loc,
+    ) -> Type {
+        let object = self.check_expression(object);
+
+        // methods of traits are looked up by codegen, which knows what every
+        // type implements. only structs and enums can implement anything
+        let symbol = match &object {
+            Type::Struct(symbol, _) | Type::Enum(symbol) => self
+                .members(*symbol)
+                .and_then(|members| members.get(method))
+                .copied(),
+            Type::Param(_) | Type::Unknown => None,
+            _ => {
+                let msg = format!("`{}` has no method `{method}`", self.type_name(&object));
+                self.unknown_member_error(msg, loc);
+                None
+            }
+        };
+
+        match symbol {
+            Some(symbol)
+                if self.symbol_kind(symbol) == SymbolKind::Method
+                    && self
+                        .functions
+                        .get(&symbol)
+                        .is_some_and(|func| func.receiver) =>
+            {
+                self.check_arguments(symbol, true, args, loc)
+            }
+            _ => {
+                self.check_expressions(args);
+                Type::Unknown
+            }
+        }
+    }
+
+    // arguments are checked as the types of their parameters, like values of
+    // variables are. the ones of generic parameters are checked first, so the
+    // type parameters can be inferred from them
+    fn check_arguments(
+        &mut self,
+        symbol: usize,
+        skip_receiver: bool,
+        args: &[ExpressionNode],
+        loc: &Loc,
+    ) -> Type {
+        let Some(func) = self.functions.get(&symbol).cloned() else {
+            self.check_expressions(args);
+            return Type::Unknown;
+        };
+
+        let path = self.symbol_name(symbol).to_owned();
+        let params = &func.params[skip_receiver as usize..];
+
+        let mut found = Vec::new();
+        let mut inferred = HashMap::new();
+        for (idx, arg) in args.iter().enumerate() {
+            match params.get(idx) {
+                Some(param) if param.r#type.has_params() => {
+                    let r#type = self.check_deferred(arg);
+                    param.r#type.infer(&r#type, &mut inferred);
+                    found.push(Some(r#type));
+                }
+                Some(param) => {
+                    self.check_value(&param.r#type, arg);
+                    found.push(None);
+                }
+                None => {
+                    self.check_expression(arg);
+                }
+            }
+        }
+
+        if args.len() > params.len() {
+            self.count_error(&path, params.len(), args.len(), loc);
+            return func.r#type.substitute(&HashMap::new());
+        }
+
+        for (idx, param) in params.iter().enumerate() {
+            match found.get(idx) {
+                Some(Some(found)) => {
+                    let expected = param.r#type.substitute(&inferred);
+                    self.expect_value(&expected, found, &args[idx]);
+                }
+                Some(None) => {}
+                None if param.default => {}
+                None => {
+                    let err = TypeError::default()
+                        .kind(TypeErrorKind::ArgumentCount)
+                        .msg(format!("missing argument {} of `{path}`", idx + 1));
+                    self.push_error(err, loc);
+                    break;
+                }
+            }
+        }
+
+        self.check_bounds(&inferred, &path, loc);
+        return func.r#type.substitute(&inferred);
+    }
+
+    // types that are only known once the generic item is compiled, like
+    // other type parameters, are left to codegen
+    fn check_bounds(&mut self, inferred: &HashMap<usize, Type>, owner: &str, loc: &Loc) {
+        let mut params: Vec<_> = inferred.iter().collect();
+        params.sort_by_key(|(param, _)| **param);
+
+        for (param, r#type) in params {
+            let bounds = self.bounds.get(param).cloned().unwrap_or_default();
+            for bound in bounds.iter() {
+                let (satisfied, name) = match bound {
+                    Bound::Trait(symbol) => (
+                        self.impls
+                            .get(symbol)
+                            .is_some_and(|types| types.iter().any(|t| t.fits(r#type))),
+                        self.symbol_name(*symbol).to_owned(),
+                    ),
+                    Bound::Eq => (
+                        matches!(
+                            r#type,
+                            Type::Int(_) | Type::Float(_) | Type::Bool | Type::Char | Type::String
+                        ),
+                        "Eq".to_owned(),
+                    ),
+                    Bound::Ord => (
+                        matches!(r#type, Type::Int(_) | Type::Float(_) | Type::Char),
+                        "Ord".to_owned(),
+                    ),
+                };
+
+                if satisfied || !r#type.is_known() {
+                    continue;
+                }
+
+                let msg = format!(
+                    "`{}` doesn't implement `{name}`, which `{owner}` requires of `{}`",
+                    self.type_name(r#type),
+                    self.symbol_name(*param)
+                );
+                self.mismatch_error(msg, loc);
+            }
+        }
+    }
+
+    // fields are checked like arguments, as the types they're declared with
+    fn check_struct(&mut self, res: &ExpressionNode, fields: &[FieldValueNode], loc: &Loc) -> Type {
+        let symbol = self.used(&res.loc);
+        let shape = match symbol.map(|symbol| (symbol, self.symbol_kind(symbol))) {
+            Some((symbol, SymbolKind::Struct)) => self
+                .structs
+                .get(&symbol)
+                .map(|node| (node.fields.clone(), node.generics.clone(), None)),
+            Some((symbol, SymbolKind::Variant)) => {
+                self.variants
+                    .get(&symbol)
+                    .and_then(|variant| match &variant.kind {
+                        VariantShape::Struct(fields) => {
+                            Some((fields.clone(), Vec::new(), Some(Type::Enum(variant.owner))))
+                        }
+                        _ => None,
+                    })
+            }
+            _ => None,
+        };
+
+        let path = res.path().unwrap_or_default();
+        let Some((declared, generics, r#type)) = shape else {
+            for field in fields.iter() {
+                self.check_expression(&field.value);
+            }
+
+            let variant = symbol.and_then(|symbol| self.variants.get(&symbol));
+            let msg = match variant {
+                Some(_) => format!("`{path}` is not a struct variant"),
+                None => format!("`{path}` is not a struct"),
+            };
+            let r#type = variant.map_or(Type::Unknown, |variant| Type::Enum(variant.owner));
+
+            self.mismatch_error(msg, &res.loc);
+            return r#type;
+        };
+
+        let field_type = |name: &str| {
+            declared
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, r#type)| r#type)
+        };
+
+        let mut inferred = HashMap::new();
+        let mut found = Vec::new();
+        for field in fields.iter() {
+            match field_type(&field.name) {
+                Some(expected) if expected.has_params() => {
+                    let r#type = self.check_deferred(&field.value);
+                    expected.infer(&r#type, &mut inferred);
+                    found.push((field, expected, r#type));
+                }
+                Some(expected) => {
+                    self.check_value(expected, &field.value);
+                }
+                None => {
+                    self.check_expression(&field.value);
+
+                    let msg = format!("`{path}` has no field `{}`", field.name);
+                    self.unknown_member_error(msg, &field.loc);
+                }
+            }
+        }
+
+        for (field, expected, r#type) in found {
+            let expected = expected.substitute(&inferred);
+            self.expect_value(&expected, &r#type, &field.value);
+        }
+        self.check_bounds(&inferred, &path, loc);
+
+        let missing: Vec<String> = symbol
+            .and_then(|symbol| self.required.get(&symbol))
+            .into_iter()
+            .flatten()
+            .filter(|name| !fields.iter().any(|field| &field.name == *name))
+            .map(|name| format!("`{name}`"))
+            .collect();
+
+        if !missing.is_empty() {
+            let noun = if missing.len() == 1 {
+                "field"
+            } else {
+                "fields"
+            };
+            let msg = format!("missing {noun} {} in `{path}`", missing.join(", "));
+            self.mismatch_error(msg, loc);
+        }
+
+        r#type.unwrap_or_else(|| {
+            let args = generics
+                .iter()
+                .map(|param| inferred.get(param).cloned().unwrap_or(Type::Unknown))
+                .collect();
+
+            Type::Struct(symbol.unwrap(), args)
+        })
+    }
+
+    fn check_member(&mut self, left: &ExpressionNode, right: &ExpressionNode) -> Type {
+        let object = self.check_expression(left);
+
+        let (symbol, args) = match &object {
+            Type::Struct(symbol, args) => (*symbol, args),
+            Type::Param(_) | Type::Unknown => return Type::Unknown,
+            _ => {
+                let msg = format!("`{}` has no fields", self.type_name(&object));
+                self.unknown_member_error(msg, &left.loc);
+                return Type::Unknown;
+            }
+        };
+
+        let (ExpressionKind::Identifier { name }, Some(node)) =
+            (&right.kind, self.structs.get(&symbol))
+        else {
+            return Type::Unknown;
+        };
+
+        let params: HashMap<usize, Type> = node
+            .generics
+            .iter()
+            .copied()
+            .zip(args.iter().cloned())
+            .collect();
+
+        let field = node.fields.iter().find(|(field, _)| field == name);
+        match field {
+            Some((_, r#type)) => r#type.substitute(&params),
+            None => {
+                let msg = format!("`{}` has no field `{name}`", self.type_name(&object));
+                self.unknown_member_error(msg, &right.loc);
+                Type::Unknown
+            }
+        }
+    }
+
+    fn check_index(&mut self, object: &ExpressionNode, index: &ExpressionNode) -> Type {
+        match self.check_expression(object) {
+            Type::Array(element) => {
+                self.check_value(&Type::INT, index);
+                *element
+            }
+            Type::Dict(key, value) => {
+                self.check_value(&key, index);
+                *value
+            }
+            // the type of an element has to be known where it's used, so
+            // tuples can only be indexed with literals
+            Type::Tuple(elements) => {
+                self.check_value(&Type::INT, index);
+
+                let ExpressionKind::Literal {
+                    kind: LiteralKind::Integer,
+                    value,
+                    ..
+                } = &index.kind
+                else {
+                    let msg = "tuples can only be indexed with an integer literal";
+                    self.mismatch_error(msg, &index.loc);
+                    return Type::Unknown;
+                };
+
+                let element = value
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|idx| elements.get(idx));
+                match element {
+                    Some(element) => element.clone(),
+                    None => {
+                        let r#type = Type::Tuple(elements.clone());
+                        let msg = format!("`{}` has no element {value}", self.type_name(&r#type));
+                        self.unknown_member_error(msg, &index.loc);
+                        Type::Unknown
+                    }
+                }
+            }
+            Type::Unknown => {
+                self.check_expression(index);
+                Type::Unknown
+            }
+            r#type => {
+                self.check_expression(index);
+
+                let msg = format!("`{}` cannot be indexed", self.type_name(&r#type));
+                self.mismatch_error(msg, &object.loc);
+                Type::Unknown
+            }
+        }
+    }
+
+    fn check_match(
+        &mut self,
+        value: &ExpressionNode,
+        arms: &[MatchArmNode<ExpressionNode>],
+    ) -> Type {
+        let r#type = self.check_expression(value);
+
+        for arm in arms.iter() {
+            self.check_arm(arm, &r#type);
+        }
+
+        self.check_exhaustive(arms, &r#type, &value.loc);
+        self.check_elements(Type::Unknown, arms.iter().map(|arm| &arm.body))
+    }
+
+    // every element has the expected type, or the type of the first one
+    // that's known when nothing is expected
+    fn check_elements<'e>(
+        &mut self,
+        mut r#type: Type,
+        elements: impl Iterator<Item = &'e ExpressionNode>,
+    ) -> Type {
+        for el in elements {
+            let found = self.check_as(&r#type, el);
+
+            if r#type.is_known() {
+                self.expect(&r#type, &found, &el.loc);
+            } else {
+                r#type = found;
+            }
+        }
+
+        return r#type;
+    }
+
+    fn operator_error(&mut self, op: &ComplexPunctuationKind, r#type: &Type, loc: &Loc) {
+        let msg = format!(
+            "operator `{}` cannot be applied to `{}`",
+            op.to_string(),
+            self.type_name(r#type)
+        );
+        self.mismatch_error(msg, loc);
+    }
+
+    fn unknown_member_error(&mut self, msg: String, loc: &Loc) {
+        let err = TypeError::default()
+            .kind(TypeErrorKind::UnknownMember)
+            .msg(msg);
+        self.push_error(err, loc);
+    }
+
+    fn check_expressions(&mut self, exprs: &[ExpressionNode]) -> Vec<Type> {
+        exprs
+            .iter()
+            .map(|expr| self.check_expression(expr))
+            .collect()
+    }
+
+    fn count_error(&mut self, path: &str, expected: usize, given: usize, loc: &Loc) {
+        let err = TypeError::default()
+            .kind(TypeErrorKind::ArgumentCount)
+            .msg(count_mismatch(path, expected, "argument", given));
+        self.push_error(err, loc);
+    }
+}
+
+fn builtin_call(res: &ExpressionNode, args: &[Type]) -> Type {
+    let Some(path) = res.path() else {
+        return Type::Unknown;
+    };
+
+    match std_path(&path) {
+        "console::push" | "console::push_line" => Type::Void,
+        "Dict::new" => match args.first() {
+            Some(Type::Array(element)) => match element.as_ref() {
+                Type::Tuple(pair) if pair.len() == 2 => {
+                    Type::Dict(Box::new(pair[0].clone()), Box::new(pair[1].clone()))
+                }
+                _ => Type::Unknown,
+            },
+            Some(_) => Type::Unknown,
+            None => Type::Dict(Box::new(Type::Unknown), Box::new(Type::Unknown)),
+        },
+        _ => Type::Unknown,
+    }
+}
+
+// primitives, builtin types and namespaces, which aren't bound to a symbol
+fn names_type(res: &ExpressionNode) -> bool {
+    let Some(path) = res.path() else {
+        return false;
+    };
+
+    return Type::primitive(&path).is_some()
+        || is_builtin_type(&path)
+        || is_builtin_namespace(&path);
+}
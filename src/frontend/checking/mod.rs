@@ -0,0 +1,10 @@
+mod checker;
+mod exhaustiveness;
+mod expressions;
+mod mutability;
+mod returns;
+mod types;
+
+pub use checker::*;
+pub use mutability::*;
+pub use types::*;
@@ -0,0 +1,443 @@
+use std::ptr;
+
+use crate::{
+    errors::{
+        context::{ErrorContext, ErrorContextBuilder},
+        mutability::{MutabilityError, MutabilityErrorKind},
+    },
+    frontend::{
+        lexing::AssignmentKind,
+        parsing::ast::{
+            bodies::{BodyElementKind, BodyKind, BodyNode},
+            expressions::{
+                ExpressionKind, ExpressionNode, InterpolationPart, ResolutionExpressionKind,
+            },
+            functions::FunctionNode,
+            items::{FieldNode, ItemKind, TraitMethodNode, VariantKind},
+            namespaces::NamespaceNode,
+            patterns::MatchArmNode,
+            projects::ProjectNode,
+            statements::{
+                IfKind, IfNode, StatementKind, StatementNode, WhileLoopKind, WhileLoopNode,
+            },
+        },
+        resolving::{ScopeTree, SymbolKind},
+        Loc,
+    },
+    structs::ScriptSource,
+};
+
+use super::checker::SharedValues;
+
+// only `let mut` variables and `static mut` statics can be assigned to,
+// along with the fields and elements of their values. values that are
+// shared rather than copied can't be made mutable through another name
+pub struct MutabilityChecker<'a> {
+    pub errors: Vec<MutabilityError>,
+
+    tree: &'a ScopeTree,
+    sources: &'a [ScriptSource<'a>],
+    shared: &'a SharedValues,
+    source: usize,
+}
+
+impl<'a> MutabilityChecker<'a> {
+    pub fn check(
+        project: &ProjectNode,
+        tree: &'a ScopeTree,
+        sources: &'a [ScriptSource<'a>],
+        shared: &'a SharedValues,
+    ) -> MutabilityChecker<'a> {
+        let mut checker = MutabilityChecker {
+            errors: Vec::new(),
+            tree,
+            sources,
+            shared,
+            source: 0,
+        };
+        checker.process(&project.root);
+
+        return checker;
+    }
+
+    fn process(&mut self, node: &NamespaceNode) {
+        self.source = node.source;
+        self.check_items(node);
+
+        for child in node.children.iter() {
+            self.process(child);
+        }
+    }
+
+    fn check_items(&mut self, node: &NamespaceNode) {
+        for item in node.items.iter() {
+            match &item.kind {
+                ItemKind::Constant(node) => self.check_expression(&node.value),
+                ItemKind::Static(node) => self.check_expression(&node.value),
+                ItemKind::Function(func) => self.check_function(func),
+                ItemKind::Struct(node) => self.check_defaults(&node.fields),
+                ItemKind::Enum(node) => {
+                    for variant in node.variants.iter() {
+                        if let VariantKind::Struct(fields) = &variant.kind {
+                            self.check_defaults(fields);
+                        }
+                    }
+                }
+                ItemKind::Trait(node) => {
+                    for method in node.methods.iter() {
+                        if let TraitMethodNode::Provided(func) = method {
+                            self.check_function(func);
+                        }
+                    }
+                }
+                ItemKind::Impl(node) => {
+                    for method in node.methods.iter() {
+                        self.check_function(&method.function);
+                    }
+                }
+                ItemKind::Use(_) => {}
+            }
+        }
+    }
+
+    fn check_function(&mut self, func: &FunctionNode) {
+        for arg in func.args.iter() {
+            if let Some(default) = &arg.default {
+                self.check_expression(default);
+            }
+        }
+
+        self.check_body(&func.body);
+    }
+
+    fn check_defaults(&mut self, fields: &[FieldNode]) {
+        for field in fields.iter() {
+            if let Some(default) = &field.default {
+                self.check_expression(default);
+            }
+        }
+    }
+
+    fn check_body(&mut self, body: &BodyNode) {
+        match &body.kind {
+            BodyKind::Inline(el) => self.check_body_element(&el.kind),
+            BodyKind::Multiline(els) => els.iter().for_each(|el| self.check_body_element(&el.kind)),
+        }
+    }
+
+    fn check_body_element(&mut self, el: &BodyElementKind) {
+        match el {
+            BodyElementKind::Statement(stmt) => self.check_statement(stmt),
+            BodyElementKind::Body(body) => self.check_body(body),
+            BodyElementKind::Empty => {}
+        }
+    }
+
+    fn check_statement(&mut self, stmt: &StatementNode) {
+        match &stmt.kind {
+            StatementKind::Expression(expr) | StatementKind::Return(expr) => {
+                self.check_expression(expr)
+            }
+            StatementKind::VariableDeclaration(var) => {
+                if let Some(value) = &var.value {
+                    self.check_expression(value);
+
+                    if var.mutable {
+                        self.check_shared(value);
+                    }
+                }
+            }
+            StatementKind::FunctionDeclaration(func) => self.check_function(func),
+            StatementKind::If(node) => self.check_if(node),
+            StatementKind::WhileLoop(node) => self.check_while(node),
+            StatementKind::ForLoop(node) => {
+                self.check_expression(&node.iterable);
+                self.check_body(&node.body);
+            }
+            StatementKind::Match(node) => {
+                self.check_expression(&node.value);
+                self.check_arms(&node.arms, |c, body| c.check_body(body));
+            }
+            StatementKind::Break(_) | StatementKind::Continue(_) => {}
+        }
+    }
+
+    fn check_if(&mut self, node: &IfNode) {
+        if let IfKind::If { cond, .. } = &node.kind {
+            self.check_expression(cond);
+        }
+
+        self.check_body(&node.body);
+
+        if let IfKind::If {
+            r#else: Some(r#else),
+            ..
+        } = &node.kind
+        {
+            self.check_if(r#else);
+        }
+    }
+
+    fn check_while(&mut self, node: &WhileLoopNode) {
+        if let WhileLoopKind::While { cond, .. } = &node.kind {
+            self.check_expression(cond);
+        }
+
+        self.check_body(&node.body);
+
+        if let WhileLoopKind::While {
+            r#else: Some(r#else),
+            ..
+        } = &node.kind
+        {
+            self.check_while(r#else);
+        }
+    }
+
+    fn check_arms<T>(
+        &mut self,
+        arms: &[MatchArmNode<T>],
+        mut check_body: impl FnMut(&mut Self, &T),
+    ) {
+        for arm in arms.iter() {
+            if let Some(guard) = &arm.guard {
+                self.check_expression(guard);
+            }
+
+            check_body(self, &arm.body);
+        }
+    }
+
+    fn check_expression(&mut self, expr: &ExpressionNode) {
+        match &expr.kind {
+            ExpressionKind::Literal { .. } | ExpressionKind::Identifier { .. } => {}
+            ExpressionKind::Resolution { left, .. } => self.check_expression(left),
+            ExpressionKind::Interpolation { parts } => {
+                for part in parts.iter() {
+                    if let InterpolationPart::Expression(expr) = part {
+                        self.check_expression(expr);
+                    }
+                }
+            }
+            ExpressionKind::Call { res, args } => {
+                self.check_expression(res);
+                args.iter().for_each(|arg| self.check_expression(arg));
+            }
+            ExpressionKind::MethodCall { object, args, .. } => {
+                self.check_expression(object);
+                args.iter().for_each(|arg| self.check_expression(arg));
+            }
+            ExpressionKind::Struct { fields, .. } => {
+                for field in fields.iter() {
+                    self.check_expression(&field.value);
+                }
+            }
+            ExpressionKind::Array { elements } | ExpressionKind::Tuple { elements } => {
+                elements.iter().for_each(|el| self.check_expression(el));
+            }
+            ExpressionKind::Index { object, index } => {
+                self.check_expression(object);
+                self.check_expression(index);
+            }
+            ExpressionKind::Range { start, end, .. } => {
+                self.check_expression(start);
+                self.check_expression(end);
+            }
+            ExpressionKind::Binary { left, right, .. } => {
+                self.check_expression(left);
+                self.check_expression(right);
+            }
+            ExpressionKind::Assignment { left, op, right } => {
+                let assignable = self.check_target(left);
+                self.check_expression(left);
+                self.check_expression(right);
+
+                if assignable && *op == AssignmentKind::Straight {
+                    self.check_shared(right);
+                }
+            }
+            ExpressionKind::Unary { right, .. } => self.check_expression(right),
+            ExpressionKind::Cast { value, .. } => self.check_expression(value),
+            ExpressionKind::Match { value, arms } => {
+                self.check_expression(value);
+                self.check_arms(arms, |c, body| c.check_expression(body));
+            }
+        }
+    }
+
+    // compound assignments like `+=` go through here too, they write to
+    // their target the same way
+    fn check_target(&mut self, target: &ExpressionNode) -> bool {
+        let Some(root) = place_root(target) else {
+            self.invalid_target("cannot assign to this expression", &target.loc);
+            return false;
+        };
+
+        let path = root.path().unwrap();
+        let Some(&symbol) = self.tree.uses.get(&(self.source, root.loc.clone())) else {
+            self.invalid_target(format!("cannot assign to `{path}`"), &target.loc);
+            return false;
+        };
+
+        let symbol = &self.tree.symbols[symbol];
+        let name = short_name(&symbol.name);
+        let whole = ptr::eq(root, target);
+
+        // a copy would share the fields and elements it's reached through
+        let copy = match whole {
+            true => format!("consider copying it into a variable with `let mut {name} = {name}`"),
+            false => "consider building a new value from its fields or elements".to_string(),
+        };
+
+        let (what, hint) = match symbol.kind {
+            SymbolKind::Variable { mutable: true } | SymbolKind::Static { mutable: true } => {
+                return true;
+            }
+            SymbolKind::Variable { .. } => (
+                "immutable variable",
+                format!("consider making it mutable with `let mut {name}`"),
+            ),
+            SymbolKind::Static { .. } => (
+                "immutable static",
+                format!("consider making it mutable with `static mut {name}`"),
+            ),
+            SymbolKind::Constant => (
+                "constant",
+                "constants never change, consider a `static mut` instead".to_string(),
+            ),
+            SymbolKind::Argument => ("immutable argument", copy),
+            SymbolKind::Binding => ("immutable binding", copy),
+            _ => {
+                self.invalid_target(format!("cannot assign to `{path}`"), &target.loc);
+                return false;
+            }
+        };
+
+        let msg = match whole {
+            true => format!("cannot assign to {what} `{path}`"),
+            false => format!("cannot assign to a part of {what} `{path}`"),
+        };
+        let note = format!("`{name}` is declared here, {hint}");
+
+        let err = MutabilityError::default()
+            .kind(MutabilityErrorKind::Immutable)
+            .msg(msg)
+            .ctx(self.error_ctx(self.source, &target.loc))
+            .note(note, self.error_ctx(symbol.source, &symbol.loc));
+        self.errors.push(err);
+
+        return false;
+    }
+
+    // a value given to a `let mut` variable or assigned to a mutable place,
+    // along with what's put into the structs, arrays and tuples built there.
+    // what a call returns can be one of its arguments or a part of one, and
+    // what a match gives is one of its arms
+    fn check_shared(&mut self, value: &ExpressionNode) {
+        match &value.kind {
+            ExpressionKind::Struct { fields, .. } => {
+                return fields
+                    .iter()
+                    .for_each(|field| self.check_shared(&field.value));
+            }
+            ExpressionKind::Array { elements } | ExpressionKind::Tuple { elements } => {
+                return elements.iter().for_each(|el| self.check_shared(el));
+            }
+            ExpressionKind::Match { arms, .. } => {
+                return arms.iter().for_each(|arm| self.check_shared(&arm.body));
+            }
+            _ => {}
+        }
+
+        if !self.shared.contains(&(self.source, value.loc.clone())) {
+            return;
+        }
+
+        match &value.kind {
+            ExpressionKind::Call { args, .. } => {
+                return args.iter().for_each(|arg| self.check_shared(arg));
+            }
+            ExpressionKind::MethodCall { object, args, .. } => {
+                self.check_shared(object);
+                return args.iter().for_each(|arg| self.check_shared(arg));
+            }
+            _ => {}
+        }
+
+        let Some(root) = place_root(value) else {
+            return;
+        };
+        let Some(&symbol) = self.tree.uses.get(&(self.source, root.loc.clone())) else {
+            return;
+        };
+
+        let path = root.path().unwrap();
+        let symbol = &self.tree.symbols[symbol];
+        let name = short_name(&symbol.name);
+
+        let (what, hint) = match symbol.kind {
+            SymbolKind::Variable { mutable: false } => (
+                "immutable variable",
+                format!("consider making it mutable with `let mut {name}`"),
+            ),
+            SymbolKind::Argument => (
+                "immutable argument",
+                "consider building a new value from its fields or elements".to_string(),
+            ),
+            SymbolKind::Binding => (
+                "immutable binding",
+                "consider building a new value from its fields or elements".to_string(),
+            ),
+            _ => return,
+        };
+
+        let msg = match ptr::eq(root, value) {
+            true => format!("cannot make {what} `{path}` mutable through another name"),
+            false => format!("cannot make a part of {what} `{path}` mutable through another name"),
+        };
+        let note = format!("`{name}` is declared here and shared rather than copied, {hint}");
+
+        let err = MutabilityError::default()
+            .kind(MutabilityErrorKind::Immutable)
+            .msg(msg)
+            .ctx(self.error_ctx(self.source, &value.loc))
+            .note(note, self.error_ctx(symbol.source, &symbol.loc));
+        self.errors.push(err);
+    }
+
+    fn invalid_target<T: ToString>(&mut self, msg: T, loc: &Loc) {
+        let err = MutabilityError::default()
+            .kind(MutabilityErrorKind::InvalidTarget)
+            .msg(msg)
+            .ctx(self.error_ctx(self.source, loc));
+        self.errors.push(err);
+    }
+
+    fn error_ctx(&self, source: usize, loc: &Loc) -> ErrorContext {
+        ErrorContextBuilder::loc(loc)
+            .from_src_and_ln(&self.sources[source], loc.ln)
+            .build()
+    }
+}
+
+// symbols of items are named by their whole path
+fn short_name(path: &str) -> &str {
+    match path.rsplit_once("::") {
+        Some((_, name)) => name,
+        None => path,
+    }
+}
+
+// the name a place is reached from, through fields and elements
+fn place_root(expr: &ExpressionNode) -> Option<&ExpressionNode> {
+    match &expr.kind {
+        _ if expr.path().is_some() => Some(expr),
+        ExpressionKind::Resolution {
+            left,
+            kind: ResolutionExpressionKind::Member,
+            ..
+        } => place_root(left),
+        ExpressionKind::Index { object, .. } => place_root(object),
+        _ => None,
+    }
+}
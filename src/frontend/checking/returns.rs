@@ -0,0 +1,130 @@
+use crate::{
+    errors::types::{TypeError, TypeErrorKind},
+    frontend::{
+        lexing::LiteralKind,
+        parsing::ast::{
+            bodies::{BodyElementKind, BodyKind, BodyNode},
+            expressions::ExpressionKind,
+            functions::FunctionNode,
+            statements::{IfKind, IfNode, StatementKind, WhileLoopKind, WhileLoopNode},
+        },
+    },
+};
+
+use super::{checker::TypeChecker, types::Type};
+
+impl TypeChecker<'_> {
+    // functions with a return variable return it when they reach their end
+    pub(super) fn check_returns(&mut self, func: &FunctionNode, r#type: &Type) {
+        if *r#type == Type::Void || func.return_var.is_some() || always_returns(&func.body) {
+            return;
+        }
+
+        let loc = func.r#type.as_ref().map_or(&func.loc, |node| &node.loc);
+        let err = TypeError::default()
+            .kind(TypeErrorKind::MissingReturn)
+            .msg(format!("not all paths of `{}` return a value", func.name));
+        self.push_error(err, loc);
+    }
+}
+
+// whether every path through a body returns before reaching its end or
+// stays in a `while true` loop forever. matches are exhaustive, so one
+// returns when all of its arms do
+fn always_returns(body: &BodyNode) -> bool {
+    let returns = |el: &BodyElementKind| match el {
+        BodyElementKind::Statement(statement) => match &statement.kind {
+            StatementKind::Return(_) => true,
+            StatementKind::If(node) => if_returns(node),
+            StatementKind::Match(node) => node.arms.iter().all(|arm| always_returns(&arm.body)),
+            StatementKind::WhileLoop(node) => loops_forever(node),
+            _ => false,
+        },
+        BodyElementKind::Body(body) => always_returns(body),
+        BodyElementKind::Empty => false,
+    };
+
+    match &body.kind {
+        BodyKind::Inline(el) => returns(&el.kind),
+        BodyKind::Multiline(els) => els.iter().any(|el| returns(&el.kind)),
+    }
+}
+
+fn if_returns(node: &IfNode) -> bool {
+    match &node.kind {
+        IfKind::If {
+            r#else: Some(r#else),
+            ..
+        } => always_returns(&node.body) && if_returns(r#else),
+        IfKind::If { r#else: None, .. } => false,
+        IfKind::Else => always_returns(&node.body),
+    }
+}
+
+fn loops_forever(node: &WhileLoopNode) -> bool {
+    let WhileLoopKind::While { cond, .. } = &node.kind else {
+        return false;
+    };
+
+    let is_true = matches!(
+        &cond.kind,
+        ExpressionKind::Literal { kind: LiteralKind::Boolean, value, .. } if value == "true"
+    );
+    let label = node.label.as_ref().map(|label| label.name.as_str());
+
+    return is_true && !breaks(&node.body, label, true);
+}
+
+// whether a body breaks out of the loop it's in, `innermost` is false inside
+// nested loops, where only breaks with the loop's label leave it
+fn breaks(body: &BodyNode, label: Option<&str>, innermost: bool) -> bool {
+    let breaks_el = |el: &BodyElementKind| match el {
+        BodyElementKind::Statement(statement) => match &statement.kind {
+            StatementKind::Break(node) => match &node.label {
+                Some(target) => Some(target.name.as_str()) == label,
+                None => innermost,
+            },
+            StatementKind::If(node) => if_breaks(node, label, innermost),
+            StatementKind::Match(node) => node
+                .arms
+                .iter()
+                .any(|arm| breaks(&arm.body, label, innermost)),
+            StatementKind::WhileLoop(node) => while_breaks(node, label, innermost),
+            StatementKind::ForLoop(node) => breaks(&node.body, label, false),
+            _ => false,
+        },
+        BodyElementKind::Body(body) => breaks(body, label, innermost),
+        BodyElementKind::Empty => false,
+    };
+
+    match &body.kind {
+        BodyKind::Inline(el) => breaks_el(&el.kind),
+        BodyKind::Multiline(els) => els.iter().any(|el| breaks_el(&el.kind)),
+    }
+}
+
+fn if_breaks(node: &IfNode, label: Option<&str>, innermost: bool) -> bool {
+    let r#else = match &node.kind {
+        IfKind::If {
+            r#else: Some(r#else),
+            ..
+        } => if_breaks(r#else, label, innermost),
+        _ => false,
+    };
+
+    return r#else || breaks(&node.body, label, innermost);
+}
+
+// the `else` of a loop runs outside of it, where breaks leave the loops
+// around it
+fn while_breaks(node: &WhileLoopNode, label: Option<&str>, innermost: bool) -> bool {
+    match &node.kind {
+        WhileLoopKind::While { r#else, .. } => {
+            breaks(&node.body, label, false)
+                || r#else
+                    .as_ref()
+                    .is_some_and(|r#else| while_breaks(r#else, label, innermost))
+        }
+        WhileLoopKind::Else => breaks(&node.body, label, innermost),
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+use crate::frontend::{
+    lexing::LiteralKind,
+    primitives::{FloatType, IntType, PrimitiveType},
+    resolving::ScopeTree,
+};
+
+// named types refer to the symbols the resolver declared for them
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    Int(IntType),
+    Float(FloatType),
+    Bool,
+    Char,
+    String,
+    Void,
+    Struct(usize, Vec<Type>),
+    Enum(usize),
+    Param(usize),
+    Array(Box<Type>),
+    Tuple(Vec<Type>),
+    Dict(Box<Type>, Box<Type>),
+    // whatever can't be told without compiling, e.g. what builtins return.
+    // it fits every type, so any mistakes with it are left to codegen
+    Unknown,
+}
+
+impl Type {
+    // what unsuffixed number literals are when nothing else is expected
+    pub const INT: Type = Type::Int(IntType::Int64);
+    pub const FLOAT: Type = Type::Float(FloatType::Float64);
+
+    pub fn primitive(name: &str) -> Option<Type> {
+        PrimitiveType::from_name(name).map(Type::from)
+    }
+
+    pub fn literal(kind: &LiteralKind) -> Type {
+        match kind {
+            LiteralKind::Integer => Type::INT,
+            LiteralKind::Float => Type::FLOAT,
+            LiteralKind::String => Type::String,
+            LiteralKind::Char => Type::Char,
+            LiteralKind::Boolean => Type::Bool,
+        }
+    }
+
+    pub fn as_primitive(&self) -> Option<PrimitiveType> {
+        match self {
+            Type::Int(int) => Some(PrimitiveType::Int(*int)),
+            Type::Float(float) => Some(PrimitiveType::Float(*float)),
+            Type::Bool => Some(PrimitiveType::Bool),
+            Type::Char => Some(PrimitiveType::Char),
+            Type::String => Some(PrimitiveType::String),
+            Type::Void => Some(PrimitiveType::Void),
+            _ => None,
+        }
+    }
+
+    pub fn is_known(&self) -> bool {
+        !matches!(self, Type::Unknown | Type::Param(_))
+    }
+
+    // values that can be changed through their fields or elements, they're
+    // shared rather than copied when they're bound to another name
+    pub fn is_shared(&self) -> bool {
+        matches!(
+            self,
+            Type::Struct(..) | Type::Array(_) | Type::Tuple(_) | Type::Dict(..)
+        )
+    }
+
+    // whether the type can only be told once its parameters are inferred
+    pub fn has_params(&self) -> bool {
+        match self {
+            Type::Param(_) => true,
+            Type::Struct(_, types) | Type::Tuple(types) => types.iter().any(Type::has_params),
+            Type::Array(element) => element.has_params(),
+            Type::Dict(key, value) => key.has_params() || value.has_params(),
+            _ => false,
+        }
+    }
+
+    // type parameters only get their types once a generic item is
+    // instantiated, so they fit anything like unknown types do
+    pub fn fits(&self, found: &Type) -> bool {
+        if !self.is_known() || !found.is_known() {
+            return true;
+        }
+
+        match (self, found) {
+            (Type::Struct(expected, args), Type::Struct(found, found_args)) => {
+                expected == found
+                    && (args.is_empty()
+                        || found_args.is_empty()
+                        || args.len() == found_args.len()
+                            && args.iter().zip(found_args).all(|(a, b)| a.fits(b)))
+            }
+            (Type::Array(expected), Type::Array(found)) => expected.fits(found),
+            (Type::Tuple(expected), Type::Tuple(found)) => {
+                expected.len() == found.len() && expected.iter().zip(found).all(|(a, b)| a.fits(b))
+            }
+            (Type::Dict(key, value), Type::Dict(found_key, found_value)) => {
+                key.fits(found_key) && value.fits(found_value)
+            }
+            _ => self == found,
+        }
+    }
+
+    // binds the type parameters in `self` to what they are in `found`, the
+    // first binding of a parameter wins
+    pub fn infer(&self, found: &Type, params: &mut HashMap<usize, Type>) {
+        match (self, found) {
+            (Type::Param(param), found) if found.is_known() => {
+                params.entry(*param).or_insert_with(|| found.clone());
+            }
+            (Type::Struct(expected, args), Type::Struct(found, found_args))
+                if expected == found && args.len() == found_args.len() =>
+            {
+                args.iter()
+                    .zip(found_args)
+                    .for_each(|(a, b)| a.infer(b, params));
+            }
+            (Type::Array(expected), Type::Array(found)) => expected.infer(found, params),
+            (Type::Tuple(expected), Type::Tuple(found)) if expected.len() == found.len() => {
+                expected
+                    .iter()
+                    .zip(found)
+                    .for_each(|(a, b)| a.infer(b, params));
+            }
+            (Type::Dict(key, value), Type::Dict(found_key, found_value)) => {
+                key.infer(found_key, params);
+                value.infer(found_value, params);
+            }
+            _ => {}
+        }
+    }
+
+    // parameters that weren't inferred become unknown
+    pub fn substitute(&self, params: &HashMap<usize, Type>) -> Type {
+        let substitute_all = |types: &[Type]| types.iter().map(|t| t.substitute(params)).collect();
+
+        match self {
+            Type::Param(param) => params.get(param).cloned().unwrap_or(Type::Unknown),
+            Type::Struct(idx, args) => Type::Struct(*idx, substitute_all(args)),
+            Type::Array(element) => Type::Array(Box::new(element.substitute(params))),
+            Type::Tuple(elements) => Type::Tuple(substitute_all(elements)),
+            Type::Dict(key, value) => Type::Dict(
+                Box::new(key.substitute(params)),
+                Box::new(value.substitute(params)),
+            ),
+            _ => self.clone(),
+        }
+    }
+
+    pub fn name(&self, tree: &ScopeTree) -> String {
+        let join = |types: &[Type]| {
+            types
+                .iter()
+                .map(|t| t.name(tree))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match self {
+            Type::Struct(idx, args) if args.is_empty() => tree.symbols[*idx].name.clone(),
+            Type::Struct(idx, args) => format!("{}<{}>", tree.symbols[*idx].name, join(args)),
+            Type::Enum(idx) | Type::Param(idx) => tree.symbols[*idx].name.clone(),
+            Type::Array(element) => format!("[{}]", element.name(tree)),
+            Type::Tuple(elements) if elements.len() == 1 => {
+                format!("({},)", elements[0].name(tree))
+            }
+            Type::Tuple(elements) => format!("({})", join(elements)),
+            Type::Dict(key, value) => format!("Dict<{}, {}>", key.name(tree), value.name(tree)),
+            Type::Unknown => "_".to_string(),
+            _ => self.as_primitive().unwrap().name().to_string(),
+        }
+    }
+}
+
+impl From<PrimitiveType> for Type {
+    fn from(primitive: PrimitiveType) -> Type {
+        match primitive {
+            PrimitiveType::Int(int) => Type::Int(int),
+            PrimitiveType::Float(float) => Type::Float(float),
+            PrimitiveType::Bool => Type::Bool,
+            PrimitiveType::Char => Type::Char,
+            PrimitiveType::String => Type::String,
+            PrimitiveType::Void => Type::Void,
+        }
+    }
+}
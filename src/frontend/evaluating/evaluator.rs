@@ -0,0 +1,418 @@
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+};
+
+use crate::{
+    errors::{
+        context::ErrorContextBuilder,
+        evaluation::{EvaluationError, EvaluationErrorKind},
+    },
+    frontend::{
+        checking::LiteralTypes,
+        lexing::{ComplexPunctuationKind, LiteralKind},
+        parsing::ast::{
+            expressions::{ExpressionKind, ExpressionNode, NumberLiteral},
+            items::{ConstantNode, ItemKind},
+            namespaces::NamespaceNode,
+            projects::ProjectNode,
+            types::{TypeKind, TypeNode},
+        },
+        primitives::PrimitiveType,
+        resolving::{ScopeTree, SymbolKind},
+        Loc,
+    },
+    structs::ScriptSource,
+};
+
+use super::ConstValue;
+
+// folds the value of every constant at compile time, so that codegen only
+// has to emit the results, and orders the statics for their initialization
+pub struct Evaluator<'a> {
+    pub errors: Vec<EvaluationError>,
+    // the value of every constant, by its full path
+    pub values: HashMap<String, ConstValue>,
+    // the full path of every static, each one after the statics it uses
+    pub statics: Vec<String>,
+
+    tree: &'a ScopeTree,
+    sources: &'a [ScriptSource<'a>],
+    literals: &'a LiteralTypes,
+    source: usize,
+
+    // every constant of the project with its source, by its symbol
+    constants: HashMap<usize, (&'a ConstantNode, usize)>,
+    states: HashMap<usize, State>,
+    // the constants or statics being looked at, each one using the next
+    stack: Vec<usize>,
+    ordered: HashSet<usize>,
+}
+
+enum State {
+    Evaluating,
+    Done(ConstValue),
+    // the error was already reported, constants using it just fail too
+    Failed,
+}
+
+impl<'a> Evaluator<'a> {
+    pub fn evaluate(
+        project: &'a ProjectNode,
+        tree: &'a ScopeTree,
+        sources: &'a [ScriptSource<'a>],
+        literals: &'a LiteralTypes,
+    ) -> Evaluator<'a> {
+        let mut evaluator = Evaluator {
+            errors: Vec::new(),
+            values: HashMap::new(),
+            statics: Vec::new(),
+            tree,
+            sources,
+            literals,
+            source: 0,
+            constants: HashMap::new(),
+            states: HashMap::new(),
+            stack: Vec::new(),
+            ordered: HashSet::new(),
+        };
+        evaluator.process(project);
+
+        return evaluator;
+    }
+
+    fn process(&mut self, project: &'a ProjectNode) {
+        let mut namespaces = Vec::new();
+        collect_namespaces(&project.root, &mut namespaces);
+
+        let (mut order, mut statics) = (Vec::new(), Vec::new());
+        for node in namespaces.iter() {
+            for item in node.items.iter() {
+                let loc = match &item.kind {
+                    ItemKind::Constant(constant) => &constant.loc,
+                    ItemKind::Static(node) => &node.loc,
+                    _ => continue,
+                };
+
+                let Some(&symbol) = self.tree.declarations.get(&(node.source, loc.clone())) else {
+                    continue;
+                };
+
+                match &item.kind {
+                    ItemKind::Constant(constant) => {
+                        self.constants.insert(symbol, (constant, node.source));
+                        order.push(symbol);
+                    }
+                    _ => statics.push(symbol),
+                }
+            }
+        }
+
+        for symbol in order {
+            if let Some(value) = self.evaluate_constant(symbol) {
+                let name = self.tree.symbols[symbol].name.clone();
+                self.values.insert(name, value);
+            }
+        }
+
+        for symbol in statics {
+            self.order_static(symbol);
+        }
+    }
+
+    // statics are initialized one after another before `main`, so the
+    // statics a value uses, directly or through the functions it calls,
+    // have to be initialized first
+    fn order_static(&mut self, symbol: usize) {
+        if self.ordered.contains(&symbol) {
+            return;
+        }
+
+        let source = self.tree.symbols[symbol].source;
+        let outer = mem::replace(&mut self.source, source);
+        self.stack.push(symbol);
+
+        for dependency in self.dependencies(symbol) {
+            if self.stack.contains(&dependency) {
+                let loc = self.tree.symbols[symbol].loc.clone();
+                self.cycle_error(dependency, &loc);
+                continue;
+            }
+
+            self.order_static(dependency);
+        }
+
+        self.stack.pop();
+        self.source = outer;
+
+        self.ordered.insert(symbol);
+        self.statics.push(self.tree.symbols[symbol].name.clone());
+    }
+
+    // the statics a static uses, following the functions it names
+    fn dependencies(&self, symbol: usize) -> Vec<usize> {
+        let mut dependencies = Vec::new();
+        let mut seen = HashSet::new();
+        let mut queue = vec![symbol];
+
+        while let Some(current) = queue.pop() {
+            let Some(references) = self.tree.references.get(&current) else {
+                continue;
+            };
+
+            for &reference in references.iter() {
+                if !seen.insert(reference) {
+                    continue;
+                }
+
+                match self.tree.symbols[reference].kind {
+                    SymbolKind::Static { .. } => dependencies.push(reference),
+                    _ => queue.push(reference),
+                }
+            }
+        }
+
+        return dependencies;
+    }
+
+    fn evaluate_constant(&mut self, symbol: usize) -> Option<ConstValue> {
+        match self.states.get(&symbol) {
+            Some(State::Done(value)) => return Some(value.clone()),
+            Some(State::Failed) => return None,
+            Some(State::Evaluating) => unreachable!(),
+            None => {}
+        }
+
+        let (node, source) = self.constants[&symbol];
+        self.states.insert(symbol, State::Evaluating);
+        self.stack.push(symbol);
+        let outer = mem::replace(&mut self.source, source);
+
+        let value = match primitive_of(&node.r#type) {
+            Some(PrimitiveType::Void) | None => self.not_constant(
+                "constants can only hold numbers, bools, chars and strings",
+                &node.r#type.loc,
+            ),
+            Some(_) => self.evaluate_expression(&node.value),
+        };
+
+        self.source = outer;
+        self.stack.pop();
+
+        let state = match &value {
+            Some(value) => State::Done(value.clone()),
+            None => State::Failed,
+        };
+        self.states.insert(symbol, state);
+
+        return value;
+    }
+
+    fn evaluate_expression(&mut self, expr: &ExpressionNode) -> Option<ConstValue> {
+        if let Some(literal) = expr.number() {
+            return self.evaluate_number(&literal, &expr.loc);
+        }
+
+        match &expr.kind {
+            ExpressionKind::Literal { kind, value, .. } => {
+                let value = match kind {
+                    LiteralKind::String => ConstValue::String(value.clone()),
+                    LiteralKind::Char => ConstValue::Char(value.chars().next().unwrap()),
+                    LiteralKind::Boolean => ConstValue::Bool(value == "true"),
+                    // numbers are evaluated by `evaluate_number`
+                    LiteralKind::Integer | LiteralKind::Float => unreachable!(),
+                };
+
+                Some(value)
+            }
+            ExpressionKind::Identifier { .. } | ExpressionKind::Resolution { .. }
+                if expr.path().is_some() =>
+            {
+                self.evaluate_name(expr)
+            }
+            ExpressionKind::Binary {
+                left, op, right, ..
+            } => self.evaluate_binary(left, op, right, &expr.loc),
+            ExpressionKind::Unary { kind, right, .. } => {
+                let value = self.evaluate_expression(right)?;
+                self.apply(value.unary(kind), &expr.loc)
+            }
+            ExpressionKind::Cast { value, r#type } => {
+                let Some(target) = primitive_of(r#type) else {
+                    return self.not_constant("only primitive types can be cast to", &r#type.loc);
+                };
+
+                let value = self.evaluate_expression(value)?;
+                self.apply(value.cast(target), &expr.loc)
+            }
+            ExpressionKind::Call { res, .. } => {
+                let msg = match res.path() {
+                    Some(path) => format!("cannot call `{path}` in a constant"),
+                    None => "cannot call functions in a constant".to_string(),
+                };
+                self.not_constant(msg, &expr.loc)
+            }
+            _ => self.not_constant(
+                "this expression can only be evaluated at runtime",
+                &expr.loc,
+            ),
+        }
+    }
+
+    // literals are given their types by the type checker, the ones it left
+    // alone have the default type of their kind
+    fn evaluate_number(&mut self, literal: &NumberLiteral, loc: &Loc) -> Option<ConstValue> {
+        let r#type = match self.literals.get(&(self.source, loc.clone())) {
+            Some(primitive) => *primitive,
+            None if *literal.kind == LiteralKind::Float => PrimitiveType::FLOAT,
+            None => PrimitiveType::INT,
+        };
+
+        let value = match r#type {
+            PrimitiveType::Int(int) => int
+                .parse(literal.value, literal.negative)
+                .map(|value| ConstValue::Int(int, value)),
+            PrimitiveType::Float(float) => float
+                .parse(literal.value, literal.negative)
+                .map(|value| ConstValue::Float(float, float.round(value))),
+            _ => unreachable!(),
+        };
+
+        if value.is_none() {
+            let err = EvaluationError::default()
+                .kind(EvaluationErrorKind::Overflow)
+                .msg(format!(
+                    "literal `{}` does not fit in `{}`",
+                    literal.to_string(),
+                    r#type.name()
+                ));
+            self.push_error(err, loc);
+        }
+
+        return value;
+    }
+
+    fn evaluate_binary(
+        &mut self,
+        left: &ExpressionNode,
+        op: &ComplexPunctuationKind,
+        right: &ExpressionNode,
+        loc: &Loc,
+    ) -> Option<ConstValue> {
+        let left = self.evaluate_expression(left)?;
+
+        // `&&` and `||` skip their right side like they do at runtime
+        if let ConstValue::Bool(value) = left
+            && matches!(
+                (op, value),
+                (ComplexPunctuationKind::OperatorAnd, false)
+                    | (ComplexPunctuationKind::OperatorOr, true)
+            )
+        {
+            return Some(left);
+        }
+
+        let right = self.evaluate_expression(right)?;
+        self.apply(left.binary(op, &right), loc)
+    }
+
+    fn evaluate_name(&mut self, expr: &ExpressionNode) -> Option<ConstValue> {
+        let path = expr.path().unwrap();
+        let Some(symbol) = self
+            .tree
+            .uses
+            .get(&(self.source, expr.loc.clone()))
+            .copied()
+        else {
+            return self.not_constant(format!("`{path}` can only be used at runtime"), &expr.loc);
+        };
+
+        match self.tree.symbols[symbol].kind {
+            SymbolKind::Constant => {
+                if let Some(State::Evaluating) = self.states.get(&symbol) {
+                    return self.cycle_error(symbol, &expr.loc);
+                }
+
+                self.evaluate_constant(symbol)
+            }
+            SymbolKind::Static { mutable: true } => self.not_constant(
+                format!("`{path}` is a mutable static, its value can change at runtime"),
+                &expr.loc,
+            ),
+            SymbolKind::Static { mutable: false } => self.not_constant(
+                format!("`{path}` is a static, its value is only known at runtime"),
+                &expr.loc,
+            ),
+            _ => self.not_constant(format!("`{path}` cannot be used in a constant"), &expr.loc),
+        }
+    }
+
+    fn cycle_error(&mut self, symbol: usize, loc: &Loc) -> Option<ConstValue> {
+        let start = self.stack.iter().position(|&s| s == symbol).unwrap();
+        let chain = self.stack[start..]
+            .iter()
+            .chain([&symbol])
+            .map(|&s| format!("`{}`", self.tree.symbols[s].name))
+            .collect::<Vec<_>>()
+            .join(" -> ");
+
+        let err = EvaluationError::default()
+            .kind(EvaluationErrorKind::Cycle)
+            .msg(format!(
+                "`{}` depends on itself: {chain}",
+                self.tree.symbols[symbol].name
+            ));
+        self.push_error(err, loc);
+
+        return None;
+    }
+
+    fn apply(
+        &mut self,
+        result: Result<ConstValue, EvaluationError>,
+        loc: &Loc,
+    ) -> Option<ConstValue> {
+        match result {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.push_error(err, loc);
+                None
+            }
+        }
+    }
+
+    fn not_constant<T: ToString>(&mut self, msg: T, loc: &Loc) -> Option<ConstValue> {
+        let err = EvaluationError::default()
+            .kind(EvaluationErrorKind::NotConstant)
+            .msg(msg);
+        self.push_error(err, loc);
+
+        return None;
+    }
+
+    fn push_error(&mut self, err: EvaluationError, loc: &Loc) {
+        let src = &self.sources[self.source];
+        let ctx = ErrorContextBuilder::loc(loc)
+            .from_src_and_ln(src, loc.ln)
+            .build();
+
+        self.errors.push(err.ctx(ctx));
+    }
+}
+
+fn primitive_of(node: &TypeNode) -> Option<PrimitiveType> {
+    match &node.kind {
+        TypeKind::Path { path, generics } if generics.is_empty() => {
+            PrimitiveType::from_name(&path.join("::"))
+        }
+        _ => None,
+    }
+}
+
+fn collect_namespaces<'n>(node: &'n NamespaceNode, namespaces: &mut Vec<&'n NamespaceNode>) {
+    namespaces.push(node);
+
+    for child in node.children.iter() {
+        collect_namespaces(child, namespaces);
+    }
+}
@@ -0,0 +1,5 @@
+mod evaluator;
+mod values;
+
+pub use evaluator::*;
+pub use values::*;
@@ -0,0 +1,321 @@
+use std::{cmp::Ordering, fmt};
+
+use crate::{
+    errors::evaluation::{EvaluationError, EvaluationErrorKind},
+    frontend::{
+        lexing::ComplexPunctuationKind,
+        parsing::ast::expressions::UnaryExpressionKind,
+        primitives::{FloatType, IntType, PrimitiveType},
+    },
+};
+
+// what a constant evaluates to. integers are kept as the bits of their type,
+// the way they're stored at runtime
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    Int(IntType, i64),
+    Float(FloatType, f64),
+    Bool(bool),
+    Char(char),
+    String(String),
+}
+
+impl ConstValue {
+    pub fn r#type(&self) -> PrimitiveType {
+        match self {
+            ConstValue::Int(int, _) => PrimitiveType::Int(*int),
+            ConstValue::Float(float, _) => PrimitiveType::Float(*float),
+            ConstValue::Bool(_) => PrimitiveType::Bool,
+            ConstValue::Char(_) => PrimitiveType::Char,
+            ConstValue::String(_) => PrimitiveType::String,
+        }
+    }
+
+    pub fn unary(&self, kind: &UnaryExpressionKind) -> Result<ConstValue, EvaluationError> {
+        let value = match (kind, self) {
+            (UnaryExpressionKind::ArithmeticNegation, ConstValue::Int(int, bits)) => {
+                let value = -int.value(*bits);
+                if !int.contains(value) {
+                    return Err(EvaluationError::default()
+                        .kind(EvaluationErrorKind::Overflow)
+                        .msg(format!(
+                            "attempt to negate `{self}`, which overflows `{}`",
+                            int.name()
+                        )));
+                }
+
+                ConstValue::Int(*int, value as i64)
+            }
+            (UnaryExpressionKind::ArithmeticNegation, ConstValue::Float(float, value)) => {
+                ConstValue::Float(*float, -value)
+            }
+            (UnaryExpressionKind::BitwiseNegation, ConstValue::Int(int, bits)) => {
+                ConstValue::Int(*int, int.wrap(!int.value(*bits)))
+            }
+            (UnaryExpressionKind::LogicalNegation, ConstValue::Bool(value)) => {
+                ConstValue::Bool(!value)
+            }
+            _ => return Err(unsupported(format!("cannot negate `{self}`"))),
+        };
+
+        return Ok(value);
+    }
+
+    pub fn binary(
+        &self,
+        op: &ComplexPunctuationKind,
+        other: &ConstValue,
+    ) -> Result<ConstValue, EvaluationError> {
+        use ComplexPunctuationKind::*;
+
+        if let Some(result) = compare(op, self.ordering(other)) {
+            return Ok(ConstValue::Bool(result));
+        }
+
+        let value = match (self, other) {
+            (ConstValue::Int(int, left), ConstValue::Int(_, right)) => {
+                return int_binary(*int, op, int.value(*left), int.value(*right));
+            }
+            (ConstValue::Float(float, left), ConstValue::Float(_, right)) => {
+                return float_binary(*float, op, *left, *right);
+            }
+            (ConstValue::Bool(left), ConstValue::Bool(right)) => match op {
+                OperatorAnd | OperatorBitAnd => ConstValue::Bool(left & right),
+                OperatorOr | OperatorBitOr => ConstValue::Bool(left | right),
+                OperatorBitXor => ConstValue::Bool(left ^ right),
+                _ => return Err(unsupported_operator(op, self, other)),
+            },
+            (ConstValue::String(left), ConstValue::String(right)) if *op == OperatorPlus => {
+                ConstValue::String(format!("{left}{right}"))
+            }
+            _ => return Err(unsupported_operator(op, self, other)),
+        };
+
+        return Ok(value);
+    }
+
+    // numbers convert into each other the way they do at runtime, wrapping
+    // between integers and saturating from floats to integers
+    pub fn cast(&self, target: PrimitiveType) -> Result<ConstValue, EvaluationError> {
+        let value = match (self, target) {
+            (value, target) if value.r#type() == target => value.clone(),
+            (ConstValue::Int(from, bits), PrimitiveType::Int(to)) => {
+                ConstValue::Int(to, to.wrap(from.value(*bits)))
+            }
+            (ConstValue::Int(from, bits), PrimitiveType::Float(to)) => {
+                ConstValue::Float(to, to.round(from.value(*bits) as f64))
+            }
+            (ConstValue::Float(_, value), PrimitiveType::Int(to)) => {
+                ConstValue::Int(to, (*value as i128).clamp(to.min(), to.max()) as i64)
+            }
+            (ConstValue::Float(_, value), PrimitiveType::Float(to)) => {
+                ConstValue::Float(to, to.round(*value))
+            }
+            (ConstValue::Bool(value), PrimitiveType::Int(to)) => ConstValue::Int(to, *value as i64),
+            (ConstValue::Char(value), PrimitiveType::Int(to)) => {
+                ConstValue::Int(to, to.wrap(*value as i128))
+            }
+            (ConstValue::Int(IntType::UInt8, bits), PrimitiveType::Char) => {
+                ConstValue::Char(*bits as u8 as char)
+            }
+            _ => {
+                return Err(unsupported(format!(
+                    "cannot cast `{self}` to `{}`",
+                    target.name()
+                )));
+            }
+        };
+
+        return Ok(value);
+    }
+
+    fn ordering(&self, other: &ConstValue) -> Option<Ordering> {
+        match (self, other) {
+            (ConstValue::Int(int, left), ConstValue::Int(_, right)) => {
+                Some(int.value(*left).cmp(&int.value(*right)))
+            }
+            (ConstValue::Float(_, left), ConstValue::Float(_, right)) => left.partial_cmp(right),
+            (ConstValue::Bool(left), ConstValue::Bool(right)) => Some(left.cmp(right)),
+            (ConstValue::Char(left), ConstValue::Char(right)) => Some(left.cmp(right)),
+            (ConstValue::String(left), ConstValue::String(right)) => Some(left.cmp(right)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ConstValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConstValue::Int(int, bits) => write!(f, "{}", int.value(*bits)),
+            ConstValue::Float(_, value) => write!(f, "{value:?}"),
+            ConstValue::Bool(value) => write!(f, "{value}"),
+            ConstValue::Char(value) => write!(f, "{value:?}"),
+            ConstValue::String(value) => write!(f, "{value:?}"),
+        }
+    }
+}
+
+// the result of a comparison, if `op` is one. values that can't be
+// ordered, like NaN, are only unequal
+fn compare(op: &ComplexPunctuationKind, ordering: Option<Ordering>) -> Option<bool> {
+    use ComplexPunctuationKind::*;
+
+    let result = match op {
+        OperatorEqual => ordering == Some(Ordering::Equal),
+        OperatorNotEqual => ordering != Some(Ordering::Equal),
+        AngleOpen => ordering == Some(Ordering::Less),
+        AngleClose => ordering == Some(Ordering::Greater),
+        OperatorLessEqual => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        OperatorGreaterEqual => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        _ => return None,
+    };
+
+    return Some(result);
+}
+
+fn int_binary(
+    int: IntType,
+    op: &ComplexPunctuationKind,
+    left: i128,
+    right: i128,
+) -> Result<ConstValue, EvaluationError> {
+    use ComplexPunctuationKind::*;
+
+    let value = match op {
+        OperatorPlus => left.checked_add(right),
+        OperatorMinus => left.checked_sub(right),
+        OperatorMultiply => left.checked_mul(right),
+        OperatorDivide if right == 0 => {
+            return Err(division_by_zero(format!(
+                "attempt to divide `{left}` by zero"
+            )));
+        }
+        OperatorModulo if right == 0 => {
+            return Err(division_by_zero(format!(
+                "attempt to calculate the remainder of `{left}` with a divisor of zero"
+            )));
+        }
+        OperatorDivide => left.checked_div(right),
+        OperatorModulo => left.checked_rem(right),
+        OperatorPower => int_power(left, right)?,
+        OperatorBitAnd => Some(left & right),
+        OperatorBitOr => Some(left | right),
+        OperatorBitXor => Some(left ^ right),
+        OperatorShiftLeft | OperatorShiftRight if right < 0 || right >= int.bits() as i128 => None,
+        // bits shifted out of the type are dropped
+        OperatorShiftLeft => Some(int.value(int.wrap(left << right))),
+        OperatorShiftRight => Some(left >> right),
+        _ => {
+            return Err(unsupported(format!(
+                "cannot apply `{}` to `{}`",
+                op.to_string(),
+                int.name()
+            )));
+        }
+    };
+
+    match value {
+        Some(value) if int.contains(value) => Ok(ConstValue::Int(int, value as i64)),
+        _ => Err(EvaluationError::default()
+            .kind(EvaluationErrorKind::Overflow)
+            .msg(format!(
+                "attempt to compute `{left} {} {right}`, which overflows `{}`",
+                op.to_string(),
+                int.name()
+            ))),
+    }
+}
+
+// like at runtime, a negative power is one divided by the positive one
+fn int_power(base: i128, exp: i128) -> Result<Option<i128>, EvaluationError> {
+    let value = match (base, exp) {
+        (0, ..0) => {
+            return Err(division_by_zero(format!(
+                "attempt to raise zero to the negative power `{exp}`"
+            )));
+        }
+        (_, 0) | (1, _) => Some(1),
+        (-1, _) if exp % 2 == 0 => Some(1),
+        (-1, _) => Some(-1),
+        (_, ..0) => Some(0),
+        _ => u32::try_from(exp)
+            .ok()
+            .and_then(|exp| base.checked_pow(exp)),
+    };
+
+    return Ok(value);
+}
+
+fn float_binary(
+    float: FloatType,
+    op: &ComplexPunctuationKind,
+    left: f64,
+    right: f64,
+) -> Result<ConstValue, EvaluationError> {
+    use ComplexPunctuationKind::*;
+
+    let value = match op {
+        OperatorPlus => left + right,
+        OperatorMinus => left - right,
+        OperatorMultiply => left * right,
+        OperatorDivide if right == 0.0 => {
+            return Err(division_by_zero(format!(
+                "attempt to divide `{left}` by zero"
+            )));
+        }
+        OperatorModulo if right == 0.0 => {
+            return Err(division_by_zero(format!(
+                "attempt to calculate the remainder of `{left}` with a divisor of zero"
+            )));
+        }
+        OperatorDivide => left / right,
+        OperatorModulo => left % right,
+        OperatorPower => left.powf(right),
+        _ => {
+            return Err(unsupported(format!(
+                "cannot apply `{}` to `{}`",
+                op.to_string(),
+                float.name()
+            )));
+        }
+    };
+
+    // float32 values are rounded after every operation, like at runtime
+    let value = float.round(value);
+    if value.is_infinite() {
+        return Err(EvaluationError::default()
+            .kind(EvaluationErrorKind::Overflow)
+            .msg(format!(
+                "attempt to compute `{left} {} {right}`, which overflows `{}`",
+                op.to_string(),
+                float.name()
+            )));
+    }
+
+    return Ok(ConstValue::Float(float, value));
+}
+
+fn division_by_zero(msg: String) -> EvaluationError {
+    EvaluationError::default()
+        .kind(EvaluationErrorKind::DivisionByZero)
+        .msg(msg)
+}
+
+fn unsupported_operator(
+    op: &ComplexPunctuationKind,
+    left: &ConstValue,
+    right: &ConstValue,
+) -> EvaluationError {
+    unsupported(format!(
+        "cannot apply `{}` to `{}` and `{}`",
+        op.to_string(),
+        left.r#type().name(),
+        right.r#type().name()
+    ))
+}
+
+fn unsupported(msg: String) -> EvaluationError {
+    EvaluationError::default()
+        .kind(EvaluationErrorKind::NotConstant)
+        .msg(msg)
+}
@@ -4,7 +4,7 @@ use super::tokens::{
     ComplexPunctuationKind::{self, *},
     KeywordKind,
     LiteralKind::*,
-    SimplePunctuationKind, Token,
+    NumberSuffix, SimplePunctuationKind, Token,
     TokenKind::{self, *},
 };
 use crate::{
@@ -22,8 +22,10 @@ pub struct Lexer<'a> {
     pub src: ScriptSource<'a>,
 
     pub tokens: Vec<Token>,
+    pub docs: Vec<Token>,
     pub errors: Vec<SyntaxError>,
 
+    cur_idx: usize,
     cur_ln: usize,
     cur_col: usize,
     start_col_buf: usize,
@@ -32,6 +34,39 @@ pub struct Lexer<'a> {
     punct_buf: LexerValueBuffer,
 
     inside_string: bool,
+    string_kind: StringKind,
+    string_start: Loc,
+    string_lines: Vec<StringLine>,
+    interpolations: Vec<usize>,
+    escape: Option<StdString>,
+    escape_start: usize,
+
+    comment: Option<CommentKind>,
+    comment_start: Loc,
+    comment_prev: Option<char>,
+    skip_chars: usize,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum StringKind {
+    Regular,
+    Char,
+    Interpolated,
+    Raw { hashes: usize },
+    MultiLine,
+}
+
+struct StringLine {
+    start: usize,
+    indent: usize,
+    blank: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CommentKind {
+    Line,
+    Doc,
+    Block { depth: usize },
 }
 
 impl<'a> Lexer<'a> {
@@ -55,12 +90,76 @@ impl<'a> Lexer<'a> {
             return;
         }
 
-        for ch in self.src.contents.chars() {
+        for (idx, ch) in self.src.contents.chars().enumerate() {
+            self.cur_idx = idx;
             self.iteration(ch);
         }
+
+        match self.comment {
+            Some(CommentKind::Doc) => self.push_doc_comment(),
+            Some(CommentKind::Block { .. }) => {
+                self.errors.push(
+                    SyntaxError::default()
+                        .ctx(
+                            ErrorContextBuilder::span(
+                                self.comment_start.start_col,
+                                self.comment_start.end_col,
+                            )
+                            .from_src_and_ln(&self.src, self.comment_start.ln)
+                            .build(),
+                        )
+                        .kind(SyntaxErrorKind::Expected(SyntaxErrorSource::Character))
+                        .msg("unterminated block comment"),
+                );
+            }
+            _ => {}
+        }
         self.cur_col += 1;
 
         if self.inside_string {
+            let (msg, delimiter_len) = match self.string_kind {
+                StringKind::Raw { hashes } => (
+                    format!(
+                        "expected `\"{}` to close raw string literal",
+                        "#".repeat(hashes)
+                    ),
+                    hashes + 2,
+                ),
+                StringKind::MultiLine => (
+                    StdString::from("expected `\"\"\"` to close multi-line string literal"),
+                    3,
+                ),
+                StringKind::Char => (
+                    StdString::from("expected single quote to close character literal"),
+                    0,
+                ),
+                _ => (
+                    StdString::from("expected double quote to close string literal"),
+                    0,
+                ),
+            };
+
+            // multi-line strings are easier to find by their opening quotes
+            let ctx = if delimiter_len > 0 {
+                ErrorContextBuilder::span(
+                    self.string_start.start_col,
+                    self.string_start.start_col + delimiter_len,
+                )
+                .from_src_and_ln(&self.src, self.string_start.ln)
+                .build()
+            } else {
+                ErrorContextBuilder::col(self.cur_col)
+                    .from_src_and_ln(&self.src, self.cur_ln)
+                    .build()
+            };
+
+            self.errors.push(
+                SyntaxError::default()
+                    .ctx(ctx)
+                    .kind(SyntaxErrorKind::Expected(SyntaxErrorSource::Character))
+                    .msg(msg),
+            );
+        } else if !self.interpolations.is_empty() {
             self.errors.push(
                 SyntaxError::default()
                     .ctx(
@@ -69,7 +168,7 @@ impl<'a> Lexer<'a> {
                             .build(),
                     )
                     .kind(SyntaxErrorKind::Expected(SyntaxErrorSource::Character))
-                    .msg("expected double quote to close string literal"),
+                    .msg("expected `}` to close interpolated expression"),
             );
         }
 
@@ -83,18 +182,47 @@ impl<'a> Lexer<'a> {
     }
 
     fn iteration(&mut self, ch: char) {
-        // TODO: comments
-        // TODO: strings and escape sequences
-        // TODO: interpolated strings
-
         if ch == '\r' {
             return;
         }
 
+        if self.skip_chars > 0 {
+            self.skip_chars -= 1;
+            self.cur_col += 1;
+            return;
+        }
+
+        if let Some(kind) = self.comment {
+            if ch != '\n' {
+                self.cur_col += 1;
+                self.comment_iteration(kind, ch);
+                return;
+            }
+
+            self.comment_prev = None;
+            if kind == CommentKind::Doc {
+                self.push_doc_comment();
+            }
+            if !matches!(kind, CommentKind::Block { .. }) {
+                self.comment = None;
+            }
+        }
+
         if ch == '\n' {
             self.cur_col += 1;
 
-            if self.inside_string {
+            if self.inside_string
+                && matches!(
+                    self.string_kind,
+                    StringKind::Regular | StringKind::Interpolated | StringKind::Char
+                )
+            {
+                let msg = if self.string_kind == StringKind::Char {
+                    "character literals cannot be over multiple lines"
+                } else {
+                    "regular string literals cannot be over multiple lines"
+                };
+
                 self.errors.push(
                     SyntaxError::default()
                         .ctx(
@@ -103,11 +231,18 @@ impl<'a> Lexer<'a> {
                                 .build(),
                         )
                         .kind(SyntaxErrorKind::Expected(SyntaxErrorSource::Character))
-                        .msg("regular string literals cannot be over multiple lines"),
+                        .msg(msg),
                 );
 
                 self.inside_string = false;
+                self.escape = None;
                 self.reset_buffers();
+            } else if self.inside_string {
+                if self.escape.take().is_some() {
+                    self.push_escape_error(self.cur_col, "expected escape sequence");
+                }
+
+                self.push_string_char('\n');
             } else {
                 if !self.punct_buf.is_empty() {
                     self.process_complex_punctuation('\n');
@@ -129,42 +264,188 @@ impl<'a> Lexer<'a> {
 
         self.cur_col += 1;
 
-        if ch == '"' {
-            self.inside_string = !self.inside_string;
+        if self.escape.is_some() && self.escape_iteration(ch) {
+            return;
+        }
 
+        if self.inside_string && ch == '\\' && !matches!(self.string_kind, StringKind::Raw { .. }) {
+            if let Some(line) = self.string_lines.last_mut() {
+                line.blank = false;
+            }
+
+            self.escape = Some(StdString::new());
+            self.escape_start = self.cur_col;
+            return;
+        }
+
+        if self.inside_string
+            && self.string_kind == StringKind::Interpolated
+            && self.interpolated_string_iteration(ch)
+        {
+            return;
+        }
+
+        if self.inside_string
+            && matches!(
+                self.string_kind,
+                StringKind::Raw { .. } | StringKind::MultiLine
+            )
+        {
+            if ch == '"'
+                && let Some(len) = self.closing_delimiter_len()
+            {
+                self.skip_chars = len - 1;
+                self.push_string(self.cur_col + len);
+            } else {
+                self.push_string_char(ch);
+            }
+
+            return;
+        }
+
+        if self.inside_string && self.string_kind == StringKind::Char {
+            if ch == '\'' {
+                self.push_char();
+            } else {
+                self.push_string_char(ch);
+            }
+
+            return;
+        }
+
+        if ch == '\'' && !self.inside_string {
+            if !self.punct_buf.is_empty() {
+                self.process_complex_punctuation(ch);
+            } else {
+                self.push_keyword_or_ident(Loc::new(self.cur_ln, self.start_col_buf, self.cur_col));
+            }
+
+            self.start_col_buf = self.cur_col;
+            self.string_start = Loc::new(self.cur_ln, self.cur_col, self.cur_col);
+            self.kind_buf = Literal(Char);
+            self.inside_string = true;
+            self.string_kind = StringKind::Char;
+            return;
+        }
+
+        if ch == '"' {
             if self.inside_string {
-                if !self.punct_buf.is_empty() {
-                    self.process_complex_punctuation(ch);
-                } else {
-                    self.push_keyword_or_ident(Loc::new(
-                        self.cur_ln,
-                        self.start_col_buf,
-                        self.cur_col,
-                    ));
-                }
+                self.push_string(self.cur_col + 1);
+                return;
+            }
 
-                self.start_col_buf = self.cur_col;
-                self.kind_buf = Literal(String);
+            if !self.punct_buf.is_empty() {
+                self.process_complex_punctuation(ch);
             } else {
-                self.push_new(
-                    Loc::new(self.cur_ln, self.start_col_buf, self.cur_col + 1),
-                    Literal(String),
-                    Some(self.value_buf.value.clone()),
-                );
-                self.reset_buffers();
+                self.push_keyword_or_ident(Loc::new(self.cur_ln, self.start_col_buf, self.cur_col));
+            }
 
-                self.start_col_buf = self.cur_col + 1;
-                self.kind_buf = Undefined;
+            self.start_col_buf = self.cur_col;
+            self.string_start = Loc::new(self.cur_ln, self.cur_col, self.cur_col);
+            self.kind_buf = Literal(String);
+            self.inside_string = true;
+
+            if self.peek(1) == Some('"') && self.peek(2) == Some('"') {
+                self.skip_chars = 2;
+                self.string_kind = StringKind::MultiLine;
+                self.string_lines = vec![StringLine {
+                    start: 0,
+                    indent: 0,
+                    blank: true,
+                }];
+            } else {
+                self.string_kind = StringKind::Regular;
             }
 
             return;
         }
 
         if self.inside_string {
-            self.value_buf.push(ch);
+            self.push_string_char(ch);
+            return;
+        }
+
+        if ch == '/'
+            && let Some(kind) = self.recognize_comment()
+        {
+            if !self.punct_buf.is_empty() {
+                self.process_complex_punctuation(ch);
+            }
+            self.push_keyword_or_ident(Loc::new(self.cur_ln, self.start_col_buf, self.cur_col));
+
+            self.skip_chars = if kind == CommentKind::Doc { 2 } else { 1 };
+            self.comment_start = Loc::new(self.cur_ln, self.cur_col, self.cur_col + 2);
+            self.comment = Some(kind);
+            return;
+        }
+
+        if ch == '$' && self.peek(1) == Some('"') {
+            if !self.punct_buf.is_empty() {
+                self.process_complex_punctuation(ch);
+            }
+            self.push_keyword_or_ident(Loc::new(self.cur_ln, self.start_col_buf, self.cur_col));
+
+            self.push_new(
+                Loc::new(self.cur_ln, self.cur_col, self.cur_col + 2),
+                InterpolatedStringStart,
+                None,
+            );
+
+            self.skip_chars = 1;
+            self.inside_string = true;
+            self.string_kind = StringKind::Interpolated;
+            self.start_col_buf = self.cur_col + 2;
             return;
         }
 
+        if ch == 'r'
+            && (self.value_buf.is_empty() || !self.punct_buf.is_empty())
+            && let Some(hashes) = self.raw_string_hashes()
+        {
+            if !self.punct_buf.is_empty() {
+                self.process_complex_punctuation(ch);
+            }
+            self.push_keyword_or_ident(Loc::new(self.cur_ln, self.start_col_buf, self.cur_col));
+
+            self.skip_chars = hashes + 1;
+            self.string_start = Loc::new(self.cur_ln, self.cur_col, self.cur_col);
+            self.kind_buf = Literal(String);
+            self.inside_string = true;
+            self.string_kind = StringKind::Raw { hashes };
+            return;
+        }
+
+        if let Some(depth) = self.interpolations.last_mut() {
+            match ch {
+                '{' => *depth += 1,
+                '}' if *depth > 0 => *depth -= 1,
+                '}' => {
+                    if !self.punct_buf.is_empty() {
+                        self.process_complex_punctuation(ch);
+                    } else {
+                        self.push_keyword_or_ident(Loc::new(
+                            self.cur_ln,
+                            self.start_col_buf,
+                            self.cur_col,
+                        ));
+                    }
+
+                    self.push_new(
+                        Loc::new(self.cur_ln, self.cur_col, self.cur_col + 1),
+                        SimplePunctuation(SimplePunctuationKind::BraceClose),
+                        None,
+                    );
+
+                    self.interpolations.pop();
+                    self.inside_string = true;
+                    self.string_kind = StringKind::Interpolated;
+                    self.start_col_buf = self.cur_col + 1;
+                    return;
+                }
+                _ => {}
+            }
+        }
+
         if let Ok(kind) = SimplePunctuationKind::from_char(ch) {
             if !self.punct_buf.is_empty() {
                 self.process_complex_punctuation(ch);
@@ -183,7 +464,7 @@ impl<'a> Lexer<'a> {
         }
 
         if ch.is_ascii_punctuation() && ch != '_' {
-            if self.punct_buf.is_empty() && self.kind_buf != Literal(Integer) {
+            if self.punct_buf.is_empty() && !self.is_number_buffered() {
                 self.push_keyword_or_ident(Loc::new(self.cur_ln, self.start_col_buf, self.cur_col));
                 self.start_col_buf = self.cur_col;
             }
@@ -212,7 +493,14 @@ impl<'a> Lexer<'a> {
                 }
             }
             Literal(lit) if lit.is_number() => {
-                if ch.is_alphabetic() && ch != 'E' && ch != 'e' {
+                let radix_prefix = self.value_buf.value == "0" && matches!(ch, 'x' | 'o' | 'b');
+
+                // digits of non-decimal literals are validated once the literal is complete
+                if ch.is_alphabetic()
+                    && !matches!(ch, 'E' | 'e' | 'i' | 'u' | 'f')
+                    && !radix_prefix
+                    && self.number_radix() == 10
+                {
                     self.kind_buf = InvalidIdentifier;
 
                     self.errors.push(
@@ -233,6 +521,385 @@ impl<'a> Lexer<'a> {
         self.value_buf.push(ch);
     }
 
+    fn interpolated_string_iteration(&mut self, ch: char) -> bool {
+        match ch {
+            '"' => {
+                self.push_interpolated_part();
+                self.push_new(
+                    Loc::new(self.cur_ln, self.cur_col, self.cur_col + 1),
+                    InterpolatedStringEnd,
+                    None,
+                );
+
+                self.inside_string = false;
+                self.start_col_buf = self.cur_col + 1;
+            }
+            '{' | '}' if self.peek(1) == Some(ch) => {
+                self.value_buf.push(ch);
+                self.skip_chars = 1;
+            }
+            '{' => {
+                self.push_interpolated_part();
+                self.push_new(
+                    Loc::new(self.cur_ln, self.cur_col, self.cur_col + 1),
+                    SimplePunctuation(SimplePunctuationKind::BraceOpen),
+                    None,
+                );
+
+                self.interpolations.push(0);
+                self.inside_string = false;
+                self.start_col_buf = self.cur_col + 1;
+            }
+            '}' => {
+                self.errors.push(
+                    SyntaxError::default()
+                        .ctx(
+                            ErrorContextBuilder::span(self.cur_col, self.cur_col + 1)
+                                .from_src_and_ln(&self.src, self.cur_ln)
+                                .build(),
+                        )
+                        .kind(SyntaxErrorKind::Unexpected(SyntaxErrorSource::Character))
+                        .msg("use `}}` to insert a closing brace into an interpolated string"),
+                );
+            }
+            _ => return false,
+        }
+
+        return true;
+    }
+
+    fn raw_string_hashes(&self) -> Option<usize> {
+        let mut hashes = 0;
+        while self.peek(hashes + 1) == Some('#') {
+            hashes += 1;
+        }
+
+        if self.peek(hashes + 1) != Some('"') {
+            return None;
+        }
+
+        Some(hashes)
+    }
+
+    fn closing_delimiter_len(&self) -> Option<usize> {
+        match self.string_kind {
+            StringKind::Raw { hashes } => (1..=hashes)
+                .all(|offset| self.peek(offset) == Some('#'))
+                .then_some(hashes + 1),
+            StringKind::MultiLine => {
+                (self.peek(1) == Some('"') && self.peek(2) == Some('"')).then_some(3)
+            }
+            _ => None,
+        }
+    }
+
+    fn push_string_char(&mut self, ch: char) {
+        self.value_buf.push(ch);
+
+        if self.string_kind != StringKind::MultiLine {
+            return;
+        }
+
+        if ch == '\n' {
+            self.string_lines.push(StringLine {
+                start: self.value_buf.value.len(),
+                indent: 0,
+                blank: true,
+            });
+            return;
+        }
+
+        let line = self.string_lines.last_mut().unwrap();
+        if line.blank && ch.is_whitespace() {
+            line.indent += 1;
+        } else {
+            line.blank = false;
+        }
+    }
+
+    fn push_string(&mut self, end_col: usize) {
+        let value = if self.string_kind == StringKind::MultiLine {
+            self.strip_indent()
+        } else {
+            self.value_buf.value.clone()
+        };
+
+        self.push_new(
+            Loc::multiline(
+                self.string_start.ln,
+                self.string_start.start_col,
+                self.cur_ln,
+                end_col,
+            ),
+            Literal(String),
+            Some(value),
+        );
+        self.reset_buffers();
+
+        self.inside_string = false;
+        self.start_col_buf = end_col;
+    }
+
+    fn push_char(&mut self) {
+        let loc = Loc::new(self.cur_ln, self.start_col_buf, self.cur_col + 1);
+        let value = self.value_buf.value.clone();
+
+        let chars = value.chars().count();
+        let msg = match value.graphemes(true).count() {
+            0 => Some(StdString::from("empty character literal")),
+            1 if chars > 1 => Some(format!(
+                "`{value}` is made of {chars} unicode characters and cannot be a character literal, use a string instead"
+            )),
+            1 => None,
+            _ => Some(StdString::from(
+                "character literals can only contain one character, use a string instead",
+            )),
+        };
+
+        if let Some(msg) = msg {
+            self.errors.push(
+                SyntaxError::default()
+                    .ctx(
+                        ErrorContextBuilder::loc(&loc)
+                            .from_src_and_ln(&self.src, loc.ln)
+                            .build(),
+                    )
+                    .kind(SyntaxErrorKind::Invalid(SyntaxErrorSource::Token))
+                    .msg(msg),
+            );
+            self.push_reported(loc, value);
+        } else {
+            self.push_new(loc, Literal(Char), Some(value));
+        }
+
+        self.reset_buffers();
+        self.inside_string = false;
+        self.start_col_buf = self.cur_col + 1;
+    }
+
+    // the first line continues the opening quotes, so it neither counts towards
+    // nor gets stripped of the common indent
+    fn strip_indent(&self) -> StdString {
+        let value = &self.value_buf.value;
+
+        let mut lines = Vec::new();
+        for (i, line) in self.string_lines.iter().enumerate() {
+            let end = match self.string_lines.get(i + 1) {
+                Some(next) => next.start - 1,
+                None => value.len(),
+            };
+
+            lines.push((&value[line.start..end], line));
+        }
+
+        let (first_text, first_line) = lines.remove(0);
+        if lines.last().is_some_and(|(_, line)| line.blank) {
+            lines.pop();
+        }
+
+        let indent = lines
+            .iter()
+            .filter(|(_, line)| !line.blank)
+            .map(|(_, line)| line.indent)
+            .min()
+            .unwrap_or(0);
+
+        let mut result = Vec::new();
+        if !first_line.blank {
+            result.push(first_text.to_owned());
+        }
+
+        for (text, _) in lines {
+            result.push(text.chars().skip(indent).collect::<StdString>());
+        }
+
+        return result.join("\n");
+    }
+
+    fn push_interpolated_part(&mut self) {
+        self.push_new_not_empty(
+            Loc::new(self.cur_ln, self.start_col_buf, self.cur_col),
+            Literal(String),
+            self.value_buf.value.clone(),
+        );
+        self.reset_buffers();
+    }
+
+    fn escape_iteration(&mut self, ch: char) -> bool {
+        let mut escape = self.escape.take().unwrap();
+
+        let Some(kind) = escape.chars().next() else {
+            let value = match ch {
+                'n' => '\n',
+                't' => '\t',
+                '\\' => '\\',
+                '"' => '"',
+                '\'' => '\'',
+                '0' => '\0',
+                'x' | 'u' => {
+                    self.escape = Some(ch.to_string());
+                    return true;
+                }
+                _ => {
+                    self.push_escape_error(
+                        self.cur_col + 1,
+                        format!("unknown escape sequence `\\{ch}`"),
+                    );
+                    return true;
+                }
+            };
+
+            self.value_buf.push(value);
+            return true;
+        };
+
+        // a quote closes the string even when the escape is malformed
+        let closing_quote = if self.string_kind == StringKind::Char {
+            '\''
+        } else {
+            '"'
+        };
+        let end_col = if ch == closing_quote {
+            self.cur_col
+        } else {
+            self.cur_col + 1
+        };
+
+        if kind == 'x' {
+            if !ch.is_ascii_hexdigit() {
+                self.push_escape_error(end_col, "expected two hexadecimal digits after `\\x`");
+                return ch != closing_quote;
+            }
+
+            escape.push(ch);
+            if escape.len() < 3 {
+                self.escape = Some(escape);
+                return true;
+            }
+
+            let code = u8::from_str_radix(&escape[1..], 16).unwrap();
+            if code > 0x7F {
+                self.push_escape_error(end_col, "`\\x` escapes can only go up to `\\x7F`");
+            } else {
+                self.value_buf.push(code as char);
+            }
+
+            return true;
+        }
+
+        if escape.len() == 1 {
+            if ch != '{' {
+                self.push_escape_error(end_col, "expected `{` after `\\u`");
+                return ch != closing_quote;
+            }
+
+            escape.push(ch);
+            self.escape = Some(escape);
+            return true;
+        }
+
+        if ch == '}' {
+            let digits = &escape[2..];
+            let value = u32::from_str_radix(digits, 16)
+                .ok()
+                .and_then(char::from_u32);
+
+            match value {
+                Some(value) => self.value_buf.push(value),
+                None if digits.is_empty() => {
+                    self.push_escape_error(end_col, "empty unicode escape")
+                }
+                None => self.push_escape_error(
+                    end_col,
+                    format!("`{digits}` is not a valid unicode character"),
+                ),
+            }
+
+            return true;
+        }
+
+        if !ch.is_ascii_hexdigit() {
+            self.push_escape_error(end_col, "expected `}` to close unicode escape");
+            return ch != closing_quote;
+        }
+
+        if escape.len() == 8 {
+            self.push_escape_error(end_col, "unicode escapes can have at most 6 digits");
+            return true;
+        }
+
+        escape.push(ch);
+        self.escape = Some(escape);
+        return true;
+    }
+
+    fn push_escape_error<T: ToString>(&mut self, end_col: usize, msg: T) {
+        self.errors.push(
+            SyntaxError::default()
+                .ctx(
+                    ErrorContextBuilder::span(self.escape_start, end_col)
+                        .from_src_and_ln(&self.src, self.cur_ln)
+                        .build(),
+                )
+                .kind(SyntaxErrorKind::Invalid(SyntaxErrorSource::Character))
+                .msg(msg),
+        );
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.src.chars.get(self.cur_idx + offset).copied()
+    }
+
+    fn recognize_comment(&self) -> Option<CommentKind> {
+        match (self.peek(1), self.peek(2), self.peek(3)) {
+            (Some('/'), Some('/'), next) if next != Some('/') => Some(CommentKind::Doc),
+            (Some('/'), _, _) => Some(CommentKind::Line),
+            (Some('*'), _, _) => Some(CommentKind::Block { depth: 1 }),
+            _ => None,
+        }
+    }
+
+    fn comment_iteration(&mut self, kind: CommentKind, ch: char) {
+        let CommentKind::Block { depth } = kind else {
+            if kind == CommentKind::Doc {
+                self.value_buf.push(ch);
+            }
+            return;
+        };
+
+        match (self.comment_prev, ch) {
+            (Some('/'), '*') => {
+                self.comment = Some(CommentKind::Block { depth: depth + 1 });
+                self.comment_prev = None;
+            }
+            (Some('*'), '/') if depth == 1 => {
+                self.comment = None;
+                self.comment_prev = None;
+                self.start_col_buf = self.cur_col + 1;
+            }
+            (Some('*'), '/') => {
+                self.comment = Some(CommentKind::Block { depth: depth - 1 });
+                self.comment_prev = None;
+            }
+            _ => self.comment_prev = Some(ch),
+        }
+    }
+
+    fn push_doc_comment(&mut self) {
+        let text = self
+            .value_buf
+            .value
+            .strip_prefix(' ')
+            .unwrap_or(&self.value_buf.value);
+
+        self.docs.push(Token::new(
+            Loc::new(self.cur_ln, self.comment_start.start_col, self.cur_col + 1),
+            DocComment,
+            Some(text.to_owned()),
+        ));
+        self.reset_buffers();
+    }
+
     fn push_keyword_or_ident(&mut self, loc: Loc) {
         let loc = Loc::new(loc.ln, loc.start_col, loc.end_col);
 
@@ -242,17 +909,191 @@ impl<'a> Lexer<'a> {
             } else {
                 self.push_new(loc, Keyword(kind), None);
             }
+        } else if let Literal(lit) = &self.kind_buf
+            && lit.is_number()
+        {
+            self.push_number(loc);
         } else {
             self.push_new_not_empty(loc, self.kind_buf.clone(), self.value_buf.value.clone());
         }
         self.reset_buffers();
     }
 
+    fn push_number(&mut self, loc: Loc) {
+        let radix = self.number_radix();
+
+        // `f` is a digit in hexadecimal literals, so they can only take integer suffixes
+        let prefix_len = if radix == 10 { 1 } else { 2 };
+        let suffix_start = self
+            .value_buf
+            .value
+            .char_indices()
+            .skip(prefix_len)
+            .find(|(_, ch)| matches!(ch, 'i' | 'u') || (*ch == 'f' && radix == 10))
+            .map(|(idx, _)| idx);
+
+        let (raw, suffix) = match suffix_start {
+            Some(idx) => {
+                let (raw, suffix_text) = self.value_buf.value.split_at(idx);
+                let suffix_len = suffix_text.chars().count();
+                let suffix_loc = Loc::new(loc.ln, loc.end_col - suffix_len, loc.end_col);
+
+                let Ok(suffix) = NumberSuffix::from_str(suffix_text) else {
+                    self.push_number_error(
+                        &loc,
+                        suffix_loc,
+                        format!("invalid suffix `{suffix_text}` for number literal"),
+                    );
+                    return;
+                };
+
+                if self.kind_buf == Literal(Float) && !suffix.is_float() {
+                    self.push_number_error(
+                        &loc,
+                        suffix_loc,
+                        format!("float literals cannot have integer suffix `{suffix_text}`"),
+                    );
+                    return;
+                }
+
+                (raw.to_owned(), Some(suffix))
+            }
+            None => (self.value_buf.value.clone(), None),
+        };
+
+        let mut kind = self.kind_buf.clone();
+        if radix == 10 && raw.contains(['e', 'E']) || suffix.is_some_and(|s| s.is_float()) {
+            kind = Literal(Float);
+        }
+
+        if raw.ends_with('_') && suffix.is_none() {
+            self.push_number_error(
+                &loc,
+                Loc::new(loc.ln, loc.end_col - 1, loc.end_col),
+                "numeric literals cannot end with `_`",
+            );
+            return;
+        }
+
+        if radix == 10 {
+            let value: StdString = raw.chars().filter(|ch| *ch != '_').collect();
+            if kind == Literal(Integer) && value.parse::<u64>().is_err() {
+                self.push_number_error(&loc, loc.clone(), "integer literal is too large");
+                return;
+            }
+
+            self.push_suffixed(loc, kind, value, suffix);
+            return;
+        }
+
+        let radix_name = match radix {
+            16 => "hexadecimal",
+            8 => "octal",
+            _ => "binary",
+        };
+
+        if kind == Literal(Float) {
+            self.push_number_error(
+                &loc,
+                loc.clone(),
+                format!("{radix_name} literals cannot be floats"),
+            );
+            return;
+        }
+
+        let mut digits = StdString::new();
+        for (i, ch) in raw.chars().enumerate().skip(2) {
+            if ch == '_' {
+                continue;
+            }
+
+            if !ch.is_digit(radix) {
+                let col = loc.start_col + i;
+                self.push_number_error(
+                    &loc,
+                    Loc::new(loc.ln, col, col + 1),
+                    format!("invalid digit `{ch}` in {radix_name} literal"),
+                );
+                return;
+            }
+
+            digits.push(ch);
+        }
+
+        if digits.is_empty() {
+            self.push_number_error(
+                &loc,
+                loc.clone(),
+                format!("expected digits after `{}`", &raw[..2]),
+            );
+            return;
+        }
+
+        let Ok(value) = u64::from_str_radix(&digits, radix) else {
+            self.push_number_error(&loc, loc.clone(), "integer literal is too large");
+            return;
+        };
+
+        self.push_suffixed(loc, Literal(Integer), value.to_string(), suffix);
+    }
+
+    fn push_suffixed(
+        &mut self,
+        loc: Loc,
+        kind: TokenKind,
+        value: StdString,
+        suffix: Option<NumberSuffix>,
+    ) {
+        let mut token = Token::new(loc, kind, Some(value));
+        token.suffix = suffix;
+        self.push(token);
+    }
+
+    // `literal` is the whole number, `loc` the part of it that's wrong
+    fn push_number_error<T: ToString>(&mut self, literal: &Loc, loc: Loc, msg: T) {
+        self.errors.push(
+            SyntaxError::default()
+                .ctx(
+                    ErrorContextBuilder::loc(&loc)
+                        .from_src_and_ln(&self.src, loc.ln)
+                        .build(),
+                )
+                .kind(SyntaxErrorKind::Invalid(SyntaxErrorSource::Token))
+                .msg(msg),
+        );
+        self.push_reported(literal.clone(), self.value_buf.value.clone());
+    }
+
+    fn number_radix(&self) -> u32 {
+        match self.value_buf.value.get(..2) {
+            Some("0x") => 16,
+            Some("0o") => 8,
+            Some("0b") => 2,
+            _ => 10,
+        }
+    }
+
+    // integers keep their digits buffered while punctuation is collected, since
+    // a `.` or an exponent sign could still continue them
+    fn push_pending_number(&mut self) {
+        if !self.is_number_buffered() {
+            return;
+        }
+
+        let end_col = self.cur_col - self.punct_buf.count();
+        self.push_keyword_or_ident(Loc::new(self.cur_ln, self.start_col_buf, end_col));
+        self.start_col_buf = end_col;
+    }
+
     fn process_complex_punctuation(&mut self, ch: char) {
         match self.punct_buf.value.as_str() {
             "." => self.recognize_dot(ch),
-            "-" => self.recognize_minus(ch),
+            "-" if self.is_exponent_sign(ch) => {
+                self.kind_buf = Literal(Float);
+                self.value_buf.push('-');
+            }
             _ => {
+                self.push_pending_number();
                 self.decompose_complex_punctuation();
             }
         }
@@ -265,7 +1106,7 @@ impl<'a> Lexer<'a> {
             self.kind_buf = Literal(Float);
             self.value_buf.push('.');
         } else {
-            if self.kind_buf == Literal(Integer) {
+            if self.is_number_buffered() {
                 self.push_keyword_or_ident(Loc::new(self.cur_ln, self.start_col_buf, self.cur_col));
                 self.start_col_buf = self.cur_col;
             }
@@ -280,23 +1121,15 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    fn recognize_minus(&mut self, ch: char) {
-        if ch.is_digit(10)
-            && (self.value_buf.value.ends_with("e") || self.value_buf.value.ends_with("E"))
-            && let Literal(lit) = &self.kind_buf
-            && lit.is_number()
-        {
-            self.kind_buf = Literal(Float);
-            self.value_buf.push('-');
-        } else {
-            self.push_new(
-                Loc::new(self.cur_ln, self.cur_col - 1, self.cur_col),
-                ComplexPunctuation(OperatorMinus),
-                None,
-            );
+    fn is_exponent_sign(&self, ch: char) -> bool {
+        ch.is_ascii_digit()
+            && self.number_radix() == 10
+            && (self.value_buf.value.ends_with('e') || self.value_buf.value.ends_with('E'))
+            && self.is_number_buffered()
+    }
 
-            self.start_col_buf = self.cur_col;
-        }
+    fn is_number_buffered(&self) -> bool {
+        matches!(&self.kind_buf, Literal(lit) if lit.is_number())
     }
 
     fn decompose_complex_punctuation(&mut self) {
@@ -377,6 +1210,12 @@ impl<'a> Lexer<'a> {
         self.tokens.push(token);
     }
 
+    // an invalid literal that was already reported still takes its place, so
+    // that the parser doesn't report a missing expression as well
+    fn push_reported(&mut self, loc: Loc, value: StdString) {
+        self.tokens.push(Token::new(loc, Undefined, Some(value)));
+    }
+
     fn push_new(&mut self, loc: Loc, kind: TokenKind, value: Option<StdString>) {
         let token = Token::new(loc, kind, value);
         self.push(token);
@@ -401,8 +1240,10 @@ impl<'a> Default for Lexer<'a> {
         Self {
             src: ScriptSource::new(PathBuf::new(), &DEFAULT_SRC_CONTENTS),
             tokens: Vec::new(),
+            docs: Vec::new(),
             errors: Vec::new(),
 
+            cur_idx: 0,
             cur_ln: 1,
             cur_col: 0,
             start_col_buf: 1,
@@ -410,6 +1251,17 @@ impl<'a> Default for Lexer<'a> {
             value_buf: LexerValueBuffer::new(),
             punct_buf: LexerValueBuffer::new(),
             inside_string: false,
+            string_kind: StringKind::Regular,
+            string_start: Loc::new(1, 1, 1),
+            string_lines: Vec::new(),
+            interpolations: Vec::new(),
+            escape: None,
+            escape_start: 0,
+
+            comment: None,
+            comment_start: Loc::new(1, 1, 1),
+            comment_prev: None,
+            skip_chars: 0,
         }
     }
 }
@@ -3,4 +3,3 @@ mod tokens;
 
 pub use lexer::*;
 pub use tokens::*;
-
@@ -3,18 +3,30 @@ use std::{
     str::FromStr,
 };
 
-use crate::{frontend::Loc, utils::colors::*};
+use crate::{
+    frontend::{
+        primitives::{FloatType, IntType, PrimitiveType},
+        Loc,
+    },
+    utils::colors::*,
+};
 
 #[derive(Clone, Debug)]
 pub struct Token {
     pub loc: Loc,
     pub kind: TokenKind,
     pub value: Option<String>,
+    pub suffix: Option<NumberSuffix>,
 }
 
 impl Token {
     pub fn new(loc: Loc, kind: TokenKind, value: Option<String>) -> Token {
-        Token { loc, kind, value }
+        Token {
+            loc,
+            kind,
+            value,
+            suffix: None,
+        }
     }
 }
 
@@ -28,6 +40,11 @@ pub enum TokenKind {
     SimplePunctuation(SimplePunctuationKind),
     ComplexPunctuation(ComplexPunctuationKind),
 
+    InterpolatedStringStart,
+    InterpolatedStringEnd,
+
+    DocComment,
+
     EOF,
     Undefined,
     InvalidIdentifier,
@@ -50,6 +67,7 @@ pub enum LiteralKind {
     Integer,
     Float,
     String,
+    Char,
     Boolean,
 }
 
@@ -65,6 +83,81 @@ impl ToString for LiteralKind {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberSuffix {
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Float32,
+    Float64,
+}
+
+impl NumberSuffix {
+    pub fn is_float(&self) -> bool {
+        matches!(self, NumberSuffix::Float32 | NumberSuffix::Float64)
+    }
+
+    pub fn r#type(&self) -> PrimitiveType {
+        use IntType::*;
+
+        match self {
+            Self::Int8 => PrimitiveType::Int(Int8),
+            Self::Int16 => PrimitiveType::Int(Int16),
+            Self::Int32 => PrimitiveType::Int(Int32),
+            Self::Int64 => PrimitiveType::Int(Int64),
+            Self::UInt8 => PrimitiveType::Int(UInt8),
+            Self::UInt16 => PrimitiveType::Int(UInt16),
+            Self::UInt32 => PrimitiveType::Int(UInt32),
+            Self::UInt64 => PrimitiveType::Int(UInt64),
+            Self::Float32 => PrimitiveType::Float(FloatType::Float32),
+            Self::Float64 => PrimitiveType::Float(FloatType::Float64),
+        }
+    }
+}
+
+impl FromStr for NumberSuffix {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "i8" => Ok(Self::Int8),
+            "i16" => Ok(Self::Int16),
+            "i32" => Ok(Self::Int32),
+            "i64" => Ok(Self::Int64),
+            "u8" => Ok(Self::UInt8),
+            "u16" => Ok(Self::UInt16),
+            "u32" => Ok(Self::UInt32),
+            "u64" => Ok(Self::UInt64),
+            "f32" => Ok(Self::Float32),
+            "f64" => Ok(Self::Float64),
+            _ => Err(()),
+        }
+    }
+}
+
+impl ToString for NumberSuffix {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Int8 => "i8",
+            Self::Int16 => "i16",
+            Self::Int32 => "i32",
+            Self::Int64 => "i64",
+            Self::UInt8 => "u8",
+            Self::UInt16 => "u16",
+            Self::UInt32 => "u32",
+            Self::UInt64 => "u64",
+            Self::Float32 => "f32",
+            Self::Float64 => "f64",
+        }
+        .to_string()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum KeywordKind {
     Var,
@@ -75,6 +168,14 @@ pub enum KeywordKind {
 
     Const,
     Static,
+    Struct,
+    Enum,
+    Trait,
+    Impl,
+
+    Use,
+    As,
+    Namespace,
 
     True,
     False,
@@ -82,8 +183,13 @@ pub enum KeywordKind {
     Return,
 
     While,
+    For,
+    In,
+    Break,
+    Continue,
     If,
     Else,
+    Match,
 }
 
 impl FromStr for KeywordKind {
@@ -99,6 +205,14 @@ impl FromStr for KeywordKind {
 
             "const" => Ok(Self::Const),
             "static" => Ok(Self::Static),
+            "struct" => Ok(Self::Struct),
+            "enum" => Ok(Self::Enum),
+            "trait" => Ok(Self::Trait),
+            "impl" => Ok(Self::Impl),
+
+            "use" => Ok(Self::Use),
+            "as" => Ok(Self::As),
+            "namespace" => Ok(Self::Namespace),
 
             "true" => Ok(Self::True),
             "false" => Ok(Self::False),
@@ -106,13 +220,36 @@ impl FromStr for KeywordKind {
             "return" => Ok(Self::Return),
 
             "while" => Ok(Self::While),
+            "for" => Ok(Self::For),
+            "in" => Ok(Self::In),
+            "break" => Ok(Self::Break),
+            "continue" => Ok(Self::Continue),
             "if" => Ok(Self::If),
             "else" => Ok(Self::Else),
+            "match" => Ok(Self::Match),
             _ => Err(()),
         }
     }
 }
 
+impl KeywordKind {
+    pub fn starts_item(&self) -> bool {
+        matches!(
+            self,
+            Self::Pub
+                | Self::Const
+                | Self::Static
+                | Self::Struct
+                | Self::Enum
+                | Self::Trait
+                | Self::Impl
+                | Self::Func
+                | Self::Use
+                | Self::Namespace
+        )
+    }
+}
+
 impl ToString for KeywordKind {
     fn to_string(&self) -> String {
         format!("{:?}", self).to_lowercase()
@@ -178,19 +315,27 @@ pub enum ComplexPunctuationKind {
     OperatorOr,
     OperatorNot,
 
+    OperatorBitAnd,
+    OperatorBitOr,
+    OperatorBitXor,
+    OperatorBitNot,
+    OperatorShiftLeft,
+    OperatorShiftRight,
+
     Assignment(AssignmentKind),
 
     ReturnSeparator,
     MemberSeparator,
     NamespaceSeparator,
     Colon,
+    Range,
+    RangeInclusive,
 
     AngleOpen,
     AngleClose,
 
-    Tilde,
-
     InlineBody,
+    Optional,
 }
 
 impl FromStr for ComplexPunctuationKind {
@@ -214,17 +359,25 @@ impl FromStr for ComplexPunctuationKind {
             "||" => Ok(Self::OperatorOr),
             "!" => Ok(Self::OperatorNot),
 
+            // `>>` is left to the parser, so that nested generics can be closed
+            "&" => Ok(Self::OperatorBitAnd),
+            "|" => Ok(Self::OperatorBitOr),
+            "^" => Ok(Self::OperatorBitXor),
+            "~" => Ok(Self::OperatorBitNot),
+            "<<" => Ok(Self::OperatorShiftLeft),
+
             "->" => Ok(Self::ReturnSeparator),
             "." => Ok(Self::MemberSeparator),
             "::" => Ok(Self::NamespaceSeparator),
             ":" => Ok(Self::Colon),
+            ".." => Ok(Self::Range),
+            "..=" => Ok(Self::RangeInclusive),
 
             "<" => Ok(Self::AngleOpen),
             ">" => Ok(Self::AngleClose),
 
-            "~" => Ok(Self::Tilde),
-
             "=>" => Ok(Self::InlineBody),
+            "?" => Ok(Self::Optional),
             _ => match AssignmentKind::from_str(s) {
                 Ok(kind) => Ok(Self::Assignment(kind)),
                 Err(_) => Err(()),
@@ -256,17 +409,25 @@ impl ToString for ComplexPunctuationKind {
             Self::OperatorOr => "||",
             Self::OperatorNot => "!",
 
+            Self::OperatorBitAnd => "&",
+            Self::OperatorBitOr => "|",
+            Self::OperatorBitXor => "^",
+            Self::OperatorBitNot => "~",
+            Self::OperatorShiftLeft => "<<",
+            Self::OperatorShiftRight => ">>",
+
             Self::ReturnSeparator => "->",
             Self::MemberSeparator => ".",
             Self::NamespaceSeparator => "::",
             Self::Colon => ":",
+            Self::Range => "..",
+            Self::RangeInclusive => "..=",
 
             Self::AngleOpen => "<",
             Self::AngleClose => ">",
 
-            Self::Tilde => "~",
-
             Self::InlineBody => "=>",
+            Self::Optional => "?",
 
             Self::Assignment(_) => unreachable!(),
         }
@@ -283,6 +444,11 @@ pub enum AssignmentKind {
     DivideEquals,
     ModuloEquals,
     PowerEquals,
+    BitAndEquals,
+    BitOrEquals,
+    BitXorEquals,
+    ShiftLeftEquals,
+    ShiftRightEquals,
 }
 
 impl FromStr for AssignmentKind {
@@ -297,11 +463,35 @@ impl FromStr for AssignmentKind {
             "/=" => Ok(Self::DivideEquals),
             "%=" => Ok(Self::ModuloEquals),
             "**=" => Ok(Self::PowerEquals),
+            "&=" => Ok(Self::BitAndEquals),
+            "|=" => Ok(Self::BitOrEquals),
+            "^=" => Ok(Self::BitXorEquals),
+            "<<=" => Ok(Self::ShiftLeftEquals),
             _ => Err(()),
         }
     }
 }
 
+impl AssignmentKind {
+    // the operator a compound assignment applies, `a += b` is `a = a + b`
+    pub fn operator(&self) -> Option<ComplexPunctuationKind> {
+        match self {
+            Self::Straight => None,
+            Self::PlusEquals => Some(ComplexPunctuationKind::OperatorPlus),
+            Self::MinusEquals => Some(ComplexPunctuationKind::OperatorMinus),
+            Self::MultiplyEquals => Some(ComplexPunctuationKind::OperatorMultiply),
+            Self::DivideEquals => Some(ComplexPunctuationKind::OperatorDivide),
+            Self::ModuloEquals => Some(ComplexPunctuationKind::OperatorModulo),
+            Self::PowerEquals => Some(ComplexPunctuationKind::OperatorPower),
+            Self::BitAndEquals => Some(ComplexPunctuationKind::OperatorBitAnd),
+            Self::BitOrEquals => Some(ComplexPunctuationKind::OperatorBitOr),
+            Self::BitXorEquals => Some(ComplexPunctuationKind::OperatorBitXor),
+            Self::ShiftLeftEquals => Some(ComplexPunctuationKind::OperatorShiftLeft),
+            Self::ShiftRightEquals => Some(ComplexPunctuationKind::OperatorShiftRight),
+        }
+    }
+}
+
 impl ToString for AssignmentKind {
     fn to_string(&self) -> String {
         match self {
@@ -312,6 +502,11 @@ impl ToString for AssignmentKind {
             Self::DivideEquals => "/=",
             Self::ModuloEquals => "%=",
             Self::PowerEquals => "**=",
+            Self::BitAndEquals => "&=",
+            Self::BitOrEquals => "|=",
+            Self::BitXorEquals => "^=",
+            Self::ShiftLeftEquals => "<<=",
+            Self::ShiftRightEquals => ">>=",
         }
         .to_string()
     }
@@ -322,6 +517,8 @@ impl fmt::Display for Token {
         let value = if let Some(value) = &self.value {
             if self.kind == TokenKind::Literal(LiteralKind::String) {
                 format!("\"{BOLD}{}\"{RESET}", value)
+            } else if self.kind == TokenKind::Literal(LiteralKind::Char) {
+                format!("'{BOLD}{}'{RESET}", value)
             } else {
                 format!("{BOLD}{}{RESET}", value)
             }
@@ -329,6 +526,11 @@ impl fmt::Display for Token {
             String::from("")
         };
 
+        let value = match &self.suffix {
+            Some(suffix) => format!("{value} {GRAY}{}{RESET}", suffix.to_string()),
+            None => value,
+        };
+
         let kind = match &self.kind {
             TokenKind::Literal(kind) => format!("{:?}", kind),
             TokenKind::Keyword(kind) => format!("Keyword {BOLD}{}{RESET}", kind.to_string()),
@@ -341,9 +543,15 @@ impl fmt::Display for Token {
             _ => format!("{:?}", self.kind),
         };
 
+        let ln = if self.loc.ln == self.loc.end_ln {
+            self.loc.ln.to_string()
+        } else {
+            format!("{}-{}", self.loc.ln, self.loc.end_ln)
+        };
+
         let loc = format!(
             "{GRAY}l:{WHITE}{}{GRAY}, c:{WHITE}{}-{}{RESET}",
-            self.loc.ln, self.loc.start_col, self.loc.end_col
+            ln, self.loc.start_col, self.loc.end_col
         );
 
         write!(f, "{:>38} | {} {}", loc, kind, value)
@@ -1,19 +1,34 @@
+pub mod checking;
+pub mod evaluating;
 pub mod lexing;
+pub mod modules;
 pub mod parsing;
+pub mod primitives;
+pub mod resolving;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Loc {
     pub ln: usize,
+    pub end_ln: usize,
     pub start_col: usize,
     pub end_col: usize,
 }
 
 impl Loc {
     pub fn new(ln: usize, start_col: usize, end_col: usize) -> Loc {
+        Loc::multiline(ln, start_col, ln, end_col)
+    }
+
+    pub fn multiline(ln: usize, start_col: usize, end_ln: usize, end_col: usize) -> Loc {
         Loc {
             ln,
+            end_ln,
             start_col,
             end_col,
         }
     }
+
+    pub fn between(start: &Loc, end: &Loc) -> Loc {
+        Loc::multiline(start.ln, start.start_col, end.end_ln, end.end_col)
+    }
 }
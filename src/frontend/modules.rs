@@ -0,0 +1,43 @@
+use std::path::{Path, PathBuf};
+
+pub const SOURCE_EXTENSION: &str = "meo";
+pub const STD_NAMESPACE: &str = "std";
+
+// what the standard library provides, all of it is built into the compiler
+const BUILTIN_NAMESPACES: [&str; 1] = ["console"];
+const BUILTINS: [&str; 3] = ["console::push", "console::push_line", "Dict::new"];
+const BUILTIN_TYPES: [&str; 1] = ["Dict"];
+
+// every file is a namespace named after its path relative to the main file,
+// `use a::b::c` tries `a/b/c.meo`, then `a/b.meo`, then `a.meo`
+pub fn resolve_module(root: &Path, path: &[String]) -> Option<(Vec<String>, PathBuf)> {
+    for len in (1..=path.len()).rev() {
+        let mut file = root.to_path_buf();
+        file.extend(&path[..len]);
+        file.set_extension(SOURCE_EXTENSION);
+
+        if file.is_file() {
+            return Some((path[..len].to_vec(), file));
+        }
+    }
+
+    return None;
+}
+
+pub fn std_path(path: &str) -> &str {
+    path.strip_prefix(STD_NAMESPACE)
+        .and_then(|path| path.strip_prefix("::"))
+        .unwrap_or(path)
+}
+
+pub fn is_builtin(path: &str) -> bool {
+    BUILTINS.contains(&std_path(path))
+}
+
+pub fn is_builtin_type(path: &str) -> bool {
+    BUILTIN_TYPES.contains(&std_path(path))
+}
+
+pub fn is_builtin_namespace(path: &str) -> bool {
+    BUILTIN_NAMESPACES.contains(&std_path(path))
+}
@@ -1,10 +1,97 @@
 use std::isize;
 
-use crate::frontend::lexing::{AssignmentKind, ComplexPunctuationKind, LiteralKind};
+use crate::frontend::{
+    lexing::{AssignmentKind, ComplexPunctuationKind, LiteralKind, NumberSuffix},
+    Loc,
+};
+
+use super::{patterns::MatchArmNode, types::TypeNode};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExpressionNode {
     pub kind: ExpressionKind,
+    pub loc: Loc,
+}
+
+impl ExpressionNode {
+    // the name an identifier or a `::` path refers to, e.g. `a::b::c`
+    pub fn path(&self) -> Option<String> {
+        match &self.kind {
+            ExpressionKind::Identifier { name } => Some(name.clone()),
+            ExpressionKind::Resolution {
+                left,
+                right,
+                kind: ResolutionExpressionKind::Namespace,
+            } => Some(format!("{}::{}", left.path()?, right.path()?)),
+            _ => None,
+        }
+    }
+
+    // a number literal with an optional minus in front, the sign is part of
+    // the literal when checking whether it fits in its type
+    pub fn number(&self) -> Option<NumberLiteral<'_>> {
+        match &self.kind {
+            ExpressionKind::Literal {
+                kind,
+                value,
+                suffix,
+            } if kind.is_number() => Some(NumberLiteral {
+                kind,
+                value,
+                suffix: *suffix,
+                negative: false,
+            }),
+            ExpressionKind::Unary {
+                op: ComplexPunctuationKind::OperatorMinus,
+                right,
+                ..
+            } => {
+                let literal = right.number()?;
+                Some(NumberLiteral {
+                    negative: !literal.negative,
+                    ..literal
+                })
+            }
+            _ => None,
+        }
+    }
+
+    // number literals and arithmetic on nothing but them, which take their
+    // type from the operand next to them like a single literal does
+    pub fn is_literal_arithmetic(&self) -> bool {
+        if self.number().is_some() {
+            return true;
+        }
+
+        match &self.kind {
+            ExpressionKind::Binary {
+                kind, left, right, ..
+            } => kind.keeps_type() && left.is_literal_arithmetic() && right.is_literal_arithmetic(),
+            ExpressionKind::Unary { kind, right, .. } => {
+                !matches!(kind, UnaryExpressionKind::LogicalNegation)
+                    && right.is_literal_arithmetic()
+            }
+            _ => false,
+        }
+    }
+}
+
+pub struct NumberLiteral<'e> {
+    pub kind: &'e LiteralKind,
+    pub value: &'e str,
+    pub suffix: Option<NumberSuffix>,
+    pub negative: bool,
+}
+
+impl ToString for NumberLiteral<'_> {
+    fn to_string(&self) -> String {
+        let sign = if self.negative { "-" } else { "" };
+        let suffix = self
+            .suffix
+            .map_or(String::new(), |suffix| suffix.to_string());
+
+        format!("{sign}{}{suffix}", self.value)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -12,17 +99,52 @@ pub enum ExpressionKind {
     Literal {
         kind: LiteralKind,
         value: String,
+        suffix: Option<NumberSuffix>,
     },
 
     Identifier {
         name: String,
     },
 
+    Interpolation {
+        parts: Vec<InterpolationPart>,
+    },
+
     Call {
         res: Box<ExpressionNode>,
         args: Vec<ExpressionNode>,
     },
 
+    MethodCall {
+        object: Box<ExpressionNode>,
+        method: String,
+        args: Vec<ExpressionNode>,
+    },
+
+    Struct {
+        res: Box<ExpressionNode>,
+        fields: Vec<FieldValueNode>,
+    },
+
+    Array {
+        elements: Vec<ExpressionNode>,
+    },
+
+    Tuple {
+        elements: Vec<ExpressionNode>,
+    },
+
+    Index {
+        object: Box<ExpressionNode>,
+        index: Box<ExpressionNode>,
+    },
+
+    Range {
+        start: Box<ExpressionNode>,
+        end: Box<ExpressionNode>,
+        inclusive: bool,
+    },
+
     Resolution {
         left: Box<ExpressionNode>,
         right: Box<ExpressionNode>,
@@ -47,12 +169,36 @@ pub enum ExpressionKind {
         op: AssignmentKind,
         right: Box<ExpressionNode>,
     },
+
+    Cast {
+        value: Box<ExpressionNode>,
+        r#type: TypeNode,
+    },
+
+    Match {
+        value: Box<ExpressionNode>,
+        arms: Vec<MatchArmNode<ExpressionNode>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldValueNode {
+    pub name: String,
+    pub value: ExpressionNode,
+    pub loc: Loc,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InterpolationPart {
+    Literal(String),
+    Expression(ExpressionNode),
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum UnaryExpressionKind {
     ArithmeticNegation,
     LogicalNegation,
+    BitwiseNegation,
 }
 
 impl UnaryExpressionKind {
@@ -60,6 +206,7 @@ impl UnaryExpressionKind {
         match opr {
             ComplexPunctuationKind::OperatorMinus => Ok(UnaryExpressionKind::ArithmeticNegation),
             ComplexPunctuationKind::OperatorNot => Ok(UnaryExpressionKind::LogicalNegation),
+            ComplexPunctuationKind::OperatorBitNot => Ok(UnaryExpressionKind::BitwiseNegation),
             _ => Err(()),
         }
     }
@@ -67,16 +214,31 @@ impl UnaryExpressionKind {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum BinaryExpressionKind {
-    LogicalAnd,
     LogicalOr,
+    LogicalAnd,
     Equality,
     Relational,
+    BitwiseOr,
+    BitwiseXor,
+    BitwiseAnd,
+    Shift,
     Additive,
     Multiplicative,
     Exponential,
 }
 
 impl BinaryExpressionKind {
+    // whether the result has the type of the operands
+    pub fn keeps_type(&self) -> bool {
+        !matches!(
+            self,
+            BinaryExpressionKind::LogicalOr
+                | BinaryExpressionKind::LogicalAnd
+                | BinaryExpressionKind::Equality
+                | BinaryExpressionKind::Relational
+        )
+    }
+
     pub fn from_punct(opr: &ComplexPunctuationKind) -> Result<BinaryExpressionKind, ()> {
         match opr {
             ComplexPunctuationKind::OperatorAnd => Ok(BinaryExpressionKind::LogicalAnd),
@@ -91,6 +253,15 @@ impl BinaryExpressionKind {
             ComplexPunctuationKind::OperatorLessEqual => Ok(BinaryExpressionKind::Relational),
             ComplexPunctuationKind::OperatorGreaterEqual => Ok(BinaryExpressionKind::Relational),
 
+            ComplexPunctuationKind::OperatorBitOr => Ok(BinaryExpressionKind::BitwiseOr),
+
+            ComplexPunctuationKind::OperatorBitXor => Ok(BinaryExpressionKind::BitwiseXor),
+
+            ComplexPunctuationKind::OperatorBitAnd => Ok(BinaryExpressionKind::BitwiseAnd),
+
+            ComplexPunctuationKind::OperatorShiftLeft => Ok(BinaryExpressionKind::Shift),
+            ComplexPunctuationKind::OperatorShiftRight => Ok(BinaryExpressionKind::Shift),
+
             ComplexPunctuationKind::OperatorPlus => Ok(BinaryExpressionKind::Additive),
             ComplexPunctuationKind::OperatorMinus => Ok(BinaryExpressionKind::Additive),
 
@@ -105,15 +276,19 @@ impl BinaryExpressionKind {
     }
 
     pub fn lowest() -> BinaryExpressionKind {
-        BinaryExpressionKind::LogicalAnd
+        BinaryExpressionKind::LogicalOr
     }
 
     pub fn from_precedence(precedence: isize) -> Result<BinaryExpressionKind, ()> {
         match precedence {
-            -4 => Ok(BinaryExpressionKind::LogicalAnd),
-            -3 => Ok(BinaryExpressionKind::LogicalOr),
-            -2 => Ok(BinaryExpressionKind::Equality),
-            -1 => Ok(BinaryExpressionKind::Relational),
+            -8 => Ok(BinaryExpressionKind::LogicalOr),
+            -7 => Ok(BinaryExpressionKind::LogicalAnd),
+            -6 => Ok(BinaryExpressionKind::Equality),
+            -5 => Ok(BinaryExpressionKind::Relational),
+            -4 => Ok(BinaryExpressionKind::BitwiseOr),
+            -3 => Ok(BinaryExpressionKind::BitwiseXor),
+            -2 => Ok(BinaryExpressionKind::BitwiseAnd),
+            -1 => Ok(BinaryExpressionKind::Shift),
             0 => Ok(BinaryExpressionKind::Additive),
             1 => Ok(BinaryExpressionKind::Multiplicative),
             2 => Ok(BinaryExpressionKind::Exponential),
@@ -123,10 +298,14 @@ impl BinaryExpressionKind {
 
     pub fn precedence(&self) -> isize {
         match self {
-            BinaryExpressionKind::LogicalAnd => -4,
-            BinaryExpressionKind::LogicalOr => -3,
-            BinaryExpressionKind::Equality => -2,
-            BinaryExpressionKind::Relational => -1,
+            BinaryExpressionKind::LogicalOr => -8,
+            BinaryExpressionKind::LogicalAnd => -7,
+            BinaryExpressionKind::Equality => -6,
+            BinaryExpressionKind::Relational => -5,
+            BinaryExpressionKind::BitwiseOr => -4,
+            BinaryExpressionKind::BitwiseXor => -3,
+            BinaryExpressionKind::BitwiseAnd => -2,
+            BinaryExpressionKind::Shift => -1,
             BinaryExpressionKind::Additive => 0,
             BinaryExpressionKind::Multiplicative => 1,
             BinaryExpressionKind::Exponential => 2,
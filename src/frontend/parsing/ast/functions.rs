@@ -1,8 +1,15 @@
-use super::{bodies::BodyNode, expressions::ExpressionNode, types::TypeNode};
+use crate::frontend::Loc;
+
+use super::{
+    bodies::BodyNode,
+    expressions::ExpressionNode,
+    types::{GenericParamNode, TypeNode},
+};
 
 #[derive(Debug)]
 pub struct ArgumentNode {
     pub name: String,
+    pub loc: Loc,
     pub r#type: Option<TypeNode>,
     pub default: Option<ExpressionNode>,
 }
@@ -10,8 +17,23 @@ pub struct ArgumentNode {
 #[derive(Debug)]
 pub struct FunctionNode {
     pub name: String,
+    pub generics: Vec<GenericParamNode>,
     pub args: Vec<ArgumentNode>,
     pub r#type: Option<TypeNode>,
     pub return_var: Option<String>,
     pub body: BodyNode,
+    pub loc: Loc,
+}
+
+impl ArgumentNode {
+    // a leading `self` without a type takes the value a method is called on
+    pub fn is_receiver(&self) -> bool {
+        self.name == "self" && self.r#type.is_none() && self.default.is_none()
+    }
+}
+
+impl FunctionNode {
+    pub fn takes_self(&self) -> bool {
+        self.args.first().is_some_and(ArgumentNode::is_receiver)
+    }
 }
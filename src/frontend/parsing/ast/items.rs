@@ -1,9 +1,17 @@
-use super::{expressions::ExpressionNode, functions::FunctionNode, types::TypeNode};
+use crate::frontend::Loc;
+
+use super::{
+    expressions::ExpressionNode,
+    functions::{ArgumentNode, FunctionNode},
+    types::{GenericParamNode, TypeNode},
+};
 
 #[derive(Debug)]
 pub struct ItemNode {
     pub kind: ItemKind,
     pub public: bool,
+    // the `///` comments right above the item, one line each
+    pub docs: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -11,11 +19,17 @@ pub enum ItemKind {
     Constant(ConstantNode),
     Static(StaticNode),
     Function(FunctionNode),
+    Struct(StructNode),
+    Enum(EnumNode),
+    Trait(TraitNode),
+    Impl(ImplNode),
+    Use(UseNode),
 }
 
 #[derive(Debug)]
 pub struct ConstantNode {
     pub name: String,
+    pub loc: Loc,
     pub r#type: TypeNode,
     pub value: ExpressionNode,
 }
@@ -23,7 +37,179 @@ pub struct ConstantNode {
 #[derive(Debug)]
 pub struct StaticNode {
     pub name: String,
+    pub loc: Loc,
     pub r#type: Option<TypeNode>,
     pub value: ExpressionNode,
     pub mutable: bool,
 }
+
+#[derive(Debug)]
+pub struct StructNode {
+    pub name: String,
+    pub generics: Vec<GenericParamNode>,
+    pub fields: Vec<FieldNode>,
+    pub loc: Loc,
+}
+
+#[derive(Debug)]
+pub struct FieldNode {
+    pub name: String,
+    pub r#type: TypeNode,
+    pub default: Option<ExpressionNode>,
+    pub loc: Loc,
+}
+
+#[derive(Debug)]
+pub struct EnumNode {
+    pub name: String,
+    pub variants: Vec<VariantNode>,
+    pub loc: Loc,
+}
+
+#[derive(Debug)]
+pub struct VariantNode {
+    pub name: String,
+    pub kind: VariantKind,
+    pub loc: Loc,
+}
+
+#[derive(Debug)]
+pub enum VariantKind {
+    Unit,
+    Tuple(Vec<TypeNode>),
+    Struct(Vec<FieldNode>),
+}
+
+#[derive(Debug)]
+pub struct TraitNode {
+    pub name: String,
+    pub methods: Vec<TraitMethodNode>,
+    pub loc: Loc,
+}
+
+#[derive(Debug)]
+pub enum TraitMethodNode {
+    Required(SignatureNode),
+    Provided(FunctionNode),
+}
+
+#[derive(Debug)]
+pub struct SignatureNode {
+    pub name: String,
+    pub args: Vec<ArgumentNode>,
+    pub r#type: Option<TypeNode>,
+    pub loc: Loc,
+}
+
+impl TraitMethodNode {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Required(node) => &node.name,
+            Self::Provided(node) => &node.name,
+        }
+    }
+
+    pub fn args(&self) -> &[ArgumentNode] {
+        match self {
+            Self::Required(node) => &node.args,
+            Self::Provided(node) => &node.args,
+        }
+    }
+
+    pub fn r#type(&self) -> Option<&TypeNode> {
+        match self {
+            Self::Required(node) => node.r#type.as_ref(),
+            Self::Provided(node) => node.r#type.as_ref(),
+        }
+    }
+
+    pub fn loc(&self) -> &Loc {
+        match self {
+            Self::Required(node) => &node.loc,
+            Self::Provided(node) => &node.loc,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ImplNode {
+    pub r#trait: Option<TypeNode>,
+    pub target: TypeNode,
+    pub methods: Vec<MethodNode>,
+    pub loc: Loc,
+}
+
+#[derive(Debug)]
+pub struct MethodNode {
+    pub function: FunctionNode,
+    pub public: bool,
+}
+
+#[derive(Debug)]
+pub struct UseNode {
+    pub tree: UseTree,
+    pub loc: Loc,
+}
+
+#[derive(Debug)]
+pub struct UseTree {
+    pub path: Vec<String>,
+    pub kind: UseTreeKind,
+    pub loc: Loc,
+}
+
+#[derive(Debug)]
+pub enum UseTreeKind {
+    Simple { alias: Option<String> },
+    Glob,
+    Group(Vec<UseTree>),
+}
+
+#[derive(Debug, Clone)]
+pub struct UseImport {
+    pub path: Vec<String>,
+    pub alias: Option<String>,
+    pub glob: bool,
+    pub loc: Loc,
+}
+
+impl UseImport {
+    pub fn name(&self) -> &str {
+        self.alias.as_ref().unwrap_or(self.path.last().unwrap())
+    }
+}
+
+impl UseNode {
+    pub fn imports(&self) -> Vec<UseImport> {
+        let mut imports = Vec::new();
+        self.tree.flatten(&[], &mut imports);
+
+        return imports;
+    }
+}
+
+impl UseTree {
+    fn flatten(&self, prefix: &[String], imports: &mut Vec<UseImport>) {
+        let path = [prefix, &self.path].concat();
+
+        match &self.kind {
+            UseTreeKind::Simple { alias } => imports.push(UseImport {
+                path,
+                alias: alias.clone(),
+                glob: false,
+                loc: self.loc.clone(),
+            }),
+            UseTreeKind::Glob => imports.push(UseImport {
+                path,
+                alias: None,
+                glob: true,
+                loc: self.loc.clone(),
+            }),
+            UseTreeKind::Group(trees) => {
+                for tree in trees {
+                    tree.flatten(&path, imports);
+                }
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@ pub mod expressions;
 pub mod functions;
 pub mod items;
 pub mod namespaces;
+pub mod patterns;
 pub mod projects;
 pub mod statements;
 pub mod types;
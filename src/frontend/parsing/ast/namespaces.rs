@@ -1,16 +1,69 @@
 use std::fmt::Debug;
 
-use super::items::ItemNode;
+use super::items::{ItemKind, ItemNode, UseImport};
 
 #[derive(Debug)]
 pub struct NamespaceNode {
     pub kind: NamespaceKind,
     pub items: Vec<ItemNode>,
+    pub children: Vec<NamespaceNode>,
+    pub source: usize,
 }
 
 impl NamespaceNode {
     pub fn new(kind: NamespaceKind, items: Vec<ItemNode>) -> Self {
-        Self { kind, items }
+        Self {
+            kind,
+            items,
+            children: Vec::new(),
+            source: 0,
+        }
+    }
+
+    pub fn imports(&self) -> Vec<UseImport> {
+        self.items
+            .iter()
+            .filter_map(|item| match &item.kind {
+                ItemKind::Use(node) => Some(node.imports()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    pub fn all_imports(&self) -> Vec<UseImport> {
+        let mut imports = self.imports();
+        for child in self.children.iter() {
+            imports.extend(child.all_imports());
+        }
+
+        return imports;
+    }
+
+    pub fn declares(&self, name: &str) -> bool {
+        let declared = matches!(&self.kind, NamespaceKind::Sub(path) if path.names()[0] == name);
+        declared || self.children.iter().any(|child| child.declares(name))
+    }
+
+    // moves the namespace and its children from the root of their file under
+    // the namespace the file is loaded as
+    pub fn nest_in(&mut self, parent: &NamespacePath, source: usize) {
+        self.kind = match &self.kind {
+            NamespaceKind::Root => NamespaceKind::Sub(parent.clone()),
+            NamespaceKind::Sub(path) => NamespaceKind::Sub(parent.join(path)),
+        };
+        self.source = source;
+
+        for child in self.children.iter_mut() {
+            child.nest_in(parent, source);
+        }
+    }
+
+    pub fn path(&self) -> String {
+        match &self.kind {
+            NamespaceKind::Root => String::new(),
+            NamespaceKind::Sub(path) => path.to_string(),
+        }
     }
 }
 
@@ -20,6 +73,7 @@ pub enum NamespaceKind {
     Sub(NamespacePath),
 }
 
+#[derive(Clone)]
 pub struct NamespacePath {
     names: Vec<String>,
 }
@@ -29,6 +83,14 @@ impl NamespacePath {
         Self { names }
     }
 
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn join(&self, other: &NamespacePath) -> NamespacePath {
+        NamespacePath::new([self.names.as_slice(), other.names()].concat())
+    }
+
     pub fn name(&self) -> String {
         self.names.last().unwrap().clone()
     }
@@ -0,0 +1,57 @@
+use crate::frontend::{lexing::LiteralKind, Loc};
+
+use super::expressions::ExpressionNode;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchArmNode<T> {
+    pub pattern: PatternNode,
+    pub guard: Option<ExpressionNode>,
+    pub body: T,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternNode {
+    pub kind: PatternKind,
+    pub loc: Loc,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatternKind {
+    Wildcard,
+
+    Literal {
+        kind: LiteralKind,
+        value: String,
+        negative: bool,
+    },
+
+    Binding {
+        name: String,
+    },
+
+    Variant {
+        path: Vec<String>,
+        payload: PatternPayload,
+    },
+
+    Tuple(Vec<PatternNode>),
+
+    Or(Vec<PatternNode>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatternPayload {
+    Unit,
+    Tuple(Vec<PatternNode>),
+    Struct {
+        fields: Vec<FieldPatternNode>,
+        rest: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldPatternNode {
+    pub name: String,
+    pub pattern: PatternNode,
+    pub loc: Loc,
+}
@@ -1,5 +1,11 @@
+use crate::frontend::Loc;
+
 use super::{
-    bodies::BodyNode, expressions::ExpressionNode, functions::FunctionNode, types::TypeNode,
+    bodies::BodyNode,
+    expressions::ExpressionNode,
+    functions::FunctionNode,
+    patterns::{MatchArmNode, PatternNode},
+    types::TypeNode,
 };
 
 #[derive(Debug)]
@@ -10,16 +16,21 @@ pub struct StatementNode {
 #[derive(Debug)]
 pub enum StatementKind {
     Expression(ExpressionNode),
-    VariableDeclaration(VariableDeclarationNode),
-    FunctionDeclaration(FunctionNode),
+    VariableDeclaration(Box<VariableDeclarationNode>),
+    FunctionDeclaration(Box<FunctionNode>),
     If(IfNode),
     WhileLoop(WhileLoopNode),
+    ForLoop(Box<ForLoopNode>),
+    Match(MatchNode),
     Return(ExpressionNode),
+    Break(LoopControlNode),
+    Continue(LoopControlNode),
 }
 
 #[derive(Debug)]
 pub struct VariableDeclarationNode {
     pub name: String,
+    pub loc: Loc,
     pub r#type: Option<TypeNode>,
     pub value: Option<ExpressionNode>,
     pub mutable: bool,
@@ -44,6 +55,7 @@ pub enum IfKind {
 pub struct WhileLoopNode {
     pub kind: WhileLoopKind,
     pub body: BodyNode,
+    pub label: Option<LabelNode>,
 }
 
 #[derive(Debug)]
@@ -54,3 +66,31 @@ pub enum WhileLoopKind {
     },
     Else,
 }
+
+#[derive(Debug)]
+pub struct ForLoopNode {
+    pub pattern: PatternNode,
+    pub iterable: ExpressionNode,
+    pub body: BodyNode,
+    pub label: Option<LabelNode>,
+}
+
+#[derive(Debug)]
+pub struct LabelNode {
+    pub name: String,
+    pub loc: Loc,
+}
+
+// `break` and `continue`, the location is the keyword's
+#[derive(Debug)]
+pub struct LoopControlNode {
+    pub label: Option<LabelNode>,
+    pub loc: Loc,
+}
+
+#[derive(Debug)]
+pub struct MatchNode {
+    pub value: ExpressionNode,
+    pub arms: Vec<MatchArmNode<BodyNode>>,
+    pub loc: Loc,
+}
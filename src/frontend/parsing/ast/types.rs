@@ -1,4 +1,97 @@
-#[derive(Debug, PartialEq)]
+use crate::frontend::Loc;
+
+use super::expressions::{ExpressionKind, ExpressionNode};
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct TypeNode {
-    pub raw: String,
+    pub kind: TypeKind,
+    pub loc: Loc,
+}
+
+impl TypeNode {
+    // plain single-segment names, e.g. `int` or the return variable in `-> out: int`
+    pub fn name(&self) -> Option<&str> {
+        match &self.kind {
+            TypeKind::Path { path, generics } if path.len() == 1 && generics.is_empty() => {
+                Some(&path[0])
+            }
+            _ => None,
+        }
+    }
+}
+
+// a type parameter of a generic item, `T: Ord + Show`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenericParamNode {
+    pub name: String,
+    pub bounds: Vec<TypeNode>,
+    pub loc: Loc,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeKind {
+    Path {
+        path: Vec<String>,
+        generics: Vec<TypeNode>,
+    },
+
+    Array {
+        element: Box<TypeNode>,
+        size: Box<ExpressionNode>,
+    },
+
+    Slice {
+        element: Box<TypeNode>,
+    },
+
+    Tuple {
+        elements: Vec<TypeNode>,
+    },
+
+    Function {
+        args: Vec<TypeNode>,
+        r#return: Option<Box<TypeNode>>,
+    },
+
+    Optional {
+        inner: Box<TypeNode>,
+    },
+}
+
+impl ToString for TypeNode {
+    fn to_string(&self) -> String {
+        let join = |types: &[TypeNode]| {
+            types
+                .iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match &self.kind {
+            TypeKind::Path { path, generics } if generics.is_empty() => path.join("::"),
+            TypeKind::Path { path, generics } => {
+                format!("{}<{}>", path.join("::"), join(generics))
+            }
+            TypeKind::Array { element, size } => {
+                let size = match &size.kind {
+                    ExpressionKind::Literal { value, .. } => value.clone(),
+                    ExpressionKind::Identifier { name } => name.clone(),
+                    _ => "..".to_string(),
+                };
+
+                format!("[{}; {}]", element.to_string(), size)
+            }
+            TypeKind::Slice { element } => format!("[{}]", element.to_string()),
+            TypeKind::Tuple { elements } if elements.len() == 1 => {
+                format!("({},)", elements[0].to_string())
+            }
+            TypeKind::Tuple { elements } => format!("({})", join(elements)),
+            TypeKind::Function { args, r#return } => match r#return {
+                Some(ret) => format!("func({}) -> {}", join(args), ret.to_string()),
+                None => format!("func({})", join(args)),
+            },
+            TypeKind::Optional { inner } => format!("{}?", inner.to_string()),
+        }
+    }
 }
@@ -36,8 +36,11 @@ impl Parser<'_> {
                 break;
             }
 
-            let el = self.parse_body_element()?;
-            els.push(el);
+            let start = self.cursor;
+            match self.parse_body_element() {
+                Ok(el) => els.push(el),
+                Err(err) => self.recover(err, start),
+            }
         }
 
         self.expect(SimplePunctuation(BraceClose))?;
@@ -47,7 +50,7 @@ impl Parser<'_> {
         })
     }
 
-    fn parse_inline_body(&mut self) -> Result<BodyNode, SyntaxError> {
+    pub(super) fn parse_inline_body(&mut self) -> Result<BodyNode, SyntaxError> {
         self.expect(ComplexPunctuation(InlineBody))?;
 
         self.advance();
@@ -4,11 +4,15 @@ use crate::{
         syntax::{SyntaxError, SyntaxErrorKind, SyntaxErrorSource},
     },
     frontend::{
-        lexing::{ComplexPunctuationKind::*, SimplePunctuationKind::*, TokenKind::*},
+        lexing::{
+            AssignmentKind, ComplexPunctuationKind, ComplexPunctuationKind::*, KeywordKind::*,
+            LiteralKind, SimplePunctuationKind::*, Token, TokenKind, TokenKind::*,
+        },
         parsing::ast::expressions::{
-            BinaryExpressionKind, ExpressionKind, ExpressionNode, ResolutionExpressionKind,
-            UnaryExpressionKind,
+            BinaryExpressionKind, ExpressionKind, ExpressionNode, FieldValueNode,
+            InterpolationPart, ResolutionExpressionKind, UnaryExpressionKind,
         },
+        Loc,
     },
 };
 
@@ -30,33 +34,79 @@ impl Parser<'_> {
         self.parse_assignment_expression()
     }
 
+    pub(super) fn parse_condition(&mut self) -> Result<ExpressionNode, SyntaxError> {
+        let outer = std::mem::replace(&mut self.no_struct_literals, true);
+        let cond = self.parse_expression();
+        self.no_struct_literals = outer;
+
+        return cond;
+    }
+
+    // expressions inside delimiters can't be confused with a body again
+    fn parse_delimited_expression(&mut self) -> Result<ExpressionNode, SyntaxError> {
+        let outer = std::mem::replace(&mut self.no_struct_literals, false);
+        let expr = self.parse_expression();
+        self.no_struct_literals = outer;
+
+        return expr;
+    }
+
     fn parse_assignment_expression(&mut self) -> Result<ExpressionNode, SyntaxError> {
-        let mut left = self.parse_binary_expression(BinaryExpressionKind::lowest())?;
+        let mut left = self.parse_range_expression()?;
 
-        if let ComplexPunctuation(Assignment(kind)) = self.current().kind {
-            self.advance();
+        if let Some(Assignment(kind)) = self.current_operator() {
+            self.advance_operator(&Assignment(kind.clone()));
             let right = self.parse_assignment_expression()?;
 
+            let loc = Loc::between(&left.loc, &right.loc);
             left = ExpressionNode {
                 kind: ExpressionKind::Assignment {
                     left: Box::new(left),
                     op: kind,
                     right: Box::new(right),
                 },
+                loc,
             };
         }
 
         return Ok(left);
     }
 
+    // ranges bind looser than any binary operator, so `0..n + 1` ends at `n + 1`
+    fn parse_range_expression(&mut self) -> Result<ExpressionNode, SyntaxError> {
+        let start = self.parse_binary_expression(BinaryExpressionKind::lowest())?;
+
+        let inclusive = match self.current().kind {
+            ComplexPunctuation(Range) => false,
+            ComplexPunctuation(RangeInclusive) => true,
+            _ => return Ok(start),
+        };
+
+        self.advance();
+        let end = self.parse_binary_expression(BinaryExpressionKind::lowest())?;
+
+        let loc = Loc::between(&start.loc, &end.loc);
+        return Ok(ExpressionNode {
+            kind: ExpressionKind::Range {
+                start: Box::new(start),
+                end: Box::new(end),
+                inclusive,
+            },
+            loc,
+        });
+    }
+
     fn parse_binary_expression(
         &mut self,
         bin_kind: BinaryExpressionKind,
     ) -> Result<ExpressionNode, SyntaxError> {
         let mut expr = self.parse_binary_expression_operand(&bin_kind)?;
 
-        while let ComplexPunctuation(punct_kind) = self.current().kind {
-            if matches!(punct_kind, Assignment(_) | InlineBody) {
+        while let Some(punct_kind) = self.current_operator() {
+            if matches!(
+                punct_kind,
+                Assignment(_) | InlineBody | Range | RangeInclusive
+            ) {
                 break;
             }
 
@@ -65,7 +115,7 @@ impl Parser<'_> {
             let Ok(punct_bin_kind) = BinaryExpressionKind::from_punct(&punct_kind) else {
                 return Err(SyntaxError::default()
                     .ctx(
-                        ErrorContextBuilder::span(token.loc.start_col, token.loc.end_col)
+                        ErrorContextBuilder::loc(&token.loc)
                             .from_src_and_ln(&self.src, token.loc.ln)
                             .build(),
                     )
@@ -77,9 +127,10 @@ impl Parser<'_> {
                 break;
             }
 
-            self.advance();
+            self.advance_operator(&punct_kind);
 
             let right = self.parse_binary_expression_operand(&bin_kind)?;
+            let loc = Loc::between(&expr.loc, &right.loc);
             expr = ExpressionNode {
                 kind: ExpressionKind::Binary {
                     kind: punct_bin_kind.clone(),
@@ -87,6 +138,7 @@ impl Parser<'_> {
                     op: punct_kind,
                     right: Box::new(right),
                 },
+                loc,
             }
         }
 
@@ -102,99 +154,253 @@ impl Parser<'_> {
         {
             self.parse_binary_expression(more_precedence)?
         } else {
-            self.parse_call_or_resolution_expression()?
+            self.parse_cast_expression()?
         };
 
         return Ok(expr);
     }
 
-    fn parse_call_or_resolution_expression(&mut self) -> Result<ExpressionNode, SyntaxError> {
-        let res = self.parse_resolution_expression()?;
+    // casts bind tighter than any binary operator, `a + b as int64` only
+    // casts `b`, and chain from left to right
+    fn parse_cast_expression(&mut self) -> Result<ExpressionNode, SyntaxError> {
+        let mut expr = self.parse_call_or_resolution_expression()?;
+
+        while self.current().kind == Keyword(As) {
+            self.advance();
+            let r#type = self.parse_type()?;
+            self.advance();
+
+            let loc = Loc::between(&expr.loc, &r#type.loc);
+            expr = ExpressionNode {
+                kind: ExpressionKind::Cast {
+                    value: Box::new(expr),
+                    r#type,
+                },
+                loc,
+            };
+        }
+
+        return Ok(expr);
+    }
 
-        if self.current().kind == SimplePunctuation(ParenOpen) {
-            return Ok(self.parse_call_expression(res)?);
+    fn parse_call_or_resolution_expression(&mut self) -> Result<ExpressionNode, SyntaxError> {
+        let res = self.parse_postfix_expression()?;
+
+        let is_path = matches!(
+            res.kind,
+            ExpressionKind::Identifier { .. }
+                | ExpressionKind::Resolution {
+                    kind: ResolutionExpressionKind::Namespace,
+                    ..
+                }
+        );
+
+        if is_path
+            && !self.no_struct_literals
+            && self.current().kind == SimplePunctuation(BraceOpen)
+        {
+            return Ok(self.parse_struct_expression(res)?);
         }
 
         return Ok(res);
     }
 
-    fn parse_call_expression(
+    fn parse_struct_expression(
         &mut self,
         res: ExpressionNode,
     ) -> Result<ExpressionNode, SyntaxError> {
-        let args = self.parse_call_arguments()?;
+        self.expect(SimplePunctuation(BraceOpen))?;
+        let mut fields = Vec::new();
 
-        let mut expr = ExpressionNode {
-            kind: ExpressionKind::Call {
-                res: Box::new(res),
-                args,
-            },
-        };
+        loop {
+            self.advance();
+            if matches!(self.current().kind, SimplePunctuation(BraceClose) | EOF) {
+                break;
+            }
 
-        self.advance();
-        if self.current().kind == SimplePunctuation(ParenOpen) {
-            expr = self.parse_call_expression(expr)?;
+            let name_token = self.expect(Identifier)?;
+            let name = name_token.value.unwrap();
+
+            self.advance();
+            let value = if self.current().kind == ComplexPunctuation(Colon) {
+                self.advance();
+                self.parse_delimited_expression()?
+            } else {
+                ExpressionNode {
+                    kind: ExpressionKind::Identifier { name: name.clone() },
+                    loc: name_token.loc.clone(),
+                }
+            };
+
+            fields.push(FieldValueNode {
+                name,
+                loc: Loc::between(&name_token.loc, &value.loc),
+                value,
+            });
+
+            if self.current().kind != SimplePunctuation(Comma) {
+                break;
+            }
         }
 
-        return Ok(expr);
+        self.expect(SimplePunctuation(BraceClose))?;
+
+        let loc = Loc::between(&res.loc, &self.current().loc);
+        self.advance();
+
+        return Ok(ExpressionNode {
+            kind: ExpressionKind::Struct {
+                res: Box::new(res),
+                fields,
+            },
+            loc,
+        });
     }
 
-    fn parse_call_arguments(&mut self) -> Result<Vec<ExpressionNode>, SyntaxError> {
-        self.expect(SimplePunctuation(ParenOpen))?;
-        let mut args = Vec::new();
+    // starts on the opening token and stops on `close`, trailing commas are allowed
+    fn parse_expression_list(
+        &mut self,
+        close: TokenKind,
+    ) -> Result<Vec<ExpressionNode>, SyntaxError> {
+        let mut exprs = Vec::new();
 
         loop {
             self.advance();
-            if matches!(self.current().kind, SimplePunctuation(ParenClose) | EOF) {
+            if self.current().kind == close || self.current().kind == EOF {
                 break;
             }
 
-            let expr = self.parse_expression()?;
-            args.push(expr);
+            let expr = self.parse_delimited_expression()?;
+            exprs.push(expr);
 
             if self.current().kind != SimplePunctuation(Comma) {
                 break;
             }
         }
 
-        self.expect(SimplePunctuation(ParenClose))?;
-        return Ok(args);
+        self.expect(close)?;
+        return Ok(exprs);
     }
 
-    fn parse_resolution_expression(&mut self) -> Result<ExpressionNode, SyntaxError> {
+    // resolutions and calls chain in any order, `a.b(c).d` included. stops on
+    // the token after the expression
+    fn parse_postfix_expression(&mut self) -> Result<ExpressionNode, SyntaxError> {
         let mut left = self.parse_primary_expression()?;
 
         loop {
             self.advance();
 
-            if !matches!(
-                self.current().kind,
-                ComplexPunctuation(MemberSeparator) | ComplexPunctuation(NamespaceSeparator)
-            ) {
-                break;
-            }
-
             let kind = match self.current().kind {
                 ComplexPunctuation(MemberSeparator) => ResolutionExpressionKind::Member,
                 ComplexPunctuation(NamespaceSeparator) => ResolutionExpressionKind::Namespace,
-                _ => unreachable!(),
+                SimplePunctuation(ParenOpen) => {
+                    left = self.parse_call_expression(left)?;
+                    continue;
+                }
+                SimplePunctuation(BracketOpen) => {
+                    left = self.parse_index_expression(left)?;
+                    continue;
+                }
+                _ => break,
             };
 
             self.advance();
             let right = self.parse_primary_expression()?;
 
+            let loc = Loc::between(&left.loc, &right.loc);
             left = ExpressionNode {
                 kind: ExpressionKind::Resolution {
                     left: Box::new(left),
                     right: Box::new(right),
                     kind,
                 },
+                loc,
             };
         }
 
         return Ok(left);
     }
 
+    // calling a member is a method call on the value left of the dot
+    fn parse_call_expression(
+        &mut self,
+        res: ExpressionNode,
+    ) -> Result<ExpressionNode, SyntaxError> {
+        let args = self.parse_expression_list(SimplePunctuation(ParenClose))?;
+        let loc = Loc::between(&res.loc, &self.current().loc);
+
+        let kind = match res.kind {
+            ExpressionKind::Resolution {
+                left,
+                right,
+                kind: ResolutionExpressionKind::Member,
+            } if matches!(right.kind, ExpressionKind::Identifier { .. }) => {
+                let ExpressionKind::Identifier { name } = right.kind else {
+                    unreachable!()
+                };
+
+                ExpressionKind::MethodCall {
+                    object: left,
+                    method: name,
+                    args,
+                }
+            }
+            kind => ExpressionKind::Call {
+                res: Box::new(ExpressionNode { kind, loc: res.loc }),
+                args,
+            },
+        };
+
+        return Ok(ExpressionNode { kind, loc });
+    }
+
+    fn parse_index_expression(
+        &mut self,
+        object: ExpressionNode,
+    ) -> Result<ExpressionNode, SyntaxError> {
+        self.advance();
+        let index = self.parse_delimited_expression()?;
+        self.expect(SimplePunctuation(BracketClose))?;
+
+        let loc = Loc::between(&object.loc, &self.current().loc);
+        return Ok(ExpressionNode {
+            kind: ExpressionKind::Index {
+                object: Box::new(object),
+                index: Box::new(index),
+            },
+            loc,
+        });
+    }
+
+    fn parse_array_expression(&mut self) -> Result<ExpressionNode, SyntaxError> {
+        let start = self.current().loc;
+        let elements = self.parse_expression_list(SimplePunctuation(BracketClose))?;
+
+        return Ok(ExpressionNode {
+            kind: ExpressionKind::Array { elements },
+            loc: Loc::between(&start, &self.current().loc),
+        });
+    }
+
+    // `(a)` only groups, a tuple needs a comma: `(a, b)` or `(a,)`
+    fn parse_parenthesized_expression(&mut self) -> Result<ExpressionNode, SyntaxError> {
+        let start = self.current().loc;
+        let mut elements = self.parse_expression_list(SimplePunctuation(ParenClose))?;
+
+        let trailing_comma = self
+            .previous()
+            .is_ok_and(|token| token.kind == SimplePunctuation(Comma));
+
+        if elements.len() == 1 && !trailing_comma {
+            return Ok(elements.remove(0));
+        }
+
+        return Ok(ExpressionNode {
+            kind: ExpressionKind::Tuple { elements },
+            loc: Loc::between(&start, &self.current().loc),
+        });
+    }
+
     fn parse_primary_expression(&mut self) -> Result<ExpressionNode, SyntaxError> {
         let token = self.current();
 
@@ -203,19 +409,22 @@ impl Parser<'_> {
                 kind: ExpressionKind::Identifier {
                     name: token.value.unwrap(),
                 },
+                loc: token.loc,
             }),
             Literal(lit) => Ok(ExpressionNode {
                 kind: ExpressionKind::Literal {
                     kind: lit,
                     value: token.value.unwrap(),
+                    suffix: token.suffix,
                 },
+                loc: token.loc,
             }),
 
             ComplexPunctuation(punct_kind) => {
                 let Ok(un_kind) = UnaryExpressionKind::from_punct(&punct_kind) else {
                     return Err(SyntaxError::default()
                         .ctx(
-                            ErrorContextBuilder::span(token.loc.start_col, token.loc.end_col)
+                            ErrorContextBuilder::loc(&token.loc)
                                 .from_src_and_ln(&self.src, token.loc.ln)
                                 .build(),
                         )
@@ -226,25 +435,24 @@ impl Parser<'_> {
                 self.advance();
                 let right = self.parse_primary_expression()?;
 
+                let loc = Loc::between(&token.loc, &right.loc);
                 Ok(ExpressionNode {
                     kind: ExpressionKind::Unary {
                         kind: un_kind,
                         op: punct_kind,
                         right: Box::new(right),
                     },
+                    loc,
                 })
             }
-            SimplePunctuation(ParenOpen) => {
-                self.advance();
-                let expr = self.parse_expression()?;
-                self.expect(SimplePunctuation(ParenClose))?;
-
-                Ok(expr)
-            }
+            InterpolatedStringStart => self.parse_interpolation(token),
+            Keyword(Match) => self.parse_match_expression(),
+            SimplePunctuation(ParenOpen) => self.parse_parenthesized_expression(),
+            SimplePunctuation(BracketOpen) => self.parse_array_expression(),
 
             _ => Err(SyntaxError::default()
                 .ctx(
-                    ErrorContextBuilder::span(token.loc.start_col, token.loc.end_col)
+                    ErrorContextBuilder::loc(&token.loc)
                         .from_src_and_ln(&self.src, token.loc.ln)
                         .build(),
                 )
@@ -252,4 +460,107 @@ impl Parser<'_> {
                 .msg("specified token cannot be used for expressions")),
         }
     }
+
+    // `>>` and `>>=` are lexed as separate angles so that nested generics can be
+    // closed, they're joined back here when the angles touch
+    fn current_operator(&self) -> Option<ComplexPunctuationKind> {
+        let token = self.current();
+        let ComplexPunctuation(kind) = token.kind else {
+            return None;
+        };
+
+        let next = self.peek();
+        if kind != AngleClose
+            || next.loc.ln != token.loc.ln
+            || next.loc.start_col != token.loc.end_col
+        {
+            return Some(kind);
+        }
+
+        match next.kind {
+            ComplexPunctuation(AngleClose) => Some(OperatorShiftRight),
+            ComplexPunctuation(OperatorGreaterEqual) => {
+                Some(Assignment(AssignmentKind::ShiftRightEquals))
+            }
+            _ => Some(kind),
+        }
+    }
+
+    fn advance_operator(&mut self, op: &ComplexPunctuationKind) {
+        self.advance();
+
+        if matches!(
+            op,
+            OperatorShiftRight | Assignment(AssignmentKind::ShiftRightEquals)
+        ) {
+            self.advance();
+        }
+    }
+
+    fn parse_match_expression(&mut self) -> Result<ExpressionNode, SyntaxError> {
+        let start = self.current().loc;
+
+        let (value, arms) = self.parse_match(|parser| {
+            parser.advance();
+            let expr = parser.parse_delimited_expression()?;
+
+            // the closing brace is left for the arm loop
+            let token = parser.expect_multiple(vec![
+                SimplePunctuation(Comma),
+                SimplePunctuation(BraceClose),
+            ])?;
+            if token.kind == SimplePunctuation(BraceClose) {
+                parser.cursor -= 1;
+            }
+
+            Ok(expr)
+        })?;
+
+        Ok(ExpressionNode {
+            kind: ExpressionKind::Match {
+                value: Box::new(value),
+                arms,
+            },
+            loc: Loc::between(&start, &self.current().loc),
+        })
+    }
+
+    fn parse_interpolation(&mut self, start: Token) -> Result<ExpressionNode, SyntaxError> {
+        let mut parts = Vec::new();
+
+        loop {
+            self.advance();
+            let token = self.current();
+
+            match token.kind {
+                Literal(LiteralKind::String) => {
+                    parts.push(InterpolationPart::Literal(token.value.unwrap()));
+                }
+                SimplePunctuation(BraceOpen) => {
+                    self.advance();
+                    let expr = self.parse_expression()?;
+                    self.expect(SimplePunctuation(BraceClose))?;
+
+                    parts.push(InterpolationPart::Expression(expr));
+                }
+                InterpolatedStringEnd => break,
+                _ => {
+                    return Err(SyntaxError::default()
+                        .ctx(
+                            ErrorContextBuilder::loc(&token.loc)
+                                .from_src_and_ln(&self.src, token.loc.ln)
+                                .build(),
+                        )
+                        .kind(SyntaxErrorKind::Unexpected(SyntaxErrorSource::Token))
+                        .msg("expected end of interpolated string"))
+                }
+            }
+        }
+
+        let loc = Loc::between(&start.loc, &self.current().loc);
+        Ok(ExpressionNode {
+            kind: ExpressionKind::Interpolation { parts },
+            loc,
+        })
+    }
 }
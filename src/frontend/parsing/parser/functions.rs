@@ -8,19 +8,61 @@ use crate::{
             AssignmentKind::*, ComplexPunctuationKind::*, KeywordKind::*, SimplePunctuationKind::*,
             TokenKind::*,
         },
-        parsing::ast::functions::{ArgumentNode, FunctionNode},
+        parsing::ast::{
+            bodies::BodyNode,
+            functions::{ArgumentNode, FunctionNode},
+            types::{GenericParamNode, TypeNode},
+        },
+        Loc,
     },
 };
 
 use super::Parser;
 
+pub(super) struct FunctionHead {
+    pub name: String,
+    pub generics: Vec<GenericParamNode>,
+    pub args: Vec<ArgumentNode>,
+    pub r#type: Option<TypeNode>,
+    pub return_var: Option<String>,
+    pub loc: Loc,
+}
+
+impl FunctionHead {
+    pub fn with_body(self, body: BodyNode, end: &Loc) -> FunctionNode {
+        FunctionNode {
+            name: self.name,
+            generics: self.generics,
+            args: self.args,
+            r#type: self.r#type,
+            return_var: self.return_var,
+            body,
+            loc: Loc::between(&self.loc, end),
+        }
+    }
+}
+
 impl Parser<'_> {
     pub(super) fn parse_function(&mut self) -> Result<FunctionNode, SyntaxError> {
-        self.expect(Keyword(Func))?;
+        let head = self.parse_function_head()?;
+        let body = self.parse_body()?;
+
+        return Ok(head.with_body(body, &self.current().loc));
+    }
+
+    // starts on `func` and stops on the token after the signature
+    pub(super) fn parse_function_head(&mut self) -> Result<FunctionHead, SyntaxError> {
+        let start = self.expect(Keyword(Func))?;
 
         self.advance();
         let name_token = self.expect(Identifier)?;
 
+        let mut generics = Vec::new();
+        if self.peek().kind == ComplexPunctuation(AngleOpen) {
+            self.advance();
+            generics = self.parse_generic_params()?;
+        }
+
         self.advance();
         let args = self.parse_function_arguments()?;
 
@@ -34,7 +76,18 @@ impl Parser<'_> {
 
             self.advance();
             if self.current().kind == ComplexPunctuation(Colon) {
-                return_var = Some(r#type.unwrap().raw);
+                let var = r#type.unwrap();
+                let Some(name) = var.name() else {
+                    return Err(SyntaxError::default()
+                        .ctx(
+                            ErrorContextBuilder::loc(&var.loc)
+                                .from_src_and_ln(&self.src, var.loc.ln)
+                                .build(),
+                        )
+                        .kind(SyntaxErrorKind::Expected(SyntaxErrorSource::Token))
+                        .msg("expected a name for the return variable"));
+                };
+                return_var = Some(name.to_string());
 
                 self.advance();
                 r#type = Some(self.parse_type()?);
@@ -43,14 +96,13 @@ impl Parser<'_> {
             }
         }
 
-        let body = self.parse_body()?;
-
-        return Ok(FunctionNode {
+        return Ok(FunctionHead {
             name: name_token.value.unwrap(),
+            generics,
             args,
             r#type,
             return_var,
-            body,
+            loc: start.loc,
         });
     }
 
@@ -83,10 +135,12 @@ impl Parser<'_> {
                 value = Some(self.parse_expression()?);
             }
 
-            if r#type == None && value == None {
+            let receiver = args.is_empty() && name_token.value.as_deref() == Some("self");
+
+            if r#type == None && value == None && !receiver {
                 return Err(SyntaxError::default()
                     .ctx(
-                        ErrorContextBuilder::span(name_token.loc.start_col, name_token.loc.end_col)
+                        ErrorContextBuilder::loc(&name_token.loc)
                             .from_src_and_ln(&self.src, name_token.loc.ln)
                             .build(),
                     )
@@ -96,6 +150,7 @@ impl Parser<'_> {
 
             args.push(ArgumentNode {
                 name: name_token.value.unwrap(),
+                loc: name_token.loc,
                 r#type,
                 default: value,
             });
@@ -8,7 +8,12 @@ use crate::{
             AssignmentKind::*, ComplexPunctuationKind::*, KeywordKind::*, SimplePunctuationKind::*,
             TokenKind::*,
         },
-        parsing::ast::items::{ConstantNode, ItemKind, ItemNode, StaticNode},
+        parsing::ast::items::{
+            ConstantNode, EnumNode, FieldNode, ImplNode, ItemKind, ItemNode, MethodNode,
+            SignatureNode, StaticNode, StructNode, TraitMethodNode, TraitNode, UseNode, UseTree,
+            UseTreeKind, VariantKind, VariantNode,
+        },
+        Loc,
     },
 };
 
@@ -16,6 +21,8 @@ use super::Parser;
 
 impl Parser<'_> {
     pub(super) fn parse_item(&mut self) -> Result<ItemNode, SyntaxError> {
+        let docs = self.docs_before(self.current().loc.ln);
+
         let mut public = false;
         if self.current().kind == Keyword(Pub) {
             public = true;
@@ -27,10 +34,25 @@ impl Parser<'_> {
             Keyword(Const) => ItemKind::Constant(self.parse_const()?),
             Keyword(Static) => ItemKind::Static(self.parse_static()?),
             Keyword(Func) => ItemKind::Function(self.parse_function()?),
+            Keyword(Struct) => ItemKind::Struct(self.parse_struct()?),
+            Keyword(Enum) => ItemKind::Enum(self.parse_enum()?),
+            Keyword(Trait) => ItemKind::Trait(self.parse_trait()?),
+            Keyword(Impl) if public => {
+                return Err(SyntaxError::default()
+                    .ctx(
+                        ErrorContextBuilder::loc(&token.loc)
+                            .from_src_and_ln(&self.src, token.loc.ln)
+                            .build(),
+                    )
+                    .kind(SyntaxErrorKind::Unexpected(SyntaxErrorSource::Token))
+                    .msg("impl blocks can't be public, mark their methods `pub` instead"));
+            }
+            Keyword(Impl) => ItemKind::Impl(self.parse_impl()?),
+            Keyword(Use) => ItemKind::Use(self.parse_use()?),
             _ => {
                 return Err(SyntaxError::default()
                     .ctx(
-                        ErrorContextBuilder::span(token.loc.start_col, token.loc.end_col)
+                        ErrorContextBuilder::loc(&token.loc)
                             .from_src_and_ln(&self.src, token.loc.ln)
                             .build(),
                     )
@@ -38,7 +60,27 @@ impl Parser<'_> {
             }
         };
 
-        Ok(ItemNode { kind, public })
+        Ok(ItemNode { kind, public, docs })
+    }
+
+    // doc comments belong to the item on the line right after them, so a
+    // blank line or anything else in between detaches them
+    fn docs_before(&self, ln: usize) -> Vec<String> {
+        let end = self.docs.partition_point(|doc| doc.loc.ln < ln);
+
+        let mut docs = Vec::new();
+        let mut next = ln;
+        for doc in self.docs[..end].iter().rev() {
+            if doc.loc.ln + 1 != next {
+                break;
+            }
+
+            docs.push(doc.value.clone().unwrap_or_default());
+            next = doc.loc.ln;
+        }
+
+        docs.reverse();
+        return docs;
     }
 
     fn parse_const(&mut self) -> Result<ConstantNode, SyntaxError> {
@@ -63,11 +105,273 @@ impl Parser<'_> {
 
         return Ok(ConstantNode {
             name: name_token.value.unwrap(),
+            loc: name_token.loc,
             r#type,
             value: expression,
         });
     }
 
+    fn parse_struct(&mut self) -> Result<StructNode, SyntaxError> {
+        let start = self.expect(Keyword(Struct))?;
+
+        self.advance();
+        let name_token = self.expect(Identifier)?;
+
+        let mut generics = Vec::new();
+        if self.peek().kind == ComplexPunctuation(AngleOpen) {
+            self.advance();
+            generics = self.parse_generic_params()?;
+        }
+
+        self.advance();
+        self.expect(SimplePunctuation(BraceOpen))?;
+
+        let mut fields = Vec::new();
+
+        loop {
+            self.advance();
+            if matches!(self.current().kind, SimplePunctuation(BraceClose) | EOF) {
+                break;
+            }
+
+            fields.push(self.parse_field()?);
+
+            if self.current().kind != SimplePunctuation(Comma) {
+                break;
+            }
+        }
+
+        self.expect(SimplePunctuation(BraceClose))?;
+
+        return Ok(StructNode {
+            name: name_token.value.unwrap(),
+            generics,
+            fields,
+            loc: Loc::between(&start.loc, &self.current().loc),
+        });
+    }
+
+    fn parse_enum(&mut self) -> Result<EnumNode, SyntaxError> {
+        let start = self.expect(Keyword(Enum))?;
+
+        self.advance();
+        let name_token = self.expect(Identifier)?;
+
+        self.advance();
+        self.expect(SimplePunctuation(BraceOpen))?;
+
+        let mut variants = Vec::new();
+
+        loop {
+            self.advance();
+            if matches!(self.current().kind, SimplePunctuation(BraceClose) | EOF) {
+                break;
+            }
+
+            variants.push(self.parse_variant()?);
+
+            self.advance();
+            if self.current().kind != SimplePunctuation(Comma) {
+                break;
+            }
+        }
+
+        self.expect(SimplePunctuation(BraceClose))?;
+
+        return Ok(EnumNode {
+            name: name_token.value.unwrap(),
+            variants,
+            loc: Loc::between(&start.loc, &self.current().loc),
+        });
+    }
+
+    fn parse_variant(&mut self) -> Result<VariantNode, SyntaxError> {
+        let name_token = self.expect(Identifier)?;
+
+        let kind = match self.peek().kind {
+            SimplePunctuation(ParenOpen) => {
+                self.advance();
+                let mut types = Vec::new();
+
+                loop {
+                    self.advance();
+                    if matches!(self.current().kind, SimplePunctuation(ParenClose) | EOF) {
+                        break;
+                    }
+
+                    types.push(self.parse_type()?);
+
+                    self.advance();
+                    if self.current().kind != SimplePunctuation(Comma) {
+                        break;
+                    }
+                }
+
+                self.expect(SimplePunctuation(ParenClose))?;
+                VariantKind::Tuple(types)
+            }
+            SimplePunctuation(BraceOpen) => {
+                self.advance();
+                let mut fields = Vec::new();
+
+                loop {
+                    self.advance();
+                    if matches!(self.current().kind, SimplePunctuation(BraceClose) | EOF) {
+                        break;
+                    }
+
+                    fields.push(self.parse_field()?);
+
+                    if self.current().kind != SimplePunctuation(Comma) {
+                        break;
+                    }
+                }
+
+                self.expect(SimplePunctuation(BraceClose))?;
+                VariantKind::Struct(fields)
+            }
+            _ => VariantKind::Unit,
+        };
+
+        return Ok(VariantNode {
+            name: name_token.value.unwrap(),
+            kind,
+            loc: Loc::between(&name_token.loc, &self.current().loc),
+        });
+    }
+
+    fn parse_trait(&mut self) -> Result<TraitNode, SyntaxError> {
+        let start = self.expect(Keyword(Trait))?;
+
+        self.advance();
+        let name_token = self.expect(Identifier)?;
+
+        self.advance();
+        self.expect(SimplePunctuation(BraceOpen))?;
+
+        let mut methods = Vec::new();
+
+        loop {
+            self.advance();
+            if matches!(self.current().kind, SimplePunctuation(BraceClose) | EOF) {
+                break;
+            }
+
+            let head = self.parse_function_head()?;
+            if let Some(param) = head.generics.first() {
+                return Err(SyntaxError::default()
+                    .ctx(
+                        ErrorContextBuilder::loc(&param.loc)
+                            .from_src_and_ln(&self.src, param.loc.ln)
+                            .build(),
+                    )
+                    .kind(SyntaxErrorKind::Invalid(SyntaxErrorSource::Token))
+                    .msg("trait methods can't have type parameters"));
+            }
+
+            // methods without a body have to be implemented by every impl
+            if self.current().kind == SimplePunctuation(Semicolon) {
+                methods.push(TraitMethodNode::Required(SignatureNode {
+                    name: head.name,
+                    args: head.args,
+                    r#type: head.r#type,
+                    loc: Loc::between(&head.loc, &self.current().loc),
+                }));
+                continue;
+            }
+
+            let body = self.parse_body()?;
+            methods.push(TraitMethodNode::Provided(
+                head.with_body(body, &self.current().loc),
+            ));
+        }
+
+        self.expect(SimplePunctuation(BraceClose))?;
+
+        return Ok(TraitNode {
+            name: name_token.value.unwrap(),
+            methods,
+            loc: Loc::between(&start.loc, &self.current().loc),
+        });
+    }
+
+    fn parse_impl(&mut self) -> Result<ImplNode, SyntaxError> {
+        let start = self.expect(Keyword(Impl))?;
+
+        self.advance();
+        let mut target = self.parse_type()?;
+        let mut r#trait = None;
+
+        self.advance();
+        if self.current().kind == Keyword(For) {
+            self.advance();
+            r#trait = Some(std::mem::replace(&mut target, self.parse_type()?));
+
+            self.advance();
+        }
+
+        self.expect(SimplePunctuation(BraceOpen))?;
+
+        let mut methods = Vec::new();
+
+        loop {
+            self.advance();
+            if matches!(self.current().kind, SimplePunctuation(BraceClose) | EOF) {
+                break;
+            }
+
+            let mut public = false;
+            if self.current().kind == Keyword(Pub) {
+                public = true;
+                self.advance();
+            }
+
+            methods.push(MethodNode {
+                function: self.parse_function()?,
+                public,
+            });
+        }
+
+        self.expect(SimplePunctuation(BraceClose))?;
+
+        return Ok(ImplNode {
+            r#trait,
+            target,
+            methods,
+            loc: Loc::between(&start.loc, &self.current().loc),
+        });
+    }
+
+    fn parse_field(&mut self) -> Result<FieldNode, SyntaxError> {
+        let name_token = self.expect(Identifier)?;
+
+        self.advance();
+        self.expect(ComplexPunctuation(Colon))?;
+
+        self.advance();
+        let r#type = self.parse_type()?;
+
+        self.advance();
+        let mut default = None;
+
+        if self.current().kind == ComplexPunctuation(Assignment(Straight)) {
+            self.advance();
+            default = Some(self.parse_expression()?);
+        }
+
+        let end = match &default {
+            Some(default) => &default.loc,
+            None => &r#type.loc,
+        };
+
+        return Ok(FieldNode {
+            name: name_token.value.unwrap(),
+            loc: Loc::between(&name_token.loc, end),
+            r#type,
+            default,
+        });
+    }
+
     fn parse_static(&mut self) -> Result<StaticNode, SyntaxError> {
         self.expect(Keyword(Static))?;
         self.advance();
@@ -99,9 +403,99 @@ impl Parser<'_> {
 
         return Ok(StaticNode {
             name: name_token.value.unwrap(),
+            loc: name_token.loc,
             r#type,
             value: expression,
             mutable,
         });
     }
+
+    fn parse_use(&mut self) -> Result<UseNode, SyntaxError> {
+        let start = self.expect(Keyword(Use))?;
+
+        self.advance();
+        let tree = self.parse_use_tree()?;
+
+        // a group already ends with a brace, so the semicolon is optional
+        let grouped = matches!(tree.kind, UseTreeKind::Group(_));
+        if !grouped || self.peek().kind == SimplePunctuation(Semicolon) {
+            self.advance();
+            self.expect(SimplePunctuation(Semicolon))?;
+        }
+
+        return Ok(UseNode {
+            tree,
+            loc: Loc::between(&start.loc, &self.current().loc),
+        });
+    }
+
+    fn parse_use_tree(&mut self) -> Result<UseTree, SyntaxError> {
+        let start = self.current();
+        let mut path = Vec::new();
+
+        loop {
+            let token = self.current();
+
+            let kind = match token.kind {
+                Identifier => {
+                    path.push(token.value.unwrap());
+
+                    if self.peek().kind == ComplexPunctuation(NamespaceSeparator) {
+                        self.advance();
+                        self.advance();
+                        continue;
+                    }
+
+                    let mut alias = None;
+                    if self.peek().kind == Keyword(As) {
+                        self.advance();
+                        self.advance();
+                        alias = Some(self.expect(Identifier)?.value.unwrap());
+                    }
+
+                    UseTreeKind::Simple { alias }
+                }
+                ComplexPunctuation(OperatorMultiply) if !path.is_empty() => UseTreeKind::Glob,
+                SimplePunctuation(BraceOpen) => UseTreeKind::Group(self.parse_use_group()?),
+                _ => {
+                    return Err(SyntaxError::default()
+                        .ctx(
+                            ErrorContextBuilder::loc(&token.loc)
+                                .from_src_and_ln(&self.src, token.loc.ln)
+                                .build(),
+                        )
+                        .kind(SyntaxErrorKind::Unexpected(SyntaxErrorSource::Token))
+                        .msg("expected a name, `*` or `{` in use path"));
+                }
+            };
+
+            return Ok(UseTree {
+                path,
+                kind,
+                loc: Loc::between(&start.loc, &self.current().loc),
+            });
+        }
+    }
+
+    fn parse_use_group(&mut self) -> Result<Vec<UseTree>, SyntaxError> {
+        self.expect(SimplePunctuation(BraceOpen))?;
+        let mut trees = Vec::new();
+
+        loop {
+            self.advance();
+            if matches!(self.current().kind, SimplePunctuation(BraceClose) | EOF) {
+                break;
+            }
+
+            trees.push(self.parse_use_tree()?);
+
+            self.advance();
+            if self.current().kind != SimplePunctuation(Comma) {
+                break;
+            }
+        }
+
+        self.expect(SimplePunctuation(BraceClose))?;
+        return Ok(trees);
+    }
 }
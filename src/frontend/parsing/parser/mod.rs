@@ -2,6 +2,8 @@ pub mod bodies;
 pub mod expressions;
 pub mod functions;
 pub mod items;
+pub mod namespaces;
+pub mod patterns;
 pub mod statements;
 pub mod types;
 
@@ -11,34 +13,41 @@ use crate::{
         syntax::{SyntaxError, SyntaxErrorKind, SyntaxErrorSource},
     },
     frontend::lexing::{
-        Token,
+        SimplePunctuationKind, Token,
         TokenKind::{self, *},
     },
     structs::ScriptSource,
 };
 
-use super::ast::projects::ProjectNode;
+use super::ast::{
+    namespaces::{NamespaceKind, NamespaceNode},
+    projects::ProjectNode,
+};
 
 pub struct Parser<'a> {
     pub project: ProjectNode,
     pub errors: Vec<SyntaxError>,
 
     tokens: &'a Vec<Token>,
+    docs: &'a [Token],
     src: ScriptSource<'a>,
     cursor: usize,
+    // `if a { }` would otherwise read `a { }` as a struct literal
+    no_struct_literals: bool,
 }
 
 impl<'a> Parser<'a> {
-    pub fn new(tokens: &'a Vec<Token>, src: ScriptSource<'a>) -> Parser<'a> {
+    pub fn new(tokens: &'a Vec<Token>, docs: &'a [Token], src: ScriptSource<'a>) -> Parser<'a> {
         Parser {
             tokens,
+            docs,
             src,
             ..Default::default()
         }
     }
 
-    pub fn parse(tokens: &'a Vec<Token>, src: ScriptSource<'a>) -> Parser<'a> {
-        let mut parser = Parser::new(tokens, src);
+    pub fn parse(tokens: &'a Vec<Token>, docs: &'a [Token], src: ScriptSource<'a>) -> Parser<'a> {
+        let mut parser = Parser::new(tokens, docs, src);
         parser.process();
 
         return parser;
@@ -49,14 +58,76 @@ impl<'a> Parser<'a> {
             return;
         }
 
-        while self.current().kind != EOF {
-            let result = self.parse_item();
-            let Ok(item) = result else {
-                self.errors.push(result.unwrap_err());
+        let mut root = NamespaceNode::new(NamespaceKind::Root, Vec::new());
+        self.parse_namespace_items(&mut root, false);
+
+        self.project.root = root;
+    }
+
+    fn recover(&mut self, err: SyntaxError, start: usize) {
+        // undefined tokens were already reported by the lexer
+        if self.current().kind != Undefined {
+            self.errors.push(err);
+        }
+
+        self.synchronize(start);
+    }
+
+    // skips the rest of a broken statement or item, stopping on the cursor
+    // of its last token so that the caller's next advance starts a new one.
+    // braces of interpolations aren't blocks, so an error inside an
+    // interpolated string skips to its end first
+    fn synchronize(&mut self, start: usize) {
+        let mut depth = 0usize;
+        let mut strings = 0usize;
+        for token in self.tokens[start..self.cursor].iter() {
+            match token.kind {
+                InterpolatedStringStart => strings += 1,
+                InterpolatedStringEnd => strings = strings.saturating_sub(1),
+                _ if strings > 0 => {}
+                SimplePunctuation(SimplePunctuationKind::BraceOpen) => depth += 1,
+                SimplePunctuation(SimplePunctuationKind::BraceClose) => {
+                    depth = depth.saturating_sub(1)
+                }
+                _ => {}
+            }
+        }
+
+        loop {
+            let token = self.current();
+            let can_stop_before = self.cursor > start;
+
+            match token.kind {
+                InterpolatedStringStart => strings += 1,
+                InterpolatedStringEnd => strings = strings.saturating_sub(1),
+                _ if strings > 0 && token.kind != EOF => {}
+                SimplePunctuation(SimplePunctuationKind::Semicolon) if depth == 0 => return,
+                SimplePunctuation(SimplePunctuationKind::BraceOpen) => depth += 1,
+                SimplePunctuation(SimplePunctuationKind::BraceClose) if depth > 0 => {
+                    depth -= 1;
+                    if depth == 0 {
+                        if self.peek().kind == SimplePunctuation(SimplePunctuationKind::Semicolon) {
+                            self.advance();
+                        }
+
+                        return;
+                    }
+                }
+                SimplePunctuation(SimplePunctuationKind::BraceClose) | EOF if can_stop_before => {
+                    self.cursor -= 1;
+                    return;
+                }
+                Keyword(kind) if depth == 0 && can_stop_before && kind.starts_item() => {
+                    self.cursor -= 1;
+                    return;
+                }
+                _ => {}
+            }
+
+            if token.kind == EOF {
                 return;
-            };
+            }
 
-            self.project.root.items.push(item);
             self.advance();
         }
     }
@@ -73,6 +144,11 @@ impl<'a> Parser<'a> {
         self.tokens[self.cursor].clone()
     }
 
+    fn peek(&self) -> Token {
+        let idx = (self.cursor + 1).min(self.tokens.len() - 1);
+        self.tokens[idx].clone()
+    }
+
     fn advance(&mut self) {
         self.cursor += 1;
     }
@@ -109,7 +185,7 @@ impl<'a> Parser<'a> {
         let str_kinds: Vec<String> = kinds.iter().map(|kind| kind.to_string()).collect();
         Err(SyntaxError::default()
             .ctx(
-                ErrorContextBuilder::span(self.current().loc.start_col, self.current().loc.end_col)
+                ErrorContextBuilder::loc(&self.current().loc)
                     .from_src_and_ln(&self.src, self.current().loc.ln)
                     .build(),
             )
@@ -124,10 +200,12 @@ impl<'a> Default for Parser<'a> {
     fn default() -> Self {
         Self {
             tokens: &DEFAULT_TOKENS,
+            docs: &[],
             errors: Vec::new(),
             project: ProjectNode::default(),
             src: ScriptSource::default(),
             cursor: 0,
+            no_struct_literals: false,
         }
     }
 }
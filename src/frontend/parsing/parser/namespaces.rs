@@ -0,0 +1,101 @@
+use crate::{
+    errors::{
+        context::ErrorContextBuilder,
+        syntax::{SyntaxError, SyntaxErrorKind, SyntaxErrorSource},
+    },
+    frontend::{
+        lexing::{
+            ComplexPunctuationKind::*, KeywordKind::*, SimplePunctuationKind::*, TokenKind::*,
+        },
+        parsing::ast::{
+            items::ItemKind,
+            namespaces::{NamespaceKind, NamespaceNode, NamespacePath},
+        },
+    },
+};
+
+use super::Parser;
+
+impl Parser<'_> {
+    pub(super) fn parse_namespace_items(&mut self, namespace: &mut NamespaceNode, nested: bool) {
+        loop {
+            let token = self.current();
+            if token.kind == EOF || (nested && token.kind == SimplePunctuation(BraceClose)) {
+                break;
+            }
+
+            let start = self.cursor;
+            if token.kind == Keyword(Namespace) {
+                if let Err(err) = self.parse_namespace(namespace) {
+                    self.recover(err, start);
+                }
+            } else {
+                match self.parse_item() {
+                    Ok(item) => namespace.items.push(item),
+                    Err(err) => self.recover(err, start),
+                }
+            }
+
+            self.advance();
+        }
+    }
+
+    fn parse_namespace(&mut self, parent: &mut NamespaceNode) -> Result<(), SyntaxError> {
+        let keyword = self.expect(Keyword(Namespace))?;
+
+        self.advance();
+        let mut names = vec![self.expect(Identifier)?.value.unwrap()];
+
+        while self.peek().kind == ComplexPunctuation(NamespaceSeparator) {
+            self.advance();
+            self.advance();
+            names.push(self.expect(Identifier)?.value.unwrap());
+        }
+
+        let path = match &parent.kind {
+            NamespaceKind::Root => NamespacePath::new(names),
+            NamespaceKind::Sub(parent) => parent.join(&NamespacePath::new(names)),
+        };
+        let mut node = NamespaceNode::new(NamespaceKind::Sub(path), Vec::new());
+
+        self.advance();
+        if self.current().kind == SimplePunctuation(Semicolon) {
+            let only_imports = parent
+                .items
+                .iter()
+                .all(|item| matches!(item.kind, ItemKind::Use(_)));
+
+            if !matches!(parent.kind, NamespaceKind::Root)
+                || !only_imports
+                || !parent.children.is_empty()
+            {
+                return Err(SyntaxError::default()
+                    .ctx(
+                        ErrorContextBuilder::loc(&keyword.loc)
+                            .from_src_and_ln(&self.src, keyword.loc.ln)
+                            .build(),
+                    )
+                    .kind(SyntaxErrorKind::Unexpected(SyntaxErrorSource::Token))
+                    .msg("file-scoped namespaces must come before any other items"));
+            }
+
+            // the rest of the file belongs to the namespace, the cursor is
+            // left before the end so that the caller stops there
+            self.advance();
+            self.parse_namespace_items(&mut node, false);
+            self.cursor -= 1;
+
+            parent.children.push(node);
+            return Ok(());
+        }
+
+        self.expect(SimplePunctuation(BraceOpen))?;
+
+        self.advance();
+        self.parse_namespace_items(&mut node, true);
+        parent.children.push(node);
+
+        self.expect(SimplePunctuation(BraceClose))?;
+        return Ok(());
+    }
+}
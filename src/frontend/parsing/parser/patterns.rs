@@ -0,0 +1,264 @@
+use crate::{
+    errors::{
+        context::ErrorContextBuilder,
+        syntax::{SyntaxError, SyntaxErrorKind, SyntaxErrorSource},
+    },
+    frontend::{
+        lexing::{
+            ComplexPunctuationKind::*, KeywordKind::*, LiteralKind, SimplePunctuationKind::*,
+            TokenKind::*,
+        },
+        parsing::ast::{
+            expressions::ExpressionNode,
+            patterns::{FieldPatternNode, MatchArmNode, PatternKind, PatternNode, PatternPayload},
+        },
+        Loc,
+    },
+};
+
+use super::Parser;
+
+impl Parser<'_> {
+    // starts on `match` and stops on the closing brace, every arm body starts
+    // on its `=>` and has to stop on its last token
+    pub(super) fn parse_match<T>(
+        &mut self,
+        mut parse_arm_body: impl FnMut(&mut Self) -> Result<T, SyntaxError>,
+    ) -> Result<(ExpressionNode, Vec<MatchArmNode<T>>), SyntaxError> {
+        self.expect(Keyword(Match))?;
+
+        self.advance();
+        let value = self.parse_condition()?;
+        self.expect(SimplePunctuation(BraceOpen))?;
+
+        let mut arms = Vec::new();
+
+        loop {
+            self.advance();
+            if matches!(self.current().kind, SimplePunctuation(BraceClose) | EOF) {
+                break;
+            }
+
+            let pattern = self.parse_pattern()?;
+
+            self.advance();
+            let mut guard = None;
+
+            if self.current().kind == Keyword(If) {
+                self.advance();
+                guard = Some(self.parse_expression()?);
+            }
+
+            self.expect(ComplexPunctuation(InlineBody))?;
+            let body = parse_arm_body(self)?;
+
+            arms.push(MatchArmNode {
+                pattern,
+                guard,
+                body,
+            });
+        }
+
+        self.expect(SimplePunctuation(BraceClose))?;
+        return Ok((value, arms));
+    }
+
+    pub(super) fn parse_pattern(&mut self) -> Result<PatternNode, SyntaxError> {
+        let first = self.parse_single_pattern()?;
+        if self.peek().kind != ComplexPunctuation(OperatorBitOr) {
+            return Ok(first);
+        }
+
+        let start = first.loc.clone();
+        let mut alternatives = vec![first];
+
+        while self.peek().kind == ComplexPunctuation(OperatorBitOr) {
+            self.advance();
+            self.advance();
+            alternatives.push(self.parse_single_pattern()?);
+        }
+
+        return Ok(PatternNode {
+            loc: Loc::between(&start, &self.current().loc),
+            kind: PatternKind::Or(alternatives),
+        });
+    }
+
+    fn parse_single_pattern(&mut self) -> Result<PatternNode, SyntaxError> {
+        let token = self.current();
+
+        match token.kind {
+            Literal(kind) => Ok(PatternNode {
+                kind: PatternKind::Literal {
+                    kind,
+                    value: token.value.unwrap(),
+                    negative: false,
+                },
+                loc: token.loc,
+            }),
+            ComplexPunctuation(OperatorMinus)
+                if matches!(
+                    self.peek().kind,
+                    Literal(LiteralKind::Integer | LiteralKind::Float)
+                ) =>
+            {
+                self.advance();
+                let number = self.current();
+
+                Ok(PatternNode {
+                    kind: PatternKind::Literal {
+                        kind: match number.kind {
+                            Literal(kind) => kind,
+                            _ => unreachable!(),
+                        },
+                        value: number.value.unwrap(),
+                        negative: true,
+                    },
+                    loc: Loc::between(&token.loc, &number.loc),
+                })
+            }
+            Identifier if token.value.as_deref() == Some("_") => Ok(PatternNode {
+                kind: PatternKind::Wildcard,
+                loc: token.loc,
+            }),
+            Identifier => self.parse_path_pattern(),
+            // `(a)` only groups, a tuple needs a comma: `(a, b)` or `(a,)`
+            SimplePunctuation(ParenOpen) => {
+                let mut patterns = self.parse_tuple_patterns()?;
+                let loc = Loc::between(&token.loc, &self.current().loc);
+
+                let trailing_comma = self
+                    .previous()
+                    .is_ok_and(|token| token.kind == SimplePunctuation(Comma));
+
+                if patterns.len() == 1 && !trailing_comma {
+                    let mut pattern = patterns.remove(0);
+                    pattern.loc = loc;
+                    return Ok(pattern);
+                }
+
+                Ok(PatternNode {
+                    kind: PatternKind::Tuple(patterns),
+                    loc,
+                })
+            }
+            _ => Err(SyntaxError::default()
+                .ctx(
+                    ErrorContextBuilder::loc(&token.loc)
+                        .from_src_and_ln(&self.src, token.loc.ln)
+                        .build(),
+                )
+                .kind(SyntaxErrorKind::Expected(SyntaxErrorSource::Token))
+                .msg("expected a pattern")),
+        }
+    }
+
+    fn parse_path_pattern(&mut self) -> Result<PatternNode, SyntaxError> {
+        let start = self.current();
+        let mut path = vec![self.expect(Identifier)?.value.unwrap()];
+
+        while self.peek().kind == ComplexPunctuation(NamespaceSeparator) {
+            self.advance();
+            self.advance();
+            path.push(self.expect(Identifier)?.value.unwrap());
+        }
+
+        let payload = match self.peek().kind {
+            SimplePunctuation(ParenOpen) => {
+                self.advance();
+                PatternPayload::Tuple(self.parse_tuple_patterns()?)
+            }
+            SimplePunctuation(BraceOpen) => {
+                self.advance();
+                self.parse_struct_patterns()?
+            }
+            _ => PatternPayload::Unit,
+        };
+
+        let loc = Loc::between(&start.loc, &self.current().loc);
+
+        // a lone name binds the value, unit variants are written with their enum
+        if path.len() == 1 && payload == PatternPayload::Unit {
+            return Ok(PatternNode {
+                kind: PatternKind::Binding {
+                    name: path.remove(0),
+                },
+                loc,
+            });
+        }
+
+        return Ok(PatternNode {
+            kind: PatternKind::Variant { path, payload },
+            loc,
+        });
+    }
+
+    fn parse_tuple_patterns(&mut self) -> Result<Vec<PatternNode>, SyntaxError> {
+        self.expect(SimplePunctuation(ParenOpen))?;
+        let mut patterns = Vec::new();
+
+        loop {
+            self.advance();
+            if matches!(self.current().kind, SimplePunctuation(ParenClose) | EOF) {
+                break;
+            }
+
+            patterns.push(self.parse_pattern()?);
+
+            self.advance();
+            if self.current().kind != SimplePunctuation(Comma) {
+                break;
+            }
+        }
+
+        self.expect(SimplePunctuation(ParenClose))?;
+        return Ok(patterns);
+    }
+
+    fn parse_struct_patterns(&mut self) -> Result<PatternPayload, SyntaxError> {
+        self.expect(SimplePunctuation(BraceOpen))?;
+        let mut fields = Vec::new();
+        let mut rest = false;
+
+        loop {
+            self.advance();
+            if matches!(self.current().kind, SimplePunctuation(BraceClose) | EOF) {
+                break;
+            }
+
+            if self.current().kind == ComplexPunctuation(Range) {
+                rest = true;
+                self.advance();
+                break;
+            }
+
+            let name_token = self.expect(Identifier)?;
+            let name = name_token.value.unwrap();
+
+            let pattern = if self.peek().kind == ComplexPunctuation(Colon) {
+                self.advance();
+                self.advance();
+                self.parse_pattern()?
+            } else {
+                PatternNode {
+                    kind: PatternKind::Binding { name: name.clone() },
+                    loc: name_token.loc.clone(),
+                }
+            };
+
+            fields.push(FieldPatternNode {
+                name,
+                loc: Loc::between(&name_token.loc, &pattern.loc),
+                pattern,
+            });
+
+            self.advance();
+            if self.current().kind != SimplePunctuation(Comma) {
+                break;
+            }
+        }
+
+        self.expect(SimplePunctuation(BraceClose))?;
+        return Ok(PatternPayload::Struct { fields, rest });
+    }
+}
@@ -9,9 +9,10 @@ use crate::{
             TokenKind::*,
         },
         parsing::ast::statements::{
-            IfKind, IfNode, StatementKind, StatementNode, VariableDeclarationNode, WhileLoopKind,
-            WhileLoopNode,
+            ForLoopNode, IfKind, IfNode, LabelNode, LoopControlNode, MatchNode, StatementKind,
+            StatementNode, VariableDeclarationNode, WhileLoopKind, WhileLoopNode,
         },
+        Loc,
     },
 };
 
@@ -22,11 +23,11 @@ impl Parser<'_> {
         let stmt = match self.current().kind {
             Keyword(Var) => {
                 let var = self.parse_variable_declaration()?;
-                StatementKind::VariableDeclaration(var)
+                StatementKind::VariableDeclaration(Box::new(var))
             }
             Keyword(Func) => {
                 let func = self.parse_function()?;
-                StatementKind::FunctionDeclaration(func)
+                StatementKind::FunctionDeclaration(Box::new(func))
             }
             Keyword(Return) => {
                 self.advance();
@@ -40,9 +41,26 @@ impl Parser<'_> {
                 StatementKind::If(if_stmt)
             }
             Keyword(While) => {
-                let while_loop = self.parse_while_loop()?;
+                let while_loop = self.parse_while_loop(None)?;
                 StatementKind::WhileLoop(while_loop)
             }
+            Keyword(For) => {
+                let for_loop = self.parse_for_loop(None)?;
+                StatementKind::ForLoop(Box::new(for_loop))
+            }
+            Identifier if self.is_label() => self.parse_labeled_loop()?,
+            Keyword(Break) => StatementKind::Break(self.parse_loop_control()?),
+            Keyword(Continue) => StatementKind::Continue(self.parse_loop_control()?),
+            Keyword(Match) => {
+                let start = self.current().loc;
+                let (value, arms) = self.parse_match(|parser| parser.parse_inline_body())?;
+
+                StatementKind::Match(MatchNode {
+                    value,
+                    arms,
+                    loc: Loc::between(&start, &self.current().loc),
+                })
+            }
             _ => {
                 let expr = self.parse_expression()?;
                 self.expect(SimplePunctuation(Semicolon))?;
@@ -86,7 +104,7 @@ impl Parser<'_> {
         if r#type == None && value == None {
             return Err(SyntaxError::default()
                 .ctx(
-                    ErrorContextBuilder::span(name_token.loc.start_col, name_token.loc.end_col)
+                    ErrorContextBuilder::loc(&name_token.loc)
                         .from_src_and_ln(&self.src, name_token.loc.ln)
                         .build(),
                 )
@@ -98,6 +116,7 @@ impl Parser<'_> {
 
         return Ok(VariableDeclarationNode {
             name: name_token.value.unwrap(),
+            loc: name_token.loc,
             r#type,
             value,
             mutable,
@@ -108,13 +127,12 @@ impl Parser<'_> {
         self.expect(Keyword(If))?;
         self.advance();
 
-        let cond = self.parse_expression()?;
+        let cond = self.parse_condition()?;
         let body = self.parse_body()?;
 
-        self.advance();
-
         let mut r#else = None;
-        if self.current().kind == Keyword(Else) {
+        if self.peek().kind == Keyword(Else) {
+            self.advance();
             self.advance();
 
             if self.current().kind == Keyword(If) {
@@ -135,27 +153,97 @@ impl Parser<'_> {
         });
     }
 
-    fn parse_while_loop(&mut self) -> Result<WhileLoopNode, SyntaxError> {
-        self.expect(Keyword(While))?;
+    // a label is a name and a colon right before a loop: `outer: for ...`
+    fn is_label(&self) -> bool {
+        let after = self.tokens.get(self.cursor + 2).map(|token| &token.kind);
+
+        self.peek().kind == ComplexPunctuation(Colon) && matches!(after, Some(Keyword(For | While)))
+    }
+
+    fn parse_labeled_loop(&mut self) -> Result<StatementKind, SyntaxError> {
+        let name_token = self.expect(Identifier)?;
+        let label = Some(LabelNode {
+            name: name_token.value.unwrap(),
+            loc: name_token.loc,
+        });
+
+        self.advance();
+        self.advance();
+
+        if self.current().kind == Keyword(For) {
+            return Ok(StatementKind::ForLoop(Box::new(
+                self.parse_for_loop(label)?,
+            )));
+        }
+
+        return Ok(StatementKind::WhileLoop(self.parse_while_loop(label)?));
+    }
+
+    fn parse_loop_control(&mut self) -> Result<LoopControlNode, SyntaxError> {
+        let keyword = self.expect_multiple(vec![Keyword(Break), Keyword(Continue)])?;
+        self.advance();
+
+        let mut label = None;
+        if self.current().kind == Identifier {
+            let token = self.current();
+            label = Some(LabelNode {
+                name: token.value.unwrap(),
+                loc: token.loc,
+            });
+
+            self.advance();
+        }
+
+        self.expect(SimplePunctuation(Semicolon))?;
+
+        return Ok(LoopControlNode {
+            label,
+            loc: keyword.loc,
+        });
+    }
+
+    fn parse_for_loop(&mut self, label: Option<LabelNode>) -> Result<ForLoopNode, SyntaxError> {
+        self.expect(Keyword(For))?;
+        self.advance();
+
+        let pattern = self.parse_pattern()?;
+
+        self.advance();
+        self.expect(Keyword(In))?;
         self.advance();
 
-        let cond = self.parse_expression()?;
+        let iterable = self.parse_condition()?;
         let body = self.parse_body()?;
 
+        return Ok(ForLoopNode {
+            pattern,
+            iterable,
+            body,
+            label,
+        });
+    }
+
+    fn parse_while_loop(&mut self, label: Option<LabelNode>) -> Result<WhileLoopNode, SyntaxError> {
+        self.expect(Keyword(While))?;
         self.advance();
 
+        let cond = self.parse_condition()?;
+        let body = self.parse_body()?;
+
         let mut r#else = None;
-        if self.current().kind == Keyword(Else) {
+        if self.peek().kind == Keyword(Else) {
+            self.advance();
             self.advance();
 
             if self.current().kind == Keyword(While) {
-                let else_while = self.parse_while_loop()?;
+                let else_while = self.parse_while_loop(None)?;
                 r#else = Some(Box::new(else_while));
             } else {
                 let else_body = self.parse_body()?;
                 r#else = Some(Box::new(WhileLoopNode {
                     kind: WhileLoopKind::Else,
                     body: else_body,
+                    label: None,
                 }));
             }
         }
@@ -163,6 +251,7 @@ impl Parser<'_> {
         return Ok(WhileLoopNode {
             kind: WhileLoopKind::While { cond, r#else },
             body,
+            label,
         });
     }
 }
@@ -1,16 +1,192 @@
 use crate::{
-    errors::syntax::SyntaxError,
-    frontend::{lexing::TokenKind::*, parsing::ast::types::TypeNode},
+    errors::{
+        context::ErrorContextBuilder,
+        syntax::{SyntaxError, SyntaxErrorKind, SyntaxErrorSource},
+    },
+    frontend::{
+        lexing::{
+            ComplexPunctuationKind::*, KeywordKind::*, SimplePunctuationKind::*, TokenKind,
+            TokenKind::*,
+        },
+        parsing::ast::types::{GenericParamNode, TypeKind, TypeNode},
+        Loc,
+    },
 };
 
 use super::Parser;
 
 impl Parser<'_> {
     pub(super) fn parse_type(&mut self) -> Result<TypeNode, SyntaxError> {
-        let type_token = self.expect(Identifier)?;
+        let start = self.current();
 
-        Ok(TypeNode {
-            raw: type_token.value.unwrap(),
-        })
+        let kind = match start.kind {
+            Identifier => self.parse_path_type()?,
+            SimplePunctuation(BracketOpen) => self.parse_array_type()?,
+            SimplePunctuation(ParenOpen) => self.parse_tuple_type()?,
+            Keyword(Func) => self.parse_function_type()?,
+            _ => {
+                return Err(SyntaxError::default()
+                    .ctx(
+                        ErrorContextBuilder::loc(&start.loc)
+                            .from_src_and_ln(&self.src, start.loc.ln)
+                            .build(),
+                    )
+                    .kind(SyntaxErrorKind::Expected(SyntaxErrorSource::Token))
+                    .msg("expected a type"));
+            }
+        };
+
+        let mut node = TypeNode {
+            kind,
+            loc: Loc::between(&start.loc, &self.current().loc),
+        };
+
+        while self.peek().kind == ComplexPunctuation(Optional) {
+            self.advance();
+
+            node = TypeNode {
+                loc: Loc::between(&node.loc, &self.current().loc),
+                kind: TypeKind::Optional {
+                    inner: Box::new(node),
+                },
+            };
+        }
+
+        return Ok(node);
+    }
+
+    fn parse_path_type(&mut self) -> Result<TypeKind, SyntaxError> {
+        let mut path = vec![self.expect(Identifier)?.value.unwrap()];
+
+        while self.peek().kind == ComplexPunctuation(NamespaceSeparator) {
+            self.advance();
+            self.advance();
+            path.push(self.expect(Identifier)?.value.unwrap());
+        }
+
+        let mut generics = Vec::new();
+
+        if self.peek().kind == ComplexPunctuation(AngleOpen) {
+            self.advance();
+            generics = self.parse_type_list(ComplexPunctuation(AngleClose))?;
+        }
+
+        return Ok(TypeKind::Path { path, generics });
+    }
+
+    fn parse_array_type(&mut self) -> Result<TypeKind, SyntaxError> {
+        self.expect(SimplePunctuation(BracketOpen))?;
+
+        self.advance();
+        let element = Box::new(self.parse_type()?);
+
+        self.advance();
+        if self.current().kind != SimplePunctuation(Semicolon) {
+            self.expect(SimplePunctuation(BracketClose))?;
+            return Ok(TypeKind::Slice { element });
+        }
+
+        self.advance();
+        let size = Box::new(self.parse_expression()?);
+
+        self.expect(SimplePunctuation(BracketClose))?;
+        return Ok(TypeKind::Array { element, size });
+    }
+
+    fn parse_tuple_type(&mut self) -> Result<TypeKind, SyntaxError> {
+        let mut elements = self.parse_type_list(SimplePunctuation(ParenClose))?;
+
+        // `(int)` only groups, a one-element tuple is written `(int,)`
+        let trailing_comma = self
+            .previous()
+            .is_ok_and(|token| token.kind == SimplePunctuation(Comma));
+
+        if elements.len() == 1 && !trailing_comma {
+            return Ok(elements.remove(0).kind);
+        }
+
+        return Ok(TypeKind::Tuple { elements });
+    }
+
+    fn parse_function_type(&mut self) -> Result<TypeKind, SyntaxError> {
+        self.expect(Keyword(Func))?;
+
+        self.advance();
+        self.expect(SimplePunctuation(ParenOpen))?;
+        let args = self.parse_type_list(SimplePunctuation(ParenClose))?;
+
+        let mut r#return = None;
+
+        if self.peek().kind == ComplexPunctuation(ReturnSeparator) {
+            self.advance();
+            self.advance();
+            r#return = Some(Box::new(self.parse_type()?));
+        }
+
+        return Ok(TypeKind::Function { args, r#return });
+    }
+
+    // starts on `<` and stops on `>`, bounds are joined with `+`
+    pub(super) fn parse_generic_params(&mut self) -> Result<Vec<GenericParamNode>, SyntaxError> {
+        self.expect(ComplexPunctuation(AngleOpen))?;
+        let mut params = Vec::new();
+
+        loop {
+            self.advance();
+            if matches!(self.current().kind, ComplexPunctuation(AngleClose) | EOF) {
+                break;
+            }
+
+            let name_token = self.expect(Identifier)?;
+            let mut bounds = Vec::new();
+
+            self.advance();
+            if self.current().kind == ComplexPunctuation(Colon) {
+                loop {
+                    self.advance();
+                    bounds.push(self.parse_type()?);
+
+                    self.advance();
+                    if self.current().kind != ComplexPunctuation(OperatorPlus) {
+                        break;
+                    }
+                }
+            }
+
+            params.push(GenericParamNode {
+                name: name_token.value.unwrap(),
+                bounds,
+                loc: Loc::between(&name_token.loc, &self.previous().unwrap().loc),
+            });
+
+            if self.current().kind != SimplePunctuation(Comma) {
+                break;
+            }
+        }
+
+        self.expect(ComplexPunctuation(AngleClose))?;
+        return Ok(params);
+    }
+
+    // starts on the opening token and stops on `close`, trailing commas are allowed
+    fn parse_type_list(&mut self, close: TokenKind) -> Result<Vec<TypeNode>, SyntaxError> {
+        let mut types = Vec::new();
+
+        loop {
+            self.advance();
+            if self.current().kind == close || self.current().kind == EOF {
+                break;
+            }
+
+            types.push(self.parse_type()?);
+
+            self.advance();
+            if self.current().kind != SimplePunctuation(Comma) {
+                break;
+            }
+        }
+
+        self.expect(close)?;
+        return Ok(types);
     }
 }
@@ -0,0 +1,198 @@
+// the types built into the language. `int` and `float` are other names for
+// `int64` and `float64`, and unsuffixed number literals default to them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrimitiveType {
+    Int(IntType),
+    Float(FloatType),
+    Bool,
+    Char,
+    String,
+    Void,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntType {
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FloatType {
+    Float32,
+    Float64,
+}
+
+impl PrimitiveType {
+    pub const INT: PrimitiveType = PrimitiveType::Int(IntType::Int64);
+    pub const FLOAT: PrimitiveType = PrimitiveType::Float(FloatType::Float64);
+
+    pub fn from_name(name: &str) -> Option<PrimitiveType> {
+        use IntType::*;
+
+        let r#type = match name {
+            "int8" => PrimitiveType::Int(Int8),
+            "int16" => PrimitiveType::Int(Int16),
+            "int32" => PrimitiveType::Int(Int32),
+            "int64" | "int" => PrimitiveType::Int(Int64),
+            "uint8" => PrimitiveType::Int(UInt8),
+            "uint16" => PrimitiveType::Int(UInt16),
+            "uint32" => PrimitiveType::Int(UInt32),
+            "uint64" => PrimitiveType::Int(UInt64),
+            "float32" => PrimitiveType::Float(FloatType::Float32),
+            "float64" | "float" => PrimitiveType::Float(FloatType::Float64),
+            "bool" => PrimitiveType::Bool,
+            "char" => PrimitiveType::Char,
+            "string" => PrimitiveType::String,
+            "void" => PrimitiveType::Void,
+            _ => return None,
+        };
+
+        return Some(r#type);
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PrimitiveType::Int(int) => int.name(),
+            PrimitiveType::Float(float) => float.name(),
+            PrimitiveType::Bool => "bool",
+            PrimitiveType::Char => "char",
+            PrimitiveType::String => "string",
+            PrimitiveType::Void => "void",
+        }
+    }
+
+    // numbers convert into each other, bools and chars into integers, and
+    // only bytes into chars
+    pub fn can_cast(&self, target: &PrimitiveType) -> bool {
+        use PrimitiveType::*;
+
+        match (self, target) {
+            (from, to) if from == to => true,
+            (Int(_) | Float(_), Int(_) | Float(_)) => true,
+            (Bool | Char, Int(_)) => true,
+            (Int(IntType::UInt8), Char) => true,
+            _ => false,
+        }
+    }
+}
+
+impl IntType {
+    pub fn bits(&self) -> u32 {
+        match self {
+            IntType::Int8 | IntType::UInt8 => 8,
+            IntType::Int16 | IntType::UInt16 => 16,
+            IntType::Int32 | IntType::UInt32 => 32,
+            IntType::Int64 | IntType::UInt64 => 64,
+        }
+    }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            IntType::Int8 | IntType::Int16 | IntType::Int32 | IntType::Int64
+        )
+    }
+
+    pub fn min(&self) -> i128 {
+        match self.is_signed() {
+            true => -(1 << (self.bits() - 1)),
+            false => 0,
+        }
+    }
+
+    pub fn max(&self) -> i128 {
+        match self.is_signed() {
+            true => (1 << (self.bits() - 1)) - 1,
+            false => (1 << self.bits()) - 1,
+        }
+    }
+
+    // the value of a literal as the bits of this type, if it fits in it.
+    // literals are lexed as decimal digits without a sign
+    pub fn parse(&self, digits: &str, negative: bool) -> Option<i64> {
+        let value: i128 = digits.parse().ok()?;
+        let value = if negative { -value } else { value };
+
+        return self.contains(value).then_some(value as i64);
+    }
+
+    pub fn contains(&self, value: i128) -> bool {
+        value >= self.min() && value <= self.max()
+    }
+
+    // the value the bits of this type stand for
+    pub fn value(&self, bits: i64) -> i128 {
+        match self.is_signed() {
+            true => bits as i128,
+            false => bits as u64 as i128,
+        }
+    }
+
+    // the bits of `value` cut down to this type, the way casts between
+    // integers wrap
+    pub fn wrap(&self, value: i128) -> i64 {
+        let shift = 128 - self.bits();
+        let value = match self.is_signed() {
+            true => (value << shift) >> shift,
+            false => ((value as u128) << shift >> shift) as i128,
+        };
+
+        return value as i64;
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            IntType::Int8 => "int8",
+            IntType::Int16 => "int16",
+            IntType::Int32 => "int32",
+            IntType::Int64 => "int64",
+            IntType::UInt8 => "uint8",
+            IntType::UInt16 => "uint16",
+            IntType::UInt32 => "uint32",
+            IntType::UInt64 => "uint64",
+        }
+    }
+}
+
+impl FloatType {
+    pub fn bits(&self) -> u32 {
+        match self {
+            FloatType::Float32 => 32,
+            FloatType::Float64 => 64,
+        }
+    }
+
+    // literals too large for the type would silently become infinity
+    pub fn parse(&self, digits: &str, negative: bool) -> Option<f64> {
+        let value: f64 = digits.parse().ok()?;
+        let value = if negative { -value } else { value };
+
+        let finite = match self {
+            FloatType::Float32 => (value as f32).is_finite(),
+            FloatType::Float64 => value.is_finite(),
+        };
+
+        return finite.then_some(value);
+    }
+
+    // the closest value of this type
+    pub fn round(&self, value: f64) -> f64 {
+        match self {
+            FloatType::Float32 => value as f32 as f64,
+            FloatType::Float64 => value,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FloatType::Float32 => "float32",
+            FloatType::Float64 => "float64",
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod resolver;
+mod scopes;
+
+pub use resolver::*;
+pub use scopes::*;
@@ -0,0 +1,965 @@
+use std::mem;
+
+use crate::{
+    errors::{
+        context::ErrorContextBuilder,
+        resolution::{ResolutionError, ResolutionErrorKind},
+    },
+    frontend::{
+        modules::{is_builtin, is_builtin_namespace, is_builtin_type, STD_NAMESPACE},
+        parsing::ast::{
+            bodies::{BodyElementKind, BodyKind, BodyNode},
+            expressions::{ExpressionKind, ExpressionNode, InterpolationPart},
+            functions::{ArgumentNode, FunctionNode},
+            items::{FieldNode, ItemKind, TraitMethodNode, UseImport, VariantKind},
+            namespaces::NamespaceNode,
+            patterns::{MatchArmNode, PatternKind, PatternNode, PatternPayload},
+            projects::ProjectNode,
+            statements::{
+                IfKind, IfNode, LabelNode, LoopControlNode, StatementKind, StatementNode,
+                WhileLoopKind, WhileLoopNode,
+            },
+            types::{GenericParamNode, TypeKind, TypeNode},
+        },
+        primitives::PrimitiveType,
+        Loc,
+    },
+    structs::ScriptSource,
+};
+
+use super::scopes::{qualify, ScopeKind, ScopeTree, Symbol, SymbolKind};
+
+// bounds every type parameter can have without declaring a trait
+const BUILTIN_BOUNDS: [&str; 2] = ["Eq", "Ord"];
+
+// binds every name in the project to the item, argument or variable it
+// refers to, before anything is compiled
+pub struct Resolver<'a> {
+    pub tree: ScopeTree,
+    pub errors: Vec<ResolutionError>,
+
+    sources: &'a [ScriptSource<'a>],
+    source: usize,
+    scope: usize,
+    // the static or function whose names are being resolved
+    item: Option<usize>,
+    // the labels of the loops around the current statement, innermost last
+    loops: Vec<Option<String>>,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn resolve(project: &ProjectNode, sources: &'a [ScriptSource<'a>]) -> Resolver<'a> {
+        let mut resolver = Resolver {
+            tree: ScopeTree::default(),
+            errors: Vec::new(),
+            sources,
+            source: 0,
+            scope: 0,
+            item: None,
+            loops: Vec::new(),
+        };
+        resolver.process(project);
+
+        return resolver;
+    }
+
+    fn process(&mut self, project: &ProjectNode) {
+        let mut namespaces = Vec::new();
+        self.collect_namespaces(&project.root, &[], None, &mut namespaces);
+
+        // items can be used before they're declared, so every one of them is
+        // known before any body is looked at. methods are declared once the
+        // types they belong to are
+        for (scope, node) in namespaces.iter() {
+            self.enter(*scope, node.source);
+            self.declare_items(node);
+        }
+
+        for (scope, node) in namespaces.iter() {
+            self.enter(*scope, node.source);
+            self.declare_methods(node);
+        }
+
+        for (scope, node) in namespaces.iter() {
+            self.enter(*scope, node.source);
+            self.resolve_imports(node);
+            self.resolve_items(node);
+        }
+    }
+
+    fn collect_namespaces<'n>(
+        &mut self,
+        node: &'n NamespaceNode,
+        inherited: &[UseImport],
+        parent: Option<usize>,
+        namespaces: &mut Vec<(usize, &'n NamespaceNode)>,
+    ) {
+        let mut imports = inherited.to_vec();
+        imports.extend(node.imports());
+
+        let kind = ScopeKind::Namespace {
+            path: node.path(),
+            imports: imports.clone(),
+        };
+        let scope = self.tree.push(kind, parent);
+        namespaces.push((scope, node));
+
+        // namespaces declared in the same file see the imports around them
+        for child in node.children.iter() {
+            let inherited = if child.source == node.source {
+                imports.as_slice()
+            } else {
+                &[]
+            };
+
+            self.collect_namespaces(child, inherited, Some(scope), namespaces);
+        }
+    }
+
+    fn enter(&mut self, scope: usize, source: usize) {
+        self.scope = scope;
+        self.source = source;
+    }
+
+    fn declare_items(&mut self, node: &NamespaceNode) {
+        let namespace = node.path();
+
+        for item in node.items.iter() {
+            let public = item.public;
+
+            match &item.kind {
+                ItemKind::Constant(node) => {
+                    let path = qualify(&namespace, &node.name);
+                    self.declare_global(path, SymbolKind::Constant, &node.loc, public);
+                }
+                ItemKind::Static(node) => {
+                    let path = qualify(&namespace, &node.name);
+                    let kind = SymbolKind::Static {
+                        mutable: node.mutable,
+                    };
+                    self.declare_global(path, kind, &node.loc, public);
+                }
+                ItemKind::Function(node) => {
+                    let path = qualify(&namespace, &node.name);
+                    self.declare_global(path, SymbolKind::Function, &node.loc, public);
+                }
+                ItemKind::Struct(node) => {
+                    let path = qualify(&namespace, &node.name);
+                    self.declare_global(path, SymbolKind::Struct, &node.loc, public);
+                    self.check_fields(&node.fields);
+                }
+                ItemKind::Enum(node) => {
+                    let path = qualify(&namespace, &node.name);
+                    let Some(symbol) =
+                        self.declare_global(path.clone(), SymbolKind::Enum, &node.loc, public)
+                    else {
+                        continue;
+                    };
+
+                    let parent = self.scope;
+                    self.scope = self.tree.members(symbol);
+                    for variant in node.variants.iter() {
+                        let name = qualify(&path, &variant.name);
+                        self.declare_global(name, SymbolKind::Variant, &variant.loc, public);
+
+                        if let VariantKind::Struct(fields) = &variant.kind {
+                            self.check_fields(fields);
+                        }
+                    }
+                    self.scope = parent;
+                }
+                ItemKind::Trait(node) => {
+                    let path = qualify(&namespace, &node.name);
+                    self.declare_global(path, SymbolKind::Trait, &node.loc, public);
+
+                    let names = node
+                        .methods
+                        .iter()
+                        .map(|method| (method.name(), method.loc()));
+                    self.check_unique(names);
+                }
+                ItemKind::Impl(_) | ItemKind::Use(_) => {}
+            }
+        }
+    }
+
+    // inherent methods are items of the type itself, so `Type::method`
+    // finds them like any other path. methods of trait impls are only
+    // reached through values and can't clash with anything but each other
+    fn declare_methods(&mut self, node: &NamespaceNode) {
+        for item in node.items.iter() {
+            let ItemKind::Impl(node) = &item.kind else {
+                continue;
+            };
+
+            if node.r#trait.is_some() {
+                let names = node.methods.iter().map(|method| {
+                    let func = &method.function;
+                    (func.name.as_str(), &func.loc)
+                });
+                self.check_unique(names);
+                continue;
+            }
+
+            // an unknown target is reported along with the rest of the impl
+            let Some(target) = self.type_symbol(&node.target) else {
+                continue;
+            };
+
+            let parent = self.scope;
+            let type_path = self.tree.symbols[target].name.clone();
+            let members = self.tree.members(target);
+
+            for method in node.methods.iter() {
+                let func = &method.function;
+                let path = qualify(&type_path, &func.name);
+
+                self.scope = members;
+                self.declare_global(path, SymbolKind::Method, &func.loc, method.public);
+            }
+
+            self.scope = parent;
+        }
+    }
+
+    // `use` paths are always full paths. the names they bring in can't clash
+    // with each other or with the items of the namespace
+    fn resolve_imports(&mut self, node: &NamespaceNode) {
+        let namespace = node.path();
+        let mut seen = Vec::new();
+
+        for import in node.imports() {
+            let target = import.path.join("::");
+            if !self.import_exists(&target, import.glob) {
+                let err = ResolutionError::default()
+                    .kind(ResolutionErrorKind::Undefined)
+                    .msg(format!("`{target}` does not exist"));
+                self.push_error(err, &import.loc);
+                continue;
+            }
+
+            if import.glob {
+                continue;
+            }
+
+            if let Some(&symbol) = self.tree.globals.get(&target) {
+                self.tree
+                    .uses
+                    .insert((self.source, import.loc.clone()), symbol);
+
+                if !self.tree.can_access(&namespace, symbol) {
+                    self.private_error(symbol, &import.loc);
+                }
+            }
+
+            let name = import.name().to_owned();
+            if seen.contains(&name) || self.tree.scopes[self.scope].names.contains_key(&name) {
+                self.duplicate_error(&name, &import.loc);
+                continue;
+            }
+
+            seen.push(name);
+        }
+    }
+
+    fn import_exists(&self, target: &str, glob: bool) -> bool {
+        let prefix = format!("{target}::");
+        let is_namespace = self.tree.scopes.iter().any(|scope| match &scope.kind {
+            ScopeKind::Namespace { path, .. } => path == target || path.starts_with(&prefix),
+            _ => false,
+        });
+
+        if glob {
+            return is_namespace || is_builtin_namespace(target);
+        }
+
+        return is_namespace
+            || target == STD_NAMESPACE
+            || is_builtin_namespace(target)
+            || is_builtin(target)
+            || is_builtin_type(target)
+            || self.tree.globals.contains_key(target);
+    }
+
+    fn resolve_items(&mut self, node: &NamespaceNode) {
+        for item in node.items.iter() {
+            match &item.kind {
+                ItemKind::Constant(node) => {
+                    self.resolve_type(&node.r#type);
+                    self.resolve_expression(&node.value);
+                }
+                ItemKind::Static(node) => {
+                    if let Some(r#type) = &node.r#type {
+                        self.resolve_type(r#type);
+                    }
+
+                    self.in_item(&node.loc, |r| r.resolve_expression(&node.value));
+                }
+                ItemKind::Function(node) => self.in_item(&node.loc, |r| r.resolve_function(node)),
+                ItemKind::Struct(node) => self.in_scope(ScopeKind::Item, |r| {
+                    r.declare_generics(&node.generics);
+                    r.resolve_fields(&node.fields);
+                }),
+                ItemKind::Enum(node) => {
+                    for variant in node.variants.iter() {
+                        match &variant.kind {
+                            VariantKind::Unit => {}
+                            VariantKind::Tuple(types) => types.iter().for_each(|t| {
+                                self.resolve_type(t);
+                            }),
+                            VariantKind::Struct(fields) => self.resolve_fields(fields),
+                        }
+                    }
+                }
+                ItemKind::Trait(node) => {
+                    for method in node.methods.iter() {
+                        match method {
+                            TraitMethodNode::Required(signature) => {
+                                self.in_scope(ScopeKind::Function, |r| {
+                                    r.resolve_arguments(&signature.args);
+                                    if let Some(r#type) = &signature.r#type {
+                                        r.resolve_type(r#type);
+                                    }
+                                })
+                            }
+                            TraitMethodNode::Provided(func) => self.resolve_function(func),
+                        }
+                    }
+                }
+                ItemKind::Impl(node) => self.in_scope(ScopeKind::Item, |r| {
+                    if let Some(r#trait) = &node.r#trait {
+                        r.resolve_bound(r#trait);
+                    }
+
+                    r.resolve_type(&node.target);
+                    for method in node.methods.iter() {
+                        let func = &method.function;
+                        r.in_item(&func.loc, |r| r.resolve_function(func));
+                    }
+                }),
+                ItemKind::Use(_) => {}
+            }
+        }
+    }
+
+    // loops around a nested function can't be jumped out of from inside it
+    fn resolve_function(&mut self, func: &FunctionNode) {
+        let loops = mem::take(&mut self.loops);
+
+        self.in_scope(ScopeKind::Function, |r| {
+            r.declare_generics(&func.generics);
+            r.resolve_arguments(&func.args);
+
+            if let Some(r#type) = &func.r#type {
+                r.resolve_type(r#type);
+
+                if let Some(name) = &func.return_var {
+                    let kind = SymbolKind::Variable { mutable: true };
+                    r.declare_local(name, kind, &r#type.loc);
+                }
+            }
+
+            r.resolve_body(&func.body);
+        });
+
+        self.loops = loops;
+    }
+
+    // defaults are filled in where the function is called, so they can't
+    // see the other arguments
+    fn resolve_arguments(&mut self, args: &[ArgumentNode]) {
+        for arg in args.iter() {
+            if let Some(r#type) = &arg.r#type {
+                self.resolve_type(r#type);
+            }
+
+            if let Some(default) = &arg.default {
+                self.resolve_expression(default);
+            }
+        }
+
+        for arg in args.iter() {
+            self.declare_unique(&arg.name, SymbolKind::Argument, &arg.loc);
+        }
+    }
+
+    fn declare_generics(&mut self, generics: &[GenericParamNode]) {
+        for param in generics.iter() {
+            self.declare_unique(&param.name, SymbolKind::TypeParam, &param.loc);
+        }
+
+        for param in generics.iter() {
+            param
+                .bounds
+                .iter()
+                .for_each(|bound| self.resolve_bound(bound));
+        }
+    }
+
+    fn resolve_fields(&mut self, fields: &[FieldNode]) {
+        for field in fields.iter() {
+            self.resolve_type(&field.r#type);
+
+            if let Some(default) = &field.default {
+                self.resolve_expression(default);
+            }
+        }
+    }
+
+    fn resolve_body(&mut self, body: &BodyNode) {
+        self.in_scope(ScopeKind::Body, |r| match &body.kind {
+            BodyKind::Inline(el) => r.resolve_body_element(&el.kind),
+            BodyKind::Multiline(els) => els.iter().for_each(|el| r.resolve_body_element(&el.kind)),
+        });
+    }
+
+    fn resolve_body_element(&mut self, el: &BodyElementKind) {
+        match el {
+            BodyElementKind::Statement(stmt) => self.resolve_statement(stmt),
+            BodyElementKind::Body(body) => self.resolve_body(body),
+            BodyElementKind::Empty => {}
+        }
+    }
+
+    fn resolve_statement(&mut self, stmt: &StatementNode) {
+        match &stmt.kind {
+            StatementKind::Expression(expr) | StatementKind::Return(expr) => {
+                self.resolve_expression(expr)
+            }
+            StatementKind::VariableDeclaration(var) => {
+                if let Some(r#type) = &var.r#type {
+                    self.resolve_type(r#type);
+                }
+
+                if let Some(value) = &var.value {
+                    self.resolve_expression(value);
+                }
+
+                let kind = SymbolKind::Variable {
+                    mutable: var.mutable,
+                };
+                self.declare_local(&var.name, kind, &var.loc);
+            }
+            // a nested function sees the functions declared before it, but
+            // not the variables around it
+            StatementKind::FunctionDeclaration(func) => {
+                self.declare_unique(&func.name, SymbolKind::Function, &func.loc);
+                self.resolve_function(func);
+            }
+            StatementKind::If(node) => self.resolve_if(node),
+            StatementKind::WhileLoop(node) => self.resolve_while(node),
+            StatementKind::ForLoop(node) => {
+                self.resolve_expression(&node.iterable);
+
+                self.in_scope(ScopeKind::Body, |r| {
+                    r.resolve_bindings(&node.pattern);
+                    r.resolve_loop_body(&node.body, &node.label);
+                });
+            }
+            StatementKind::Match(node) => {
+                self.resolve_expression(&node.value);
+                self.resolve_arms(&node.arms, |r, body| r.resolve_body(body));
+            }
+            StatementKind::Break(node) => self.check_loop_control(node, "break"),
+            StatementKind::Continue(node) => self.check_loop_control(node, "continue"),
+        }
+    }
+
+    fn resolve_if(&mut self, node: &IfNode) {
+        if let IfKind::If { cond, .. } = &node.kind {
+            self.resolve_expression(cond);
+        }
+
+        self.resolve_body(&node.body);
+
+        if let IfKind::If {
+            r#else: Some(r#else),
+            ..
+        } = &node.kind
+        {
+            self.resolve_if(r#else);
+        }
+    }
+
+    // the `else` of a loop runs after it, so it can't jump back into it
+    fn resolve_while(&mut self, node: &WhileLoopNode) {
+        match &node.kind {
+            WhileLoopKind::While { cond, .. } => {
+                self.resolve_expression(cond);
+                self.resolve_loop_body(&node.body, &node.label);
+            }
+            WhileLoopKind::Else => self.resolve_body(&node.body),
+        }
+
+        if let WhileLoopKind::While {
+            r#else: Some(r#else),
+            ..
+        } = &node.kind
+        {
+            self.resolve_while(r#else);
+        }
+    }
+
+    fn resolve_loop_body(&mut self, body: &BodyNode, label: &Option<LabelNode>) {
+        self.loops
+            .push(label.as_ref().map(|label| label.name.clone()));
+        self.resolve_body(body);
+        self.loops.pop();
+    }
+
+    // labels can be shadowed, so any loop around with the label will do
+    fn check_loop_control(&mut self, node: &LoopControlNode, keyword: &str) {
+        let (msg, loc) = match &node.label {
+            _ if self.loops.is_empty() => (format!("`{keyword}` outside of a loop"), &node.loc),
+            Some(label) if !self.loops.contains(&Some(label.name.clone())) => {
+                (format!("no loop is labeled `{}`", label.name), &label.loc)
+            }
+            _ => return,
+        };
+
+        let err = ResolutionError::default()
+            .kind(ResolutionErrorKind::ControlFlow)
+            .msg(msg);
+        self.push_error(err, loc);
+    }
+
+    fn resolve_arms<T>(
+        &mut self,
+        arms: &[MatchArmNode<T>],
+        mut resolve_body: impl FnMut(&mut Self, &T),
+    ) {
+        for arm in arms.iter() {
+            self.in_scope(ScopeKind::Body, |r| {
+                r.resolve_bindings(&arm.pattern);
+
+                if let Some(guard) = &arm.guard {
+                    r.resolve_expression(guard);
+                }
+
+                resolve_body(r, &arm.body);
+            });
+        }
+    }
+
+    fn resolve_expression(&mut self, expr: &ExpressionNode) {
+        match &expr.kind {
+            ExpressionKind::Literal { .. } => {}
+            ExpressionKind::Identifier { .. } => self.resolve_value(expr),
+            ExpressionKind::Resolution { left, .. } => match expr.path() {
+                Some(_) => self.resolve_value(expr),
+                // the right side of a member access is a field of the value
+                None => self.resolve_expression(left),
+            },
+            ExpressionKind::Interpolation { parts } => {
+                for part in parts.iter() {
+                    if let InterpolationPart::Expression(expr) = part {
+                        self.resolve_expression(expr);
+                    }
+                }
+            }
+            // callees and struct literals name functions and types
+            ExpressionKind::Call { res, args } => {
+                match res.path() {
+                    Some(_) => _ = self.resolve_path(res),
+                    None => self.resolve_expression(res),
+                }
+                args.iter().for_each(|arg| self.resolve_expression(arg));
+            }
+            ExpressionKind::MethodCall {
+                object,
+                method,
+                args,
+            } => {
+                match object.path() {
+                    Some(path) => {
+                        // `Type.function()` written for `Type::function()`
+                        if let Some(what) = self.resolve_path(object) {
+                            let msg = format!(
+                                "`{path}` is {what}, not a value, call `{path}::{method}` instead"
+                            );
+                            self.not_a_value_error(msg, &object.loc);
+                        }
+                    }
+                    None => self.resolve_expression(object),
+                }
+                args.iter().for_each(|arg| self.resolve_expression(arg));
+            }
+            ExpressionKind::Struct { res, fields } => {
+                match res.path() {
+                    Some(_) => _ = self.resolve_path(res),
+                    None => self.resolve_expression(res),
+                }
+                for field in fields.iter() {
+                    self.resolve_expression(&field.value);
+                }
+            }
+            ExpressionKind::Array { elements } | ExpressionKind::Tuple { elements } => {
+                elements.iter().for_each(|el| self.resolve_expression(el));
+            }
+            ExpressionKind::Index { object, index } => {
+                self.resolve_expression(object);
+                self.resolve_expression(index);
+            }
+            ExpressionKind::Range { start, end, .. } => {
+                self.resolve_expression(start);
+                self.resolve_expression(end);
+            }
+            ExpressionKind::Binary { left, right, .. }
+            | ExpressionKind::Assignment { left, right, .. } => {
+                self.resolve_expression(left);
+                self.resolve_expression(right);
+            }
+            ExpressionKind::Unary { right, .. } => self.resolve_expression(right),
+            ExpressionKind::Cast { value, r#type } => {
+                self.resolve_expression(value);
+                self.resolve_type(r#type);
+            }
+            ExpressionKind::Match { value, arms } => {
+                self.resolve_expression(value);
+                self.resolve_arms(arms, |r, body| r.resolve_expression(body));
+            }
+        }
+    }
+
+    // `member` accesses on a path (`a::b.c`) only name a value on the left
+    fn resolve_value(&mut self, expr: &ExpressionNode) {
+        if let Some(what) = self.resolve_path(expr) {
+            let msg = format!("`{}` is {what}, not a value", expr.path().unwrap());
+            self.not_a_value_error(msg, &expr.loc);
+        }
+    }
+
+    // binds a path and tells what it names when that isn't a value,
+    // primitives, builtin types and namespaces only exist in paths and types
+    fn resolve_path(&mut self, expr: &ExpressionNode) -> Option<&'static str> {
+        let path = expr.path().unwrap();
+        if let Some(symbol) = self.bind(&path, &expr.loc) {
+            return match self.tree.symbols[symbol].kind {
+                SymbolKind::Function | SymbolKind::Method => Some("a function"),
+                SymbolKind::Struct => Some("a struct"),
+                SymbolKind::Enum => Some("an enum"),
+                SymbolKind::Trait => Some("a trait"),
+                SymbolKind::TypeParam => Some("a type parameter"),
+                _ => None,
+            };
+        }
+
+        if self.is_builtin(&path, is_builtin) {
+            return Some("a function");
+        }
+
+        if PrimitiveType::from_name(&path).is_some() {
+            return Some("a primitive type");
+        }
+
+        if self.is_builtin(&path, is_builtin_type) {
+            return Some("a builtin type");
+        }
+
+        if self.is_builtin(&path, is_builtin_namespace) {
+            return Some("a namespace");
+        }
+
+        self.undefined_error(&path, &expr.loc);
+        return None;
+    }
+
+    fn resolve_type(&mut self, node: &TypeNode) {
+        match &node.kind {
+            TypeKind::Path { path, generics } => {
+                let path = path.join("::");
+                let symbol = self.bind(&path, &node.loc);
+                let known = PrimitiveType::from_name(&path).is_some()
+                    || symbol.is_some()
+                    || self.is_builtin(&path, is_builtin_type);
+
+                if !known {
+                    self.undefined_error(&path, &node.loc);
+                } else if let Some(symbol) = symbol
+                    && !matches!(
+                        self.tree.symbols[symbol].kind,
+                        SymbolKind::Struct
+                            | SymbolKind::Enum
+                            | SymbolKind::Trait
+                            | SymbolKind::TypeParam
+                    )
+                {
+                    let err = ResolutionError::default()
+                        .kind(ResolutionErrorKind::Undefined)
+                        .msg(format!("`{path}` is not a type"));
+                    self.push_error(err, &node.loc);
+                }
+
+                generics
+                    .iter()
+                    .for_each(|generic| self.resolve_type(generic));
+            }
+            TypeKind::Array { element, size } => {
+                self.resolve_type(element);
+                self.resolve_expression(size);
+            }
+            TypeKind::Slice { element } => self.resolve_type(element),
+            TypeKind::Optional { inner } => self.resolve_type(inner),
+            TypeKind::Tuple { elements } => elements.iter().for_each(|t| self.resolve_type(t)),
+            TypeKind::Function { args, r#return } => {
+                args.iter().for_each(|arg| self.resolve_type(arg));
+                if let Some(r#return) = r#return {
+                    self.resolve_type(r#return);
+                }
+            }
+        }
+    }
+
+    fn resolve_bound(&mut self, node: &TypeNode) {
+        match node.name() {
+            Some(name) if BUILTIN_BOUNDS.contains(&name) && self.lookup(name).is_none() => {}
+            _ => self.resolve_type(node),
+        }
+    }
+
+    // declares the names a pattern binds in the current scope
+    fn resolve_bindings(&mut self, pattern: &PatternNode) {
+        for (name, loc) in self.resolve_pattern(pattern) {
+            self.declare_local(&name, SymbolKind::Binding, &loc);
+        }
+    }
+
+    // the names the pattern binds. every alternative of an or-pattern binds
+    // the same ones, so the first alternative declares them and the others
+    // refer to those
+    fn resolve_pattern(&mut self, pattern: &PatternNode) -> Vec<(String, Loc)> {
+        match &pattern.kind {
+            PatternKind::Wildcard | PatternKind::Literal { .. } => Vec::new(),
+            PatternKind::Binding { name } => {
+                // unit variants brought in with `use` match instead of binding
+                if let Some(symbol) = self.lookup(name)
+                    && self.tree.symbols[symbol].kind == SymbolKind::Variant
+                {
+                    self.bind(name, &pattern.loc);
+                    return Vec::new();
+                }
+
+                vec![(name.clone(), pattern.loc.clone())]
+            }
+            PatternKind::Variant { path, payload } => {
+                let path = path.join("::");
+                if self.bind(&path, &pattern.loc).is_none() {
+                    self.undefined_error(&path, &pattern.loc);
+                }
+
+                match payload {
+                    PatternPayload::Unit => Vec::new(),
+                    PatternPayload::Tuple(patterns) => patterns
+                        .iter()
+                        .flat_map(|p| self.resolve_pattern(p))
+                        .collect(),
+                    PatternPayload::Struct { fields, .. } => fields
+                        .iter()
+                        .flat_map(|field| self.resolve_pattern(&field.pattern))
+                        .collect(),
+                }
+            }
+            PatternKind::Tuple(patterns) => patterns
+                .iter()
+                .flat_map(|p| self.resolve_pattern(p))
+                .collect(),
+            PatternKind::Or(alternatives) => {
+                let mut alternatives = alternatives.iter().map(|p| self.resolve_pattern(p));
+                let first = alternatives.next().unwrap_or_default();
+                let rest: Vec<_> = alternatives.flatten().collect();
+
+                let symbols = first
+                    .iter()
+                    .map(|(name, loc)| {
+                        let kind = SymbolKind::Binding;
+                        (name.clone(), self.declare_local(name, kind, loc))
+                    })
+                    .collect::<Vec<_>>();
+
+                for (name, loc) in rest {
+                    if let Some((_, symbol)) = symbols.iter().find(|(bound, _)| *bound == name) {
+                        self.tree.uses.insert((self.source, loc), *symbol);
+                    }
+                }
+
+                Vec::new()
+            }
+        }
+    }
+
+    fn lookup(&self, path: &str) -> Option<usize> {
+        self.tree.lookup(self.scope, path)
+    }
+
+    // records what the name at `loc` refers to. private items can be reached
+    // through full paths like `a::b::hidden` too, so every use is checked
+    fn bind(&mut self, path: &str, loc: &Loc) -> Option<usize> {
+        let symbol = self.lookup(path)?;
+        self.tree.uses.insert((self.source, loc.clone()), symbol);
+
+        if !self
+            .tree
+            .can_access(self.tree.namespace_path(self.scope), symbol)
+        {
+            self.private_error(symbol, loc);
+        }
+
+        if let Some(item) = self.item
+            && matches!(
+                self.tree.symbols[symbol].kind,
+                SymbolKind::Static { .. } | SymbolKind::Function | SymbolKind::Method
+            )
+        {
+            let references = self.tree.references.entry(item).or_default();
+            if !references.contains(&symbol) {
+                references.push(symbol);
+            }
+        }
+
+        Some(symbol)
+    }
+
+    fn is_builtin(&self, path: &str, kind: impl Fn(&str) -> bool) -> bool {
+        self.tree
+            .candidates(self.scope, path)
+            .iter()
+            .any(|candidate| kind(candidate))
+    }
+
+    // the struct or enum a type refers to, without reporting anything
+    fn type_symbol(&self, node: &TypeNode) -> Option<usize> {
+        let TypeKind::Path { path, .. } = &node.kind else {
+            return None;
+        };
+
+        let symbol = self.lookup(&path.join("::"))?;
+        match self.tree.symbols[symbol].kind {
+            SymbolKind::Struct | SymbolKind::Enum => Some(symbol),
+            _ => None,
+        }
+    }
+
+    // the names used inside the static or function declared at `loc` are
+    // recorded as its references
+    fn in_item(&mut self, loc: &Loc, resolve: impl FnOnce(&mut Self)) {
+        self.item = self
+            .tree
+            .declarations
+            .get(&(self.source, loc.clone()))
+            .copied();
+        resolve(self);
+        self.item = None;
+    }
+
+    fn in_scope(&mut self, kind: ScopeKind, resolve: impl FnOnce(&mut Self)) {
+        let parent = self.scope;
+        self.scope = self.tree.push(kind, Some(parent));
+
+        resolve(self);
+        self.scope = parent;
+    }
+
+    fn declare_global(
+        &mut self,
+        path: String,
+        kind: SymbolKind,
+        loc: &Loc,
+        public: bool,
+    ) -> Option<usize> {
+        if self.tree.globals.contains_key(&path) {
+            self.duplicate_error(&path, loc);
+            return None;
+        }
+
+        let symbol = self.declare(path.clone(), kind, loc, public);
+        self.tree.globals.insert(path, symbol);
+
+        Some(symbol)
+    }
+
+    // later variables shadow earlier ones with the same name
+    fn declare_local(&mut self, name: &str, kind: SymbolKind, loc: &Loc) -> usize {
+        self.declare(name.to_owned(), kind, loc, false)
+    }
+
+    fn declare_unique(&mut self, name: &str, kind: SymbolKind, loc: &Loc) {
+        let existing = self.tree.scopes[self.scope].names.get(name);
+        if existing.is_some_and(|&symbol| self.tree.symbols[symbol].kind == kind) {
+            self.duplicate_error(name, loc);
+            return;
+        }
+
+        self.declare_local(name, kind, loc);
+    }
+
+    fn declare(&mut self, name: String, kind: SymbolKind, loc: &Loc, public: bool) -> usize {
+        self.tree.declare(Symbol {
+            name,
+            kind,
+            scope: self.scope,
+            source: self.source,
+            loc: loc.clone(),
+            public,
+        })
+    }
+
+    fn check_fields(&mut self, fields: &[FieldNode]) {
+        self.check_unique(fields.iter().map(|field| (field.name.as_str(), &field.loc)));
+    }
+
+    fn check_unique<'n>(&mut self, names: impl Iterator<Item = (&'n str, &'n Loc)>) {
+        let mut seen = Vec::new();
+
+        for (name, loc) in names {
+            if seen.contains(&name) {
+                self.duplicate_error(name, loc);
+                continue;
+            }
+
+            seen.push(name);
+        }
+    }
+
+    fn undefined_error(&mut self, path: &str, loc: &Loc) {
+        let err = ResolutionError::default()
+            .kind(ResolutionErrorKind::Undefined)
+            .msg(format!("`{path}` is not defined"));
+        self.push_error(err, loc);
+    }
+
+    fn not_a_value_error(&mut self, msg: String, loc: &Loc) {
+        let err = ResolutionError::default()
+            .kind(ResolutionErrorKind::NotAValue)
+            .msg(msg);
+        self.push_error(err, loc);
+    }
+
+    fn duplicate_error(&mut self, name: &str, loc: &Loc) {
+        let err = ResolutionError::default()
+            .kind(ResolutionErrorKind::Duplicate)
+            .msg(format!("`{name}` is defined multiple times"));
+        self.push_error(err, loc);
+    }
+
+    fn private_error(&mut self, symbol: usize, loc: &Loc) {
+        let symbol = &self.tree.symbols[symbol];
+        let owner = match self.tree.namespace_path(symbol.scope) {
+            "" => "the root namespace",
+            owner => owner,
+        };
+
+        let err = ResolutionError::default()
+            .kind(ResolutionErrorKind::Private)
+            .msg(format!("`{}` is private to `{owner}`", symbol.name));
+        self.push_error(err, loc);
+    }
+
+    fn push_error(&mut self, err: ResolutionError, loc: &Loc) {
+        let src = &self.sources[self.source];
+        let ctx = ErrorContextBuilder::loc(loc)
+            .from_src_and_ln(src, loc.ln)
+            .build();
+
+        self.errors.push(err.ctx(ctx));
+    }
+}
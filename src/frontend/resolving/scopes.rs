@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use crate::frontend::{parsing::ast::items::UseImport, Loc};
+
+// every scope of the project and every name declared in them. items live in
+// `globals` under their full path too, since namespaces reach each other's
+// items through paths rather than through the tree
+#[derive(Default)]
+pub struct ScopeTree {
+    pub scopes: Vec<Scope>,
+    pub symbols: Vec<Symbol>,
+    pub globals: HashMap<String, usize>,
+    // the symbol every resolved name refers to, by source and location
+    pub uses: HashMap<(usize, Loc), usize>,
+    // the symbol every declared name introduces, by source and location
+    pub declarations: HashMap<(usize, Loc), usize>,
+    // the scope holding the variants and methods of a type, by the type's symbol
+    pub members: HashMap<usize, usize>,
+    // the statics, functions and methods every static and function names
+    // directly, by the symbol of the one naming them
+    pub references: HashMap<usize, Vec<usize>>,
+}
+
+pub struct Scope {
+    pub kind: ScopeKind,
+    pub parent: Option<usize>,
+    pub names: HashMap<String, usize>,
+}
+
+pub enum ScopeKind {
+    Namespace {
+        path: String,
+        imports: Vec<UseImport>,
+    },
+    // structs, enums, traits and impls, holding type parameters
+    Item,
+    Function,
+    Body,
+}
+
+pub struct Symbol {
+    // items have their full path, everything else the name it's written with
+    pub name: String,
+    pub kind: SymbolKind,
+    pub scope: usize,
+    pub source: usize,
+    pub loc: Loc,
+    pub public: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SymbolKind {
+    Constant,
+    Static { mutable: bool },
+    Function,
+    Method,
+    Struct,
+    Enum,
+    Variant,
+    Trait,
+    TypeParam,
+    Argument,
+    Variable { mutable: bool },
+    Binding,
+}
+
+impl SymbolKind {
+    // nested functions don't capture the locals around them, only the
+    // functions and type parameters
+    fn is_captured(&self) -> bool {
+        matches!(self, SymbolKind::Function | SymbolKind::TypeParam)
+    }
+}
+
+impl ScopeTree {
+    pub fn push(&mut self, kind: ScopeKind, parent: Option<usize>) -> usize {
+        self.scopes.push(Scope {
+            kind,
+            parent,
+            names: HashMap::new(),
+        });
+
+        return self.scopes.len() - 1;
+    }
+
+    // scopes know their symbols by the name they're written with
+    pub fn declare(&mut self, symbol: Symbol) -> usize {
+        let idx = self.symbols.len();
+        let name = match symbol.name.rsplit_once("::") {
+            Some((_, name)) => name,
+            None => &symbol.name,
+        };
+
+        self.scopes[symbol.scope].names.insert(name.to_owned(), idx);
+        self.declarations
+            .insert((symbol.source, symbol.loc.clone()), idx);
+        self.symbols.push(symbol);
+
+        return idx;
+    }
+
+    pub fn members(&mut self, symbol: usize) -> usize {
+        if let Some(&scope) = self.members.get(&symbol) {
+            return scope;
+        }
+
+        let parent = self.symbols[symbol].scope;
+        let scope = self.push(ScopeKind::Item, Some(parent));
+        self.members.insert(symbol, scope);
+
+        return scope;
+    }
+
+    // the namespace scope `scope` is in
+    pub fn namespace(&self, mut scope: usize) -> usize {
+        while let Some(parent) = self.scopes[scope].parent
+            && !matches!(self.scopes[scope].kind, ScopeKind::Namespace { .. })
+        {
+            scope = parent;
+        }
+
+        return scope;
+    }
+
+    pub fn namespace_path(&self, scope: usize) -> &str {
+        match &self.scopes[self.namespace(scope)].kind {
+            ScopeKind::Namespace { path, .. } => path,
+            _ => "",
+        }
+    }
+
+    // locals shadow items, and items are looked up the way `candidates`
+    // orders them. a private item that can't be reached is still returned
+    // when nothing else matches, its use is reported later on
+    pub fn lookup(&self, scope: usize, name: &str) -> Option<usize> {
+        let mut current = Some(scope);
+        let mut crossed_function = false;
+
+        while let Some(idx) = current {
+            let scope = &self.scopes[idx];
+            if let ScopeKind::Namespace { .. } = scope.kind {
+                break;
+            }
+
+            if let Some(&symbol) = scope.names.get(name)
+                && (!crossed_function || self.symbols[symbol].kind.is_captured())
+            {
+                return Some(symbol);
+            }
+
+            if let ScopeKind::Function = scope.kind {
+                crossed_function = true;
+            }
+
+            current = scope.parent;
+        }
+
+        let namespace = self.namespace_path(scope);
+        let found: Vec<usize> = self
+            .candidates(scope, name)
+            .iter()
+            .filter_map(|candidate| self.globals.get(candidate).copied())
+            .collect();
+
+        found
+            .iter()
+            .find(|&&symbol| self.can_access(namespace, symbol))
+            .or(found.first())
+            .copied()
+    }
+
+    pub fn candidates(&self, scope: usize, name: &str) -> Vec<String> {
+        match &self.scopes[self.namespace(scope)].kind {
+            ScopeKind::Namespace { path, imports } => candidates(path, imports, name),
+            _ => vec![name.to_owned()],
+        }
+    }
+
+    // the full path of the item the name at `loc` refers to, names of
+    // locals have none
+    pub fn item(&self, source: usize, loc: &Loc) -> Option<&str> {
+        let &symbol = self.uses.get(&(source, loc.clone()))?;
+        let name = &self.symbols[symbol].name;
+
+        (self.globals.get(name) == Some(&symbol)).then_some(name.as_str())
+    }
+
+    pub fn can_access(&self, namespace: &str, symbol: usize) -> bool {
+        let symbol = &self.symbols[symbol];
+        symbol.public || is_inside(namespace, self.namespace_path(symbol.scope))
+    }
+}
+
+// every full path a name written inside `namespace` can refer to, in the
+// order they shadow each other
+pub fn candidates(namespace: &str, imports: &[UseImport], name: &str) -> Vec<String> {
+    let (first, rest) = match name.split_once("::") {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (name, None),
+    };
+
+    let mut candidates = vec![qualify(namespace, name)];
+
+    for import in imports.iter().filter(|import| !import.glob) {
+        if import.name() != first {
+            continue;
+        }
+
+        let target = import.path.join("::");
+        candidates.push(match rest {
+            Some(rest) => format!("{target}::{rest}"),
+            None => target,
+        });
+    }
+
+    for import in imports.iter().filter(|import| import.glob) {
+        candidates.push(qualify(&import.path.join("::"), name));
+    }
+
+    let mut ancestor = namespace;
+    while !ancestor.is_empty() {
+        ancestor = ancestor.rsplit_once("::").map_or("", |(parent, _)| parent);
+        candidates.push(qualify(ancestor, name));
+    }
+
+    return candidates;
+}
+
+pub fn qualify(namespace: &str, name: &str) -> String {
+    if namespace.is_empty() {
+        return name.to_owned();
+    }
+
+    format!("{namespace}::{name}")
+}
+
+// private items can only be used from the namespace declaring them and the
+// namespaces inside it
+pub fn is_inside(namespace: &str, owner: &str) -> bool {
+    owner.is_empty() || namespace == owner || namespace.starts_with(&format!("{owner}::"))
+}
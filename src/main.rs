@@ -1,4 +1,4 @@
-#![feature(let_chains)]
+pub mod backend;
 #[allow(unused)]
 pub mod errors;
 pub mod frontend;
@@ -7,31 +7,52 @@ pub mod utils;
 
 use std::time::Instant;
 
+use backend::{codegen::Codegen, jit, object};
+use cranelift_module::{FuncId, Module};
 use errors::ErrorList;
 use frontend::{
+    checking::{LiteralTypes, MutabilityChecker, SharedValues, TypeChecker},
+    evaluating::{ConstValue, Evaluator},
     lexing::Token,
-    parsing::{ast::projects::ProjectNode, Parser},
+    modules,
+    parsing::{
+        ast::{
+            namespaces::{NamespaceNode, NamespacePath},
+            projects::ProjectNode,
+        },
+        Parser,
+    },
+    resolving::{Resolver, ScopeTree},
 };
 
 use crate::{
-    errors::command_line::{CommandLineError, CommandLineErrorKind},
+    errors::{
+        command_line::{CommandLineError, CommandLineErrorKind},
+        context::ErrorContextBuilder,
+        module::{ModuleError, ModuleErrorKind},
+        syntax::SyntaxError,
+    },
     frontend::lexing::Lexer,
-    structs::{MeowindArguments, ScriptSource},
+    structs::{MeowindArguments, MeowindCommand, ScriptSource},
     utils::colors::*,
 };
-use std::{env, fs, io::ErrorKind, path::PathBuf, process};
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process,
+};
 
 fn main() {
-    env::set_var("RUST_BACKTRACE", "1");
+    // SAFETY: no other threads are running yet
+    unsafe { env::set_var("RUST_BACKTRACE", "1") };
 
     #[cfg(windows)]
     init_windows_colors();
 
     let args = parse_arguments();
 
-    let source_contents = read_source_contents(&args.path);
-    let source = ScriptSource::new(args.path.clone(), &source_contents);
-
     println!(
         "{GREEN}{BOLD}compiling{WHITE} {}{RESET}",
         args.path.display()
@@ -39,31 +60,72 @@ fn main() {
 
     let comp_start = Instant::now();
 
-    let tokens = run_lexer(source.clone());
+    let (files, ast) = load_project(&args.path);
+    let sources: Vec<ScriptSource> = files
+        .iter()
+        .map(|(path, contents)| ScriptSource::new(path.clone(), contents))
+        .collect();
+
+    let tree = run_resolver(&ast, &sources);
+    let (literals, shared) = run_checker(&ast, &tree, &sources);
+    run_mutability_checker(&ast, &tree, &sources, &shared);
+    let (constants, statics) = run_evaluator(&ast, &tree, &sources, &literals);
+
+    if args.command == MeowindCommand::Build {
+        let output = args.output.unwrap();
+        let name = output.file_stem().unwrap().to_string_lossy();
+
+        let (module, _) = run_codegen(
+            object::new_module(&name),
+            &ast,
+            &tree,
+            &sources,
+            &literals,
+            &constants,
+            &statics,
+        );
+        let object_path = object::emit(module, &output);
+        object::link(&object_path, &output);
+
+        print_compiled(&args.path, comp_start);
+        println!("{GREEN}{BOLD}built{WHITE} {}{RESET}", output.display());
+        return;
+    }
 
-    #[allow(unused)]
-    let ast = run_parser(&tokens, source.clone());
+    let (module, entry) = run_codegen(
+        jit::new_module(),
+        &ast,
+        &tree,
+        &sources,
+        &literals,
+        &constants,
+        &statics,
+    );
+
+    print_compiled(&args.path, comp_start);
+    println!("{GREEN}{BOLD}running{WHITE} {}{RESET}", args.path.display());
 
+    let exit_code = jit::execute(module, entry);
+    process::exit(exit_code);
+}
+
+fn print_compiled(path: &Path, comp_start: Instant) {
     let comp_micros = comp_start.elapsed().as_micros();
     let comp_millis = comp_start.elapsed().as_millis();
 
     println!(
         "{GREEN}{BOLD}successfully compiled{WHITE} {} {GREEN}in{WHITE} {}us {GREEN}or{WHITE} {}ms{RESET}",
-        args.path.display(),
+        path.display(),
         comp_micros,
         comp_millis
     );
-
-    process::exit(0);
 }
 
-fn run_lexer(source: ScriptSource) -> Vec<Token> {
+fn run_lexer(source: ScriptSource) -> (Vec<Token>, Vec<Token>, Vec<SyntaxError>) {
     #[cfg(debug_assertions)]
     let lexer_start = Instant::now();
     let lexer = Lexer::tokenize(source);
 
-    lexer.errors.throw_if_there();
-
     #[cfg(debug_assertions)]
     let lexer_micros = lexer_start.elapsed().as_micros();
     #[cfg(debug_assertions)]
@@ -84,15 +146,129 @@ fn run_lexer(source: ScriptSource) -> Vec<Token> {
         lexer_millis
     );
 
-    return lexer.tokens;
+    return (lexer.tokens, lexer.docs, lexer.errors);
+}
+
+// loads the main file and every file reachable through `use` items, each
+// one becomes a namespace of the project named after its path
+fn load_project(path: &Path) -> (Vec<(PathBuf, String)>, ProjectNode) {
+    let root = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut files = vec![(
+        path.to_path_buf(),
+        read_source_contents(&path.to_path_buf()),
+    )];
+    let mut names = vec![Vec::new()];
+    let mut canonical = vec![fs::canonicalize(path).ok()];
+
+    let mut project = ProjectNode::default();
+    let mut syntax_errors = Vec::new();
+    let mut module_errors = Vec::new();
+    let mut unresolved = Vec::new();
+
+    let mut idx = 0;
+    while idx < files.len() {
+        let source = ScriptSource::new(files[idx].0.clone(), &files[idx].1);
+
+        let (tokens, docs, lexer_errors) = run_lexer(source.clone());
+        let (mut node, parser_errors) = run_parser(&tokens, &docs, source.clone());
+
+        let mut errors = lexer_errors;
+        errors.extend(parser_errors);
+        errors.sort_by_key(|err| err.ln());
+        syntax_errors.extend(errors);
+
+        let mut found = Vec::new();
+        for import in node.all_imports() {
+            if import.path[0] == modules::STD_NAMESPACE {
+                continue;
+            }
+
+            let Some((module, file)) = modules::resolve_module(root, &import.path) else {
+                unresolved.push((idx, import));
+                continue;
+            };
+
+            let file_canonical = fs::canonicalize(&file).ok();
+            if canonical.contains(&file_canonical)
+                || found.iter().any(|(_, _, other)| *other == file_canonical)
+            {
+                continue;
+            }
+
+            match fs::read_to_string(&file) {
+                Ok(contents) => found.push((module, (file, contents), file_canonical)),
+                Err(err) => module_errors.push(
+                    ModuleError::default()
+                        .kind(ModuleErrorKind::FailedToRead)
+                        .msg(format!("\"{}\": {err}", file.display()))
+                        .ctx(
+                            ErrorContextBuilder::loc(&import.loc)
+                                .from_src_and_ln(&source, import.loc.ln)
+                                .build(),
+                        ),
+                ),
+            }
+        }
+
+        if idx == 0 {
+            project.root = node;
+        } else {
+            node.nest_in(&NamespacePath::new(names[idx].clone()), idx);
+            project.root.children.push(node);
+        }
+
+        for (module, file, file_canonical) in found {
+            names.push(module);
+            files.push(file);
+            canonical.push(file_canonical);
+        }
+
+        idx += 1;
+    }
+
+    // imports of namespaces declared inside files don't need a file of their own
+    for (idx, import) in unresolved {
+        if project.root.declares(&import.path[0]) {
+            continue;
+        }
+
+        let source = ScriptSource::new(files[idx].0.clone(), &files[idx].1);
+        module_errors.push(
+            ModuleError::default()
+                .kind(ModuleErrorKind::NotFound)
+                .msg(format!(
+                    "no file found for `{}` in \"{}\"",
+                    import.path.join("::"),
+                    fs::canonicalize(root)
+                        .unwrap_or(root.to_path_buf())
+                        .display()
+                ))
+                .ctx(
+                    ErrorContextBuilder::loc(&import.loc)
+                        .from_src_and_ln(&source, import.loc.ln)
+                        .build(),
+                ),
+        );
+    }
+
+    syntax_errors.throw_if_there();
+    module_errors.throw_if_there();
+
+    return (files, project);
 }
 
-fn run_parser(tokens: &Vec<Token>, source: ScriptSource) -> ProjectNode {
+fn run_parser(
+    tokens: &Vec<Token>,
+    docs: &[Token],
+    source: ScriptSource,
+) -> (NamespaceNode, Vec<SyntaxError>) {
     #[cfg(debug_assertions)]
     let parser_start = Instant::now();
-    let parser = Parser::parse(tokens, source);
-
-    parser.errors.throw_if_there();
+    let parser = Parser::parse(tokens, docs, source);
 
     #[cfg(debug_assertions)]
     let parser_micros = parser_start.elapsed().as_micros();
@@ -106,28 +282,202 @@ fn run_parser(tokens: &Vec<Token>, source: ScriptSource) -> ProjectNode {
         parser_millis
     );
 
-    return parser.project;
+    return (parser.project.root, parser.errors);
+}
+
+fn run_resolver(ast: &ProjectNode, sources: &[ScriptSource]) -> ScopeTree {
+    #[cfg(debug_assertions)]
+    let resolver_start = Instant::now();
+    let resolver = Resolver::resolve(ast, sources);
+
+    resolver.errors.throw_if_there();
+
+    #[cfg(debug_assertions)]
+    let resolver_micros = resolver_start.elapsed().as_micros();
+    #[cfg(debug_assertions)]
+    let resolver_millis = resolver_start.elapsed().as_millis();
+
+    log!(
+        "resolver output: {} symbols in {} scopes, {} names resolved\nresolver finished in: {}us or {}ms",
+        resolver.tree.symbols.len(),
+        resolver.tree.scopes.len(),
+        resolver.tree.uses.len(),
+        resolver_micros,
+        resolver_millis
+    );
+
+    return resolver.tree;
+}
+
+fn run_checker(
+    ast: &ProjectNode,
+    tree: &ScopeTree,
+    sources: &[ScriptSource],
+) -> (LiteralTypes, SharedValues) {
+    #[cfg(debug_assertions)]
+    let checker_start = Instant::now();
+    let checker = TypeChecker::check(ast, tree, sources);
+
+    checker.errors.throw_if_there();
+
+    #[cfg(debug_assertions)]
+    let checker_micros = checker_start.elapsed().as_micros();
+    #[cfg(debug_assertions)]
+    let checker_millis = checker_start.elapsed().as_millis();
+
+    log!(
+        "type checker finished in: {}us or {}ms",
+        checker_micros,
+        checker_millis
+    );
+
+    return (checker.literals, checker.shared);
+}
+
+fn run_mutability_checker(
+    ast: &ProjectNode,
+    tree: &ScopeTree,
+    sources: &[ScriptSource],
+    shared: &SharedValues,
+) {
+    #[cfg(debug_assertions)]
+    let checker_start = Instant::now();
+    let checker = MutabilityChecker::check(ast, tree, sources, shared);
+
+    checker.errors.throw_if_there();
+
+    #[cfg(debug_assertions)]
+    let checker_micros = checker_start.elapsed().as_micros();
+    #[cfg(debug_assertions)]
+    let checker_millis = checker_start.elapsed().as_millis();
+
+    log!(
+        "mutability checker finished in: {}us or {}ms",
+        checker_micros,
+        checker_millis
+    );
+}
+
+fn run_evaluator(
+    ast: &ProjectNode,
+    tree: &ScopeTree,
+    sources: &[ScriptSource],
+    literals: &LiteralTypes,
+) -> (HashMap<String, ConstValue>, Vec<String>) {
+    #[cfg(debug_assertions)]
+    let evaluator_start = Instant::now();
+    let evaluator = Evaluator::evaluate(ast, tree, sources, literals);
+
+    evaluator.errors.throw_if_there();
+
+    #[cfg(debug_assertions)]
+    let evaluator_micros = evaluator_start.elapsed().as_micros();
+    #[cfg(debug_assertions)]
+    let evaluator_millis = evaluator_start.elapsed().as_millis();
+
+    log!(
+        "evaluator finished in: {}us or {}ms",
+        evaluator_micros,
+        evaluator_millis
+    );
+
+    return (evaluator.values, evaluator.statics);
+}
+
+fn run_codegen<M: Module>(
+    module: M,
+    ast: &ProjectNode,
+    tree: &ScopeTree,
+    sources: &[ScriptSource],
+    literals: &LiteralTypes,
+    constants: &HashMap<String, ConstValue>,
+    statics: &[String],
+) -> (M, FuncId) {
+    #[cfg(debug_assertions)]
+    let codegen_start = Instant::now();
+    let codegen = Codegen::generate(module, ast, tree, sources, literals, constants, statics);
+
+    codegen.errors.throw_if_there();
+
+    #[cfg(debug_assertions)]
+    let codegen_micros = codegen_start.elapsed().as_micros();
+    #[cfg(debug_assertions)]
+    let codegen_millis = codegen_start.elapsed().as_millis();
+
+    log!(
+        "codegen output:\n{}\ncodegen finished in: {}us or {}ms",
+        codegen.ir.join("\n"),
+        codegen_micros,
+        codegen_millis
+    );
+
+    return (codegen.module, codegen.entry.unwrap());
 }
 
 fn parse_arguments() -> MeowindArguments {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().skip(1).collect();
+
+    let command = match args.first().map(String::as_str) {
+        Some("build") => MeowindCommand::Build,
+        Some("run") => MeowindCommand::Run,
+        _ => {
+            args.insert(0, String::from("run"));
+            MeowindCommand::Run
+        }
+    };
+    args.remove(0);
 
-    if args.len() < 2 {
+    if args.is_empty() {
         errors::throw(CommandLineError::new(
             CommandLineErrorKind::InvalidArguments,
             "path not provided",
         ));
     }
 
-    if args.len() > 2 {
+    let path = PathBuf::from(args.remove(0));
+
+    let output = if command == MeowindCommand::Build {
+        if matches!(args.first().map(String::as_str), Some("-o" | "--output")) {
+            args.remove(0);
+
+            if args.is_empty() {
+                errors::throw(CommandLineError::new(
+                    CommandLineErrorKind::InvalidArguments,
+                    "output path not provided",
+                ));
+            }
+
+            Some(PathBuf::from(args.remove(0)))
+        } else {
+            let mut output = PathBuf::from(path.file_stem().unwrap_or_default());
+            output.set_extension(env::consts::EXE_EXTENSION);
+            Some(output)
+        }
+    } else {
+        None
+    };
+
+    if !args.is_empty() {
+        errors::throw(CommandLineError::new(
+            CommandLineErrorKind::InvalidArguments,
+            format!("unexpected argument `{}`", args[0]),
+        ));
+    }
+
+    // the object file and the executable are named after the output's file
+    if let Some(output) = &output
+        && (output.file_stem().is_none() || output.is_dir())
+    {
         errors::throw(CommandLineError::new(
             CommandLineErrorKind::InvalidArguments,
-            format!("expected 1 argument, got {}", args.len() - 1),
+            format!("output path \"{}\" is not a file", output.display()),
         ));
     }
 
     MeowindArguments {
-        path: PathBuf::from(&args[1]),
+        command,
+        path,
+        output,
     }
 }
 
@@ -33,5 +33,13 @@ impl Clone for ScriptSource<'_> {
 }
 
 pub struct MeowindArguments {
+    pub command: MeowindCommand,
     pub path: PathBuf,
+    pub output: Option<PathBuf>,
+}
+
+#[derive(PartialEq)]
+pub enum MeowindCommand {
+    Run,
+    Build,
 }
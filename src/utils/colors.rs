@@ -1,3 +1,4 @@
+#[cfg(windows)]
 use winapi_util::console::Console;
 
 pub const RED: &str = "\x1b[91m";
@@ -0,0 +1,16 @@
+// "2 arguments", "1 argument"
+pub fn counted(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {noun}"),
+        _ => format!("{count} {noun}s"),
+    }
+}
+
+// "`f` takes 2 arguments, but 1 was given"
+pub fn count_mismatch(path: &str, expected: usize, noun: &str, given: usize) -> String {
+    let were = if given == 1 { "was" } else { "were" };
+    format!(
+        "`{path}` takes {}, but {given} {were} given",
+        counted(expected, noun)
+    )
+}